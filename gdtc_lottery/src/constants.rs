@@ -10,4 +10,13 @@ pub static LPTOKEN_SEED: &[u8] = b"lp_token";
 pub static STAKING_SEED: &[u8] = b"staking_instance";
 pub static USER_SEED: &[u8] = b"user_deposit";
 
+// 账户布局版本：新增字段从末尾的保留字节中划出，递增版本号并在 migrate_* 中补齐默认值
+// version 2: LotteryState / LotteryRound 新增 PDA bump 字段
+pub const LOTTERY_STATE_VERSION: u8 = 2;
+pub const LOTTERY_ROUND_VERSION: u8 = 2;
+pub const USER_LOTTERY_STATE_VERSION: u8 = 1;
+pub const LOTTERY_RESERVED_BYTES: usize = 64; // 各彩票账户最初的预留字节数
+pub const LOTTERY_STATE_RESERVED_BYTES: usize = LOTTERY_RESERVED_BYTES - 2; // 已用 2 字节存放 bump
+pub const LOTTERY_ROUND_RESERVED_BYTES: usize = LOTTERY_RESERVED_BYTES - 2; // 已用 2 字节存放 bump
+
 // pub static Stake_CA: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"; //质押合约
@@ -6,9 +6,19 @@ use constants::*;
 
 use structures::{
     error::ErrorCode, initialize_lottery::*, initialize_lottery_round::*, initialize_user::*,
-    participate::{*,Participate}, claim_reward::{*,ClaimReward},claim_lp::{*,ClaimLP},admin_claim_reward::{*,AdminClaimReward}
+    participate::{*,Participate}, claim_reward::{*,ClaimReward},claim_lp::{*,ClaimLP},admin_claim_reward::{*,AdminClaimReward},
+    migrate_accounts::*, close_user_lottery_state::*, set_authority::*,
 };
 
+/// 用抽奖状态中保存的 bump 直接计算全局 LP/奖励金库权限 PDA，只需一次 create_program_address
+pub fn lp_authority_address(lp_authority_bump: u8, program_id: &Pubkey) -> Result<Pubkey> {
+    Pubkey::create_program_address(
+        &[crate::LPTOKEN_SEED, &[lp_authority_bump]],
+        program_id,
+    )
+    .map_err(|_| ErrorCode::PdaAccountIsNotMatch.into())
+}
+
 // This is your program's public key and it will update
 // automatically when you build the project.
 declare_id!("LUnrZKoeBMjvNWJZJmdZ7oEMq8R8PjVveavrGnTwCxc");
@@ -19,48 +29,24 @@ pub mod gdtc_lottery {
 
     pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
         let program_id = ctx.program_id; // 获取当前合约的程序ID
-                                         // 计算 lottery_instance 的派生地址
-        let (expected_pda_address, _) =
-            Pubkey::find_program_address(&[crate::LOTTERY_SEED.as_ref()], program_id);
-
-        if expected_pda_address != ctx.accounts.lottery_state.key() {
-            return Err(ErrorCode::PdaAccountIsNotMatch.into());
-        }
-        ctx.accounts.process()
+        // lottery_state 的派生地址已由 init 约束校验；全局 LP 权限的 bump 只在此计算一次并保存
+        let (_, lp_authority_bump) =
+            Pubkey::find_program_address(&[crate::LPTOKEN_SEED], program_id);
+        ctx.accounts
+            .process(ctx.bumps.lottery_state, lp_authority_bump)
     }
 
     pub fn initialize_lottery_round(
         ctx: Context<InitializeLotteryRound>,
         round_number: u64, // 轮次号
     ) -> Result<()> {
-        let program_id = ctx.program_id; // 获取当前合约的程序ID // 计算 lottery_instance 的派生地址
-        let (expected_pda_address, _) = Pubkey::find_program_address(
-            &[
-                crate::LOTTERY_ROUND_SEED.as_ref(),
-                &round_number.to_le_bytes(),
-            ],
-            program_id,
-        );
-
-        if expected_pda_address != ctx.accounts.lottery_round.key() {
-            return Err(ErrorCode::PdaAccountIsNotMatch.into());
-        }
-       
-        
-
+        // lottery_round 与 pda_account 的派生地址已由账户约束校验，bump 保存到轮次账户中
         let bump_seed = ctx.bumps.pda_account;
-        ctx.accounts.process(round_number,bump_seed)
+        ctx.accounts.process(round_number,bump_seed,ctx.bumps.lottery_round)
     }
 
     pub fn initialize_user_lottery_state(ctx: Context<InitializeUserLotteryState>) -> Result<()> {
-        let program_id = ctx.program_id; // 获取当前合约的程序ID
-                                         // 计算 user_lottery_state 的派生地址
-        let (expected_pda_address, _) =
-            Pubkey::find_program_address(&[ctx.accounts.authority.key().as_ref()], program_id);
-
-        if expected_pda_address != ctx.accounts.user_lottery_state.key() {
-            return Err(ErrorCode::PdaAccountIsNotMatch.into());
-        }
+        // user_lottery_state 的派生地址已由 init 约束校验
         ctx.accounts.process() // 调用初始化函数
     }
 
@@ -70,26 +56,7 @@ pub mod gdtc_lottery {
         user_index: u64,
     ) -> Result<()> {
         let program_id = ctx.program_id; // 获取当前合约的程序ID
-        let (expected_lottery_state_pda_address, _) =
-            Pubkey::find_program_address(&[crate::LOTTERY_SEED.as_ref()], program_id);
-
-        let (expected_lottery_round_pda_address, _) = Pubkey::find_program_address(
-            &[
-                crate::LOTTERY_ROUND_SEED.as_ref(),
-                &round_number.to_le_bytes(),
-            ],
-            program_id,
-        );
-
-        let (expected_user_lottery_state_pda_address, _) =
-            Pubkey::find_program_address(&[ctx.accounts.authority.key().as_ref()], program_id);
-
-        if expected_user_lottery_state_pda_address != ctx.accounts.user_lottery_state.key()
-            && expected_lottery_round_pda_address != ctx.accounts.lottery_round.key()
-            && expected_lottery_state_pda_address != ctx.accounts.lottery_state.key()
-        {
-            return Err(ErrorCode::PdaAccountIsNotMatch.into());
-        }
+        // lottery_state、lottery_round、user_lottery_state 与 pda_account 的派生地址已由账户约束校验
 
         // 验证 gdtc_lp_in_account 的 mint 地址
         if ctx.accounts.gdtc_lp_in_account.mint != ctx.accounts.lottery_state.staking_token_mint
@@ -100,8 +67,8 @@ pub mod gdtc_lottery {
         }
 
         //验证lptoken的pda账户
-        let (expected_pda_address, _) =
-            Pubkey::find_program_address(&[crate::LPTOKEN_SEED.as_ref()], program_id);
+        let expected_pda_address =
+            lp_authority_address(ctx.accounts.lottery_state.lp_authority_bump, program_id)?;
 
         if expected_pda_address != ctx.accounts.gdtc_lp_in_account.owner.key() {
             return Err(ErrorCode::InvalidAccountOwner.into());
@@ -111,33 +78,13 @@ pub mod gdtc_lottery {
         if ctx.accounts.user_lp_token_account.owner != ctx.accounts.authority.key() {
             return Err(ErrorCode::InvalidAccountOwner.into());
         }
-        let bump_seed = ctx.bumps.pda_account;
+        let bump_seed = ctx.accounts.lottery_round.lp_authority_bump;
         ctx.accounts.process(bump_seed,user_index,round_number)
     }
 
     pub fn claim_reward(ctx: Context<ClaimReward>,round_number: u64,
         user_index: u64) -> Result<()> {
-        let program_id = ctx.program_id; // 获取当前合约的程序ID
-        let (expected_lottery_state_pda_address, _) =
-            Pubkey::find_program_address(&[crate::LOTTERY_SEED.as_ref()], program_id);
-
-        let (expected_lottery_round_pda_address, _) = Pubkey::find_program_address(
-            &[
-                crate::LOTTERY_ROUND_SEED.as_ref(),
-                &round_number.to_le_bytes(),
-            ],
-            program_id,
-        );
-
-        let (expected_user_lottery_state_pda_address, _) =
-            Pubkey::find_program_address(&[ctx.accounts.authority.key().as_ref()], program_id);
-
-        if expected_user_lottery_state_pda_address != ctx.accounts.user_lottery_state.key()
-            && expected_lottery_round_pda_address != ctx.accounts.lottery_round.key()
-            && expected_lottery_state_pda_address != ctx.accounts.lottery_state.key()
-        {
-            return Err(ErrorCode::PdaAccountIsNotMatch.into());
-        }
+        // lottery_state、lottery_round、user_lottery_state 与 pda_account 的派生地址已由账户约束校验
 
         // 验证  mint 地址
         if ctx.accounts.gdtc_reward_out_account.mint != ctx.accounts.lottery_state.reward_token_mint
@@ -151,14 +98,11 @@ pub mod gdtc_lottery {
             return Err(ErrorCode::InvalidAccountOwner.into());
         }
 
-         //验证lptoken的pda账户
-         let (expected_pda_address, _) =
-         Pubkey::find_program_address(&[crate::LPTOKEN_SEED.as_ref()], program_id);
-
-     if expected_pda_address != ctx.accounts.gdtc_reward_out_account.owner.key() {
+         //验证lptoken的pda账户，pda_account 即全局 LP 权限 PDA
+     if ctx.accounts.pda_account.key() != ctx.accounts.gdtc_reward_out_account.owner.key() {
          return Err(ErrorCode::InvalidAccountOwner.into());
      }
-        let bump_seed = ctx.bumps.pda_account;
+        let bump_seed = ctx.accounts.lottery_state.lp_authority_bump;
         ctx.accounts.process(bump_seed,user_index)
     }
 
@@ -168,26 +112,7 @@ pub mod gdtc_lottery {
 
             let program_id = ctx.program_id; // 获取当前合约的程序ID
 
-        let (expected_lottery_state_pda_address, _) =
-            Pubkey::find_program_address(&[crate::LOTTERY_SEED.as_ref()], program_id);
-
-        let (expected_lottery_round_pda_address, _) = Pubkey::find_program_address(
-            &[
-                crate::LOTTERY_ROUND_SEED.as_ref(),
-                &round_number.to_le_bytes(),
-            ],
-            program_id,
-        );
-
-        let (expected_user_lottery_state_pda_address, _) =
-            Pubkey::find_program_address(&[ctx.accounts.authority.key().as_ref()], program_id);
-
-        if expected_user_lottery_state_pda_address != ctx.accounts.user_lottery_state.key()
-            && expected_lottery_round_pda_address != ctx.accounts.lottery_round.key()
-            && expected_lottery_state_pda_address != ctx.accounts.lottery_state.key()
-        {
-            return Err(ErrorCode::PdaAccountIsNotMatch.into());
-        }
+        // lottery_state、lottery_round、user_lottery_state 与 pda_account 的派生地址已由账户约束校验
 
         // 验证  mint 地址
         if ctx.accounts.user_lp_token_account.mint != ctx.accounts.lottery_state.staking_token_mint
@@ -204,14 +129,14 @@ pub mod gdtc_lottery {
         }
 
          //验证lptoken的pda账户
-         let (expected_pda_address, _) =
-         Pubkey::find_program_address(&[crate::LPTOKEN_SEED.as_ref()], program_id);
+         let expected_pda_address =
+         lp_authority_address(ctx.accounts.lottery_state.lp_authority_bump, program_id)?;
 
      if expected_pda_address != ctx.accounts.gdtc_lp_in_account.owner.key() {
          return Err(ErrorCode::InvalidAccountOwner.into());
      }
 
-        let bump_seed = ctx.bumps.pda_account;
+        let bump_seed = ctx.accounts.lottery_round.lp_authority_bump;
         ctx.accounts.process(bump_seed,user_index,round_number)
     }
 
@@ -223,5 +148,33 @@ pub mod gdtc_lottery {
         
         ctx.accounts.process()
     }
+
+    // 关闭用户抽奖状态账户并退还租金，之后可重新 initialize_user_lottery_state
+    pub fn close_user_lottery_state(ctx: Context<CloseUserLotteryState>) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    // 把管理权限交给质押合约中多签管理员集合的 admin_authority PDA，之后只能通过时间锁提案修改
+    pub fn set_authority(ctx: Context<SetLotteryAuthority>) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    // 以下迁移指令把旧布局的账户扩容并升级到当前版本，用户无需重新初始化
+    pub fn migrate_lottery_state(ctx: Context<MigrateLotteryState>) -> Result<()> {
+        ctx.accounts
+            .process(ctx.program_id, ctx.bumps.lottery_state)
+    }
+
+    pub fn migrate_lottery_round(
+        ctx: Context<MigrateLotteryRound>,
+        round_number: u64, // 轮次号，用于校验轮次账户的派生地址并补齐本轮 LP 权限 PDA 的 bump
+    ) -> Result<()> {
+        ctx.accounts
+            .process(ctx.program_id, round_number, ctx.bumps.lottery_round)
+    }
+
+    pub fn migrate_user_lottery_state(ctx: Context<MigrateUserLotteryState>) -> Result<()> {
+        ctx.accounts.process(ctx.program_id)
+    }
 }
 
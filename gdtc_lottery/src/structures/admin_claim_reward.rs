@@ -1,7 +1,6 @@
 use super::{LotteryRound, LotteryState, UserLotteryState};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount,transfer};
-use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::token_interface::{TokenAccount, TokenInterface};
 
 
 #[derive(Accounts)]
@@ -11,28 +10,29 @@ pub struct AdminClaimReward<'info> {
     /// CHECK:` doc comment explaining why no checks through types are necessary.
     #[account(mut,
         seeds = [crate::LOTTERY_SEED.as_ref()],
-            bump)]
+            bump = lottery_state.bump,
+        has_one = authority @ ErrorCode::Unauthorized)]
     pub lottery_state: Account<'info, LotteryState>, // 抽奖状态账户
     #[account(mut)]
-    pub authority: Signer<'info>, //签名用户
+    pub authority: Signer<'info>, // 抽奖管理员，移交后为管理员集合的 admin_authority PDA
     /// CHECK:` doc comment explaining why no checks through types are necessary.
     #[account(mut,
     seeds = [crate::LOTTERY_ROUND_SEED.as_ref(),&round_number.to_le_bytes()], // 动态轮次号,
-        bump)]
+        bump = lottery_round.bump)]
     pub lottery_round: Account<'info, LotteryRound>,
     #[account(mut)]
-    pub admin_gdtc_token_account: Account<'info, TokenAccount>, // 用户gdtc token账户
+    pub admin_gdtc_token_account: InterfaceAccount<'info, TokenAccount>, // 用户gdtc token账户
     #[account(mut)]
-    pub gdtc_reward_out_account: Account<'info, TokenAccount>, //合约转出gdtc 的token账户
+    pub gdtc_reward_out_account: InterfaceAccount<'info, TokenAccount>, //合约转出gdtc 的token账户
     /// CHECK:` doc comment explaining why no checks through types are necessary.
     #[account(
         mut,
         seeds = [crate::LPTOKEN_SEED.as_ref()], 
-        bump,
+        bump = lottery_state.lp_authority_bump,
     )]
     pub pda_account: AccountInfo<'info>, //合约pda账户
     pub system_program: Program<'info, System>, //系统账户 programid
-    pub token_program: Program<'info, Token>,   //token账户 可从sdk里导入
+    pub token_program: Interface<'info, TokenInterface>,   //token账户 可从sdk里导入
 }
 
 impl<'info> AdminClaimReward<'info> {
@@ -58,4 +58,7 @@ pub enum ErrorCode {
 
     #[msg("User index is not match.")]
     UserIndexIsNotMatch,
+
+    #[msg("Signer is not the lottery authority.")]
+    Unauthorized,
 }
@@ -1,7 +1,8 @@
 use super::{LotteryRound, LotteryState, UserLotteryState};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount,transfer};
-use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
 
 use gdtc_stake::structures::{StakingInstance,User};
 use gdtc_stake::program::GdtcStaking;
@@ -14,12 +15,12 @@ pub struct ClaimLP<'info> {
     /// CHECK:` doc comment explaining why no checks through types are necessary.
     #[account(mut,
         seeds = [crate::LOTTERY_SEED.as_ref()],
-            bump)]
+            bump = lottery_state.bump)]
         pub lottery_state: Account<'info, LotteryState>, // 抽奖状态账户
         /// CHECK:` doc comment explaining why no checks through types are necessary.
         #[account(mut,
         seeds = [crate::LOTTERY_ROUND_SEED.as_ref(),&round_number.to_le_bytes()], // 动态轮次号,
-        bump)]
+        bump = lottery_round.bump)]
         pub lottery_round: Account<'info, LotteryRound>,
         /// CHECK:` doc comment explaining why no checks through types are necessary.
         #[account(mut, seeds = [authority.key().as_ref()],bump)]
@@ -27,9 +28,10 @@ pub struct ClaimLP<'info> {
         #[account(mut)]
         pub authority: Signer<'info>, //签名账户
         #[account(mut)]
-        pub user_lp_token_account: Account<'info, TokenAccount>,
+        pub user_lp_token_account: InterfaceAccount<'info, TokenAccount>,
         #[account(mut)]
-        pub gdtc_lp_in_account: Account<'info, TokenAccount>,
+        pub gdtc_lp_in_account: InterfaceAccount<'info, TokenAccount>,
+        pub staking_token_mint: InterfaceAccount<'info, Mint>, // 质押代币 Mint (LP)，transfer_checked 需要
        
         pub system_program: Program<'info, System>, // 系统程序
         
@@ -39,19 +41,19 @@ pub struct ClaimLP<'info> {
         #[account(mut)]
         pub user_instance: Account<'info, User>, //彩票合约指定期数的用户实例
         #[account(mut)] 
-        pub gdtc_stake_lp_in_account: Account<'info, TokenAccount>,
+        pub gdtc_stake_lp_in_account: InterfaceAccount<'info, TokenAccount>,
         /// CHECK:` doc comment explaining why no checks through types are necessary.
         #[account(
             mut,
             seeds = [crate::LPTOKEN_SEED.as_ref(),&round_number.to_le_bytes()], 
-            bump,
+            bump = lottery_round.lp_authority_bump,
         )]
         pub pda_account: AccountInfo<'info>, //合约pda账户
         /// CHECK:` doc comment explaining why no checks through types are necessary.
         #[account(mut)]
         pub stake_pda_account:AccountInfo<'info>,
         pub staking_program: Program<'info, GdtcStaking>, // 质押合约
-        pub token_program: Program<'info, Token>,
+        pub token_program: Interface<'info, TokenInterface>,
 }
 
 impl<'info> ClaimLP<'info> {
@@ -108,6 +110,7 @@ impl<'info> ClaimLP<'info> {
             user_instance:self.user_instance.to_account_info(),
             user_lp_token_account:self.gdtc_lp_in_account.to_account_info(),
             gdtc_lp_in_account:self.gdtc_stake_lp_in_account.to_account_info(),
+            staking_token_mint:self.staking_token_mint.to_account_info(),
             pda_account:self.stake_pda_account.to_account_info(),
             system_program:self.system_program.to_account_info(),
             token_program:self.token_program.to_account_info(),
@@ -138,27 +141,18 @@ impl<'info> ClaimLP<'info> {
      // 获取 PDA 签名者
      let signer_seeds: &[&[&[u8]]] = &[&[crate::LPTOKEN_SEED.as_ref(), &[bump_seed]]];
 
-     // 生成从 GDTC 托管账户到用户 LP Token 账户的转账指令
-     let transfer_instruction = spl_token::instruction::transfer(
-         &self.token_program.key(),
-         &self.gdtc_lp_in_account.key(),
-         &self.user_lp_token_account.key(),
-         &self.pda_account.key(),
-         &[],
-         1_000_000_000, //取消质押1lp
-     )?;
-
-     // 执行带签名的 CPI 调用
-     invoke_signed(
-         &transfer_instruction,
-         &[
-            self.token_program.to_account_info(),
-            self.gdtc_lp_in_account.to_account_info(),
-            self.user_lp_token_account.to_account_info(),
-            self.pda_account.to_account_info(),
-         ],
+     // 从 GDTC 托管账户转回用户 LP Token 账户，执行带签名的 CPI 调用
+     let cpi_ctx = CpiContext::new_with_signer(
+         self.token_program.to_account_info(),
+         TransferChecked {
+             from: self.gdtc_lp_in_account.to_account_info(),
+             mint: self.staking_token_mint.to_account_info(),
+             to: self.user_lp_token_account.to_account_info(),
+             authority: self.pda_account.to_account_info(),
+         },
          signer_seeds,
-     )?;
+     );
+     transfer_checked(cpi_ctx, 1_000_000_000, self.staking_token_mint.decimals)?; //取消质押1lp
 
         Ok(())
     }
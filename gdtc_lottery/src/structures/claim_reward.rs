@@ -1,7 +1,8 @@
 use super::{LotteryRound, LotteryState, UserLotteryState};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount,transfer};
-use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
 
 
 #[derive(Accounts)]
@@ -11,32 +12,33 @@ pub struct ClaimReward<'info> {
     /// CHECK:` doc comment explaining why no checks through types are necessary.
     #[account(mut,
         seeds = [crate::LOTTERY_SEED.as_ref()],
-            bump)]
+            bump = lottery_state.bump)]
     pub lottery_state: Account<'info, LotteryState>, // 抽奖状态账户
     #[account(mut)]
     pub authority: Signer<'info>, //签名用户
     /// CHECK:` doc comment explaining why no checks through types are necessary.
     #[account(mut,
     seeds = [crate::LOTTERY_ROUND_SEED.as_ref(),&round_number.to_le_bytes()], // 动态轮次号,
-        bump)]
+        bump = lottery_round.bump)]
     pub lottery_round: Account<'info, LotteryRound>,
 
         #[account(mut, seeds = [authority.key().as_ref()],bump)]
     pub user_lottery_state: Account<'info, UserLotteryState>, // 用户抽奖状态账户
 
     #[account(mut)]
-    pub user_gdtc_token_account: Account<'info, TokenAccount>, // 用户gdtc token账户
+    pub user_gdtc_token_account: InterfaceAccount<'info, TokenAccount>, // 用户gdtc token账户
     #[account(mut)]
-    pub gdtc_reward_out_account: Account<'info, TokenAccount>, //合约转出gdtc 的token账户
+    pub gdtc_reward_out_account: InterfaceAccount<'info, TokenAccount>, //合约转出gdtc 的token账户
+    pub reward_token_mint: InterfaceAccount<'info, Mint>, // 奖励代币 Mint，transfer_checked 需要
     /// CHECK:` doc comment explaining why no checks through types are necessary.
     #[account(
         mut,
         seeds = [crate::LPTOKEN_SEED.as_ref()], 
-        bump,
+        bump = lottery_state.lp_authority_bump,
     )]
     pub pda_account: AccountInfo<'info>, //合约pda账户
     pub system_program: Program<'info, System>, //系统账户 programid
-    pub token_program: Program<'info, Token>,   //token账户 可从sdk里导入
+    pub token_program: Interface<'info, TokenInterface>,   //token账户 可从sdk里导入
 }
 
 impl<'info> ClaimReward<'info> {
@@ -77,27 +79,18 @@ impl<'info> ClaimReward<'info> {
      // 获取 PDA 签名者
      let signer_seeds: &[&[&[u8]]] = &[&[crate::LPTOKEN_SEED.as_ref(), &[bump_seed]]];
 
-     // 生成从 GDTC 托管账户到用户 LP Token 账户的转账指令
-     let transfer_instruction = spl_token::instruction::transfer(
-         &self.token_program.key(),
-         &self.gdtc_reward_out_account.key(),
-         &self.user_gdtc_token_account.key(),
-         &self.pda_account.key(),
-         &[],
-         10_000_000_000, //奖励10个gdtc
-     )?;
-
-     // 执行带签名的 CPI 调用
-     invoke_signed(
-         &transfer_instruction,
-         &[
-            self.token_program.to_account_info(),
-            self.gdtc_reward_out_account.to_account_info(),
-            self.user_gdtc_token_account.to_account_info(),
-            self.pda_account.to_account_info(),
-         ],
+     // 从 GDTC 托管账户转出奖励到用户 GDTC Token 账户，执行带签名的 CPI 调用
+     let cpi_ctx = CpiContext::new_with_signer(
+         self.token_program.to_account_info(),
+         TransferChecked {
+             from: self.gdtc_reward_out_account.to_account_info(),
+             mint: self.reward_token_mint.to_account_info(),
+             to: self.user_gdtc_token_account.to_account_info(),
+             authority: self.pda_account.to_account_info(),
+         },
          signer_seeds,
-     )?;
+     );
+     transfer_checked(cpi_ctx, 10_000_000_000, self.reward_token_mint.decimals)?; //奖励10个gdtc
 
         Ok(())
     }
@@ -0,0 +1,43 @@
+use super::UserLotteryState;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseUserLotteryState<'info> {
+    #[account(mut, seeds = [authority.key().as_ref()], bump, close = authority)]
+    pub user_lottery_state: Account<'info, UserLotteryState>, // 用户抽奖状态账户，关闭后租金退回用户
+    #[account(mut)]
+    pub authority: Signer<'info>, // 用户本人
+    pub system_program: Program<'info, System>, // 系统程序
+}
+
+/// 用户抽奖状态可以关闭的条件：参与过的每一轮都已取回 LP。
+/// claim_lp 会把该轮的 reward_claimed 置为 true，所以 LP 全部取回后也不会再有待领取的中奖奖励
+pub fn check_user_lottery_state_closable(user_lottery_state: &UserLotteryState) -> Result<()> {
+    if user_lottery_state
+        .participated_rounds
+        .iter()
+        .any(|round| round.is_exist && !round.is_unstaked)
+    {
+        return Err(ErrorCode::ActiveParticipation.into());
+    }
+    Ok(())
+}
+
+impl<'info> CloseUserLotteryState<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        if self.user_lottery_state.user_address != self.authority.key() {
+            return Err(ErrorCode::UserAccountIsNotMatch.into());
+        }
+        // 校验通过后由 `close = authority` 在指令结束时转出租金并清空账户
+        check_user_lottery_state_closable(&self.user_lottery_state)
+    }
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("User still has LP staked in a lottery round.")]
+    ActiveParticipation,
+
+    #[msg("User address does not match.")]
+    UserAccountIsNotMatch,
+}
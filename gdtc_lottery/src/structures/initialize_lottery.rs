@@ -1,6 +1,6 @@
 use super::LotteryState;
 use anchor_lang::prelude::*;
-use anchor_spl::token::Mint;
+use anchor_spl::token_interface::Mint;
 
 #[derive(Accounts)]
 pub struct Initialize<'info> {
@@ -8,7 +8,7 @@ pub struct Initialize<'info> {
     #[account(
         init, 
         payer = authority, 
-        space = 8+core::mem::size_of::<LotteryState>(),
+        space = 8 + LotteryState::INIT_SPACE,
         seeds = [crate::LOTTERY_SEED.as_ref()],
         bump
         )]
@@ -19,13 +19,13 @@ pub struct Initialize<'info> {
     /// CHECK:` doc comment explaining why no checks through types are necessary.
     #[account(mut)]
     pub fund_foundation: AccountInfo<'info>, // 基金会账户
-    pub reward_token_mint: Account<'info, Mint>, // 奖励代币 Mint 地址 (GDTC)
-    pub staking_token_mint: Account<'info, Mint>, // 质押代币 Mint 地址 (LP)
+    pub reward_token_mint: InterfaceAccount<'info, Mint>, // 奖励代币 Mint 地址 (GDTC)
+    pub staking_token_mint: InterfaceAccount<'info, Mint>, // 质押代币 Mint 地址 (LP)
     pub system_program: Program<'info, System>,  // 系统程序
 }
 
 impl<'info> Initialize<'info> {
-    pub fn process(&mut self) -> Result<()> {
+    pub fn process(&mut self, bump: u8, lp_authority_bump: u8) -> Result<()> {
         let lottery_state = &mut self.lottery_state;
         // 设置抽奖合约的状态
         lottery_state.authority = self.authority.key();
@@ -33,6 +33,9 @@ impl<'info> Initialize<'info> {
         lottery_state.reward_token_mint = self.reward_token_mint.key();
         lottery_state.staking_token_mint = self.staking_token_mint.key();
         lottery_state.lottery_number = 0;
+        lottery_state.version = crate::LOTTERY_STATE_VERSION;
+        lottery_state.bump = bump;
+        lottery_state.lp_authority_bump = lp_authority_bump;
         Ok(())
     }
 }
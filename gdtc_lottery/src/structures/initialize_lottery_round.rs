@@ -5,7 +5,7 @@ use anchor_lang::solana_program::{program::invoke, system_instruction};
 use gdtc_stake::structures::{StakingInstance,User,Staked};
 use gdtc_stake::program::GdtcStaking;
 use gdtc_stake::cpi::accounts::InitializeUser;
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::TokenAccount;
 
 // 需要的账户结构
 #[derive(Accounts)]
@@ -15,13 +15,19 @@ pub struct InitializeLotteryRound<'info> {
     #[account(
     init, 
     payer = authority, 
-    space = 8+core::mem::size_of::<LotteryRound>(),
+    space = 8 + LotteryRound::INIT_SPACE,
     seeds = [crate::LOTTERY_ROUND_SEED.as_ref(),&round_number.to_le_bytes()], // 动态轮次号,
     bump
     )]
     pub lottery_round: Account<'info, LotteryRound>,
     #[account(mut)]
-    pub authority: Signer<'info>, // 账户的签名者，通常是管理员
+    pub authority: Signer<'info>, // 抽奖管理员，同时支付轮次账户的租金；移交后为管理员集合的 admin_authority PDA
+    #[account(
+        seeds = [crate::LOTTERY_SEED],
+        bump = lottery_state.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+    )]
+    pub lottery_state: Account<'info, LotteryState>, // 抽奖状态账户，只用于校验管理员
 
     /// CHECK:` doc comment explaining why no checks through types are necessary.
     #[account(
@@ -48,12 +54,12 @@ pub struct InitializeLotteryRound<'info> {
         mut
     )]
     pub user_instance: UncheckedAccount<'info>, //彩票合约指定期数的用户实例
-    pub user_superior_token_account: Account<'info, TokenAccount>,
+    pub user_superior_token_account: InterfaceAccount<'info, TokenAccount>,
     pub staking_program: Program<'info, GdtcStaking>, // 质押合约
 }
 
 impl<'info> InitializeLotteryRound<'info> {
-    pub fn process(&mut self, round_number: u64,bump_seed:u8) -> Result<()> {
+    pub fn process(&mut self, round_number: u64,bump_seed:u8,round_bump:u8) -> Result<()> {
 
 
         let transfer_sol_ix = system_instruction::transfer(
@@ -84,6 +90,9 @@ impl<'info> InitializeLotteryRound<'info> {
         lottery_round.is_unstake = true;
         lottery_round.unclaim_lp_number = 50; // 初始化未领取的 LP 数量为 50
         lottery_round.reward_claimed = false;
+        lottery_round.version = crate::LOTTERY_ROUND_VERSION;
+        lottery_round.bump = round_bump;
+        lottery_round.lp_authority_bump = bump_seed;
 
         
         if self.user_superior_token_account.owner != lottery_round.key(){
@@ -108,6 +117,7 @@ impl<'info> InitializeLotteryRound<'info> {
             user_instance:self.user_instance.to_account_info(),
             user_superior_token_account:self.user_superior_token_account.to_account_info(),
             system_program:self.system_program.to_account_info(),
+            allow_entry:None, // 抽奖使用的质押实例不开启白名单模式
         };
 
         // Creates a `CpiContext` object using the new method
@@ -137,4 +147,7 @@ pub enum ErrorCode {
     #[msg("Invalid token mint address.")]
     InvalidTokenMint,
 
+    #[msg("Signer is not the lottery authority.")]
+    Unauthorized,
+
 }
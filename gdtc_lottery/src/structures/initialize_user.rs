@@ -1,4 +1,4 @@
-use super::{ParticipatedRound, UserLotteryState};
+use super::UserLotteryState;
 use anchor_lang::prelude::*;
 use anchor_spl::token::Mint;
 
@@ -7,7 +7,7 @@ pub struct InitializeUserLotteryState<'info> {
     #[account(
         init, 
         payer = authority, 
-        space = 8 + core::mem::size_of::<UserLotteryState>() + 30 * core::mem::size_of::<ParticipatedRound>(),
+        space = 8 + UserLotteryState::INIT_SPACE,
         seeds = [authority.key().as_ref()],
         bump
     )]
@@ -29,6 +29,7 @@ impl<'info> InitializeUserLotteryState<'info> {
             participated_round.is_unstaked = false; // 初始时未解除质押
             participated_round.is_exist = false;
         }
+        user_lottery_state.version = crate::USER_LOTTERY_STATE_VERSION;
 
         Ok(())
     }
@@ -0,0 +1,142 @@
+use super::{LotteryRound, LotteryState, UserLotteryState};
+use anchor_lang::prelude::*;
+use gdtc_stake::{load_for_migration, write_account};
+
+// 旧布局的账户空间不足以按新结构反序列化，这里以 UncheckedAccount 接收，扩容补 0 后再读取
+
+#[derive(Accounts)]
+pub struct MigrateLotteryState<'info> {
+    /// CHECK: owner and discriminator are verified in `process`
+    #[account(mut, seeds = [crate::LOTTERY_SEED], bump)]
+    pub lottery_state: UncheckedAccount<'info>, // 抽奖状态账户
+    #[account(mut)]
+    pub authority: Signer<'info>, // 合约管理员账户，支付扩容租金
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateLotteryState<'info> {
+    pub fn process(&mut self, program_id: &Pubkey, bump: u8) -> Result<()> {
+        let account = self.lottery_state.to_account_info();
+        if account.owner != program_id {
+            return Err(ErrorCode::InvalidAccountOwner.into());
+        }
+        let mut lottery_state = load_for_migration::<LotteryState>(
+            &account,
+            8 + LotteryState::INIT_SPACE,
+            &self.authority.to_account_info(),
+            &self.system_program.to_account_info(),
+        )?;
+        if lottery_state.version >= crate::LOTTERY_STATE_VERSION {
+            return Err(ErrorCode::AccountAlreadyMigrated.into());
+        }
+        if lottery_state.authority != self.authority.key() {
+            return Err(ErrorCode::Unauthorized.into());
+        }
+
+        // 版本 1 -> 2：补齐 bump，迁移只执行一次，这里的 find_program_address 不影响日常指令
+        lottery_state.bump = bump;
+        lottery_state.lp_authority_bump =
+            Pubkey::find_program_address(&[crate::LPTOKEN_SEED], program_id).1;
+        lottery_state.version = crate::LOTTERY_STATE_VERSION;
+        write_account(&account, &lottery_state)
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(round_number:u64)]
+pub struct MigrateLotteryRound<'info> {
+    #[account(seeds = [crate::LOTTERY_SEED], bump = lottery_state.bump)]
+    pub lottery_state: Account<'info, LotteryState>, // 需先完成抽奖状态账户的迁移
+    /// CHECK: owner and discriminator are verified in `process`
+    #[account(
+        mut,
+        seeds = [crate::LOTTERY_ROUND_SEED,&round_number.to_le_bytes()],
+        bump
+    )]
+    pub lottery_round: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>, // 合约管理员账户，支付扩容租金
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateLotteryRound<'info> {
+    pub fn process(&mut self, program_id: &Pubkey, round_number: u64, bump: u8) -> Result<()> {
+        let account = self.lottery_round.to_account_info();
+        if account.owner != program_id {
+            return Err(ErrorCode::InvalidAccountOwner.into());
+        }
+        if self.lottery_state.authority != self.authority.key() {
+            return Err(ErrorCode::Unauthorized.into());
+        }
+        let mut lottery_round = load_for_migration::<LotteryRound>(
+            &account,
+            8 + LotteryRound::INIT_SPACE,
+            &self.authority.to_account_info(),
+            &self.system_program.to_account_info(),
+        )?;
+        if lottery_round.version >= crate::LOTTERY_ROUND_VERSION {
+            return Err(ErrorCode::AccountAlreadyMigrated.into());
+        }
+
+        // 版本 1 -> 2：补齐轮次账户与本轮 LP 权限 PDA 的 bump
+        lottery_round.bump = bump;
+        lottery_round.lp_authority_bump = Pubkey::find_program_address(
+            &[crate::LPTOKEN_SEED, &round_number.to_le_bytes()],
+            program_id,
+        )
+        .1;
+        lottery_round.version = crate::LOTTERY_ROUND_VERSION;
+        write_account(&account, &lottery_round)
+    }
+}
+
+#[derive(Accounts)]
+pub struct MigrateUserLotteryState<'info> {
+    /// CHECK: owner, discriminator and PDA address are verified in `process`
+    #[account(mut)]
+    pub user_lottery_state: UncheckedAccount<'info>, // 用户抽奖状态账户
+    #[account(mut)]
+    pub payer: Signer<'info>, // 任何人都可以代付租金完成迁移，迁移结果是确定的
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateUserLotteryState<'info> {
+    pub fn process(&mut self, program_id: &Pubkey) -> Result<()> {
+        let account = self.user_lottery_state.to_account_info();
+        if account.owner != program_id {
+            return Err(ErrorCode::InvalidAccountOwner.into());
+        }
+        let mut user_lottery_state = load_for_migration::<UserLotteryState>(
+            &account,
+            8 + UserLotteryState::INIT_SPACE,
+            &self.payer.to_account_info(),
+            &self.system_program.to_account_info(),
+        )?;
+        if user_lottery_state.version >= crate::USER_LOTTERY_STATE_VERSION {
+            return Err(ErrorCode::AccountAlreadyMigrated.into());
+        }
+        let (expected_pda_address, _) =
+            Pubkey::find_program_address(&[user_lottery_state.user_address.as_ref()], program_id);
+        if expected_pda_address != account.key() {
+            return Err(ErrorCode::PdaAccountIsNotMatch.into());
+        }
+
+        user_lottery_state.version = crate::USER_LOTTERY_STATE_VERSION;
+        write_account(&account, &user_lottery_state)
+    }
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Invalid account owner.")]
+    InvalidAccountOwner,
+
+    #[msg("Account is already at the current layout version.")]
+    AccountAlreadyMigrated,
+
+    #[msg("Signer is not the lottery authority.")]
+    Unauthorized,
+
+    #[msg("The PDA account does not match.")]
+    PdaAccountIsNotMatch,
+}
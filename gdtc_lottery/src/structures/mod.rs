@@ -1,4 +1,7 @@
 use anchor_lang::prelude::*;
+use crate::constants::{
+    LOTTERY_RESERVED_BYTES, LOTTERY_ROUND_RESERVED_BYTES, LOTTERY_STATE_RESERVED_BYTES,
+};
 pub mod error;
 pub mod initialize_lottery;
 pub mod initialize_lottery_round;
@@ -7,18 +10,28 @@ pub mod participate;
 pub mod claim_reward;
 pub mod claim_lp;
 pub mod admin_claim_reward;
+pub mod migrate_accounts;
+pub mod close_user_lottery_state;
+pub mod set_authority;
 
 
 #[account]
+#[derive(InitSpace)]
 pub struct LotteryState {
     pub authority: Pubkey, // 管理员账户
     pub fund_foundation: Pubkey,    // 基金会地址
     pub reward_token_mint: Pubkey,  // 奖励代币 Mint 地址
     pub staking_token_mint: Pubkey, // 质押代币 Mint 地址
     pub lottery_number: u64,        //当前彩票轮次
+    // 以下字段必须位于末尾：旧账户扩容后新增字节为 0，读出的 version 为 0 即表示待迁移
+    pub version: u8,                // 账户布局版本
+    pub bump: u8,                   // 抽奖状态 PDA 的 bump（version 2 起，取自预留字节）
+    pub lp_authority_bump: u8,      // 全局 LP/奖励金库权限 PDA 的 bump（version 2 起）
+    pub reserved: [u8; LOTTERY_STATE_RESERVED_BYTES], // 预留给后续新增字段
 }
 
 #[account]
+#[derive(InitSpace)]
 pub struct LotteryRound {
     pub round_number: u64,           // 当前抽奖的期数
     pub max_hash_values: u128, // 固定长度的元组数组，每个元素是参与者的 Pubkey 和去掉字母后的哈希值
@@ -30,6 +43,10 @@ pub struct LotteryRound {
     pub is_unstake: bool,      //是否已解除质押
     pub unclaim_lp_number: u64, //未领取的lp数量，初始值应为50
     pub reward_claimed: bool,  // 用户是否已经领取奖励
+    pub version: u8,           // 账户布局版本，与以下字段一起位于末尾
+    pub bump: u8,              // 轮次 PDA 的 bump（version 2 起，取自预留字节）
+    pub lp_authority_bump: u8, // 本轮 LP 权限 PDA 的 bump（version 2 起）
+    pub reserved: [u8; LOTTERY_ROUND_RESERVED_BYTES], // 预留给后续新增字段
 }
 
 // #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
@@ -40,12 +57,15 @@ pub struct LotteryRound {
 // }
 
 #[account]
+#[derive(InitSpace)]
 pub struct UserLotteryState {
     pub user_address: Pubkey,                         // 用户的公钥
     pub participated_rounds: [ParticipatedRound; 30], // 用户参与的轮次信息
+    pub version: u8,                                  // 账户布局版本，必须位于末尾
+    pub reserved: [u8; LOTTERY_RESERVED_BYTES],       // 预留给后续新增字段
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, InitSpace)]
 pub struct ParticipatedRound {
     pub round_number: u64, // 用户参与的抽奖轮次
     pub is_unstaked: bool, // 用户是否取消了质押
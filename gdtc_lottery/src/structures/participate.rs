@@ -1,11 +1,12 @@
 use super::{LotteryRound, LotteryState, UserLotteryState};
 use anchor_lang::prelude::*;
 use solana_program::hash::{hash, Hash};
-use anchor_spl::token::{Mint, Token, TokenAccount, Transfer,transfer};
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
 use gdtc_stake::structures::{StakingInstance,User};
 use gdtc_stake::program::GdtcStaking;
 use gdtc_stake::cpi::accounts::EnterStaking;
-use anchor_lang::solana_program::program::invoke_signed;
 
 
 #[derive(Accounts)]
@@ -14,20 +15,21 @@ pub struct Participate<'info> {
     /// CHECK:` doc comment explaining why no checks through types are necessary.
     #[account(mut,
     seeds = [crate::LOTTERY_SEED.as_ref()],
-        bump)]
+        bump = lottery_state.bump)]
     pub lottery_state: Account<'info, LotteryState>, // 抽奖状态账户
     #[account(mut,
     seeds = [crate::LOTTERY_ROUND_SEED.as_ref(),&round_number.to_le_bytes()], // 动态轮次号,
-    bump)]
+    bump = lottery_round.bump)]
     pub lottery_round: Account<'info, LotteryRound>,
     #[account(mut, seeds = [authority.key().as_ref()],bump)]
     pub user_lottery_state: Account<'info, UserLotteryState>, // 用户抽奖状态账户
     #[account(mut)]
     pub authority: Signer<'info>, //签名账户
     #[account(mut)]
-    pub user_lp_token_account: Account<'info, TokenAccount>,
+    pub user_lp_token_account: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
-    pub gdtc_lp_in_account: Account<'info, TokenAccount>,
+    pub gdtc_lp_in_account: InterfaceAccount<'info, TokenAccount>,
+    pub staking_token_mint: InterfaceAccount<'info, Mint>, // 质押代币 Mint (LP)，transfer_checked 需要
    
     pub system_program: Program<'info, System>, // 系统程序
     
@@ -37,16 +39,16 @@ pub struct Participate<'info> {
     #[account(mut)]
     pub user_instance: Account<'info, User>, //彩票合约指定期数的用户实例
     #[account(mut)] 
-    pub gdtc_stake_lp_in_account: Account<'info, TokenAccount>,
+    pub gdtc_stake_lp_in_account: InterfaceAccount<'info, TokenAccount>,
     /// CHECK:` doc comment explaining why no checks through types are necessary.
     #[account(
         mut,
         seeds = [crate::LPTOKEN_SEED.as_ref(),&round_number.to_le_bytes()], 
-        bump,
+        bump = lottery_round.lp_authority_bump,
     )]
     pub pda_account: AccountInfo<'info>, //合约pda账户
     pub staking_program: Program<'info, GdtcStaking>, // 质押合约
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 impl<'info> Participate<'info> {
@@ -137,7 +139,11 @@ impl<'info> Participate<'info> {
 
 
         //转入lp
-        transfer(self.into_transfer_to_vault_context(), lp_number)?;
+        transfer_checked(
+            self.into_transfer_to_vault_context(),
+            lp_number,
+            self.staking_token_mint.decimals,
+        )?;
         
 
         
@@ -155,8 +161,10 @@ impl<'info> Participate<'info> {
             user_instance:self.user_instance.to_account_info(),
             user_lp_token_account:self.gdtc_lp_in_account.to_account_info(),
             gdtc_lp_in_account:self.gdtc_stake_lp_in_account.to_account_info(),
+            staking_token_mint:self.staking_token_mint.to_account_info(),
             system_program:self.system_program.to_account_info(),
             token_program:self.token_program.to_account_info(),
+            allow_entry:None, // 抽奖使用的质押实例不开启白名单模式
         };
 
         // Creates a `CpiContext` object using the new method
@@ -173,11 +181,12 @@ impl<'info> Participate<'info> {
         
     }
 
-    pub fn into_transfer_to_vault_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+    pub fn into_transfer_to_vault_context(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
         CpiContext::new(
             self.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: self.user_lp_token_account.to_account_info(),
+                mint: self.staking_token_mint.to_account_info(),
                 to: self.gdtc_lp_in_account.to_account_info(),
                 authority: self.authority.to_account_info(),
             },
@@ -0,0 +1,44 @@
+use super::LotteryState;
+use anchor_lang::prelude::*;
+use gdtc_stake::structures::AdminSet;
+
+// 管理员把抽奖合约的管理权限交给质押合约中多签管理员集合的 admin_authority PDA。
+// 当前管理员若仍是单签账户，只能借此一次性移交；移交后再次调用也只能通过时间锁提案
+#[derive(Accounts)]
+pub struct SetLotteryAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [crate::LOTTERY_SEED],
+        bump = lottery_state.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+    )]
+    pub lottery_state: Account<'info, LotteryState>, // 抽奖状态账户
+    pub authority: Signer<'info>, // 当前管理员账户
+    #[account(
+        seeds = [gdtc_stake::constants::ADMIN_SET_SEED, new_admin_set.create_key.as_ref()],
+        bump = new_admin_set.bump,
+        seeds::program = gdtc_stake::ID,
+    )]
+    pub new_admin_set: Account<'info, AdminSet>, // 接管的管理员集合
+    /// CHECK: only the address is used; it becomes the new authority
+    #[account(
+        seeds = [gdtc_stake::constants::ADMIN_AUTHORITY_SEED, new_admin_set.key().as_ref()],
+        bump = new_admin_set.authority_bump,
+        seeds::program = gdtc_stake::ID,
+    )]
+    pub new_admin_authority: UncheckedAccount<'info>, // 新管理员集合的 admin_authority PDA
+}
+
+impl<'info> SetLotteryAuthority<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        // 当前管理员和新管理员的派生地址都已由账户约束校验
+        self.lottery_state.authority = self.new_admin_authority.key();
+        Ok(())
+    }
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Signer is not the lottery authority.")]
+    Unauthorized,
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use gdtc_lottery::constants::{
+    LOTTERY_RESERVED_BYTES, LOTTERY_ROUND_RESERVED_BYTES, LOTTERY_ROUND_VERSION,
+    LOTTERY_STATE_RESERVED_BYTES, LOTTERY_STATE_VERSION, USER_LOTTERY_STATE_VERSION,
+};
+use gdtc_lottery::structures::{LotteryRound, LotteryState, ParticipatedRound, UserLotteryState};
+
+// 序列化结果必须正好填满账户；Option 字段取 Some，按最大编码长度检查
+fn serialized_len<T: AccountSerialize>(account: &T) -> usize {
+    let mut bytes = Vec::new();
+    account.try_serialize(&mut bytes).unwrap();
+    bytes.len()
+}
+
+#[test]
+fn lottery_state_fills_its_space_exactly() {
+    let state = LotteryState {
+        authority: Pubkey::new_unique(),
+        fund_foundation: Pubkey::new_unique(),
+        reward_token_mint: Pubkey::new_unique(),
+        staking_token_mint: Pubkey::new_unique(),
+        lottery_number: u64::MAX,
+        version: LOTTERY_STATE_VERSION,
+        bump: 255,
+        lp_authority_bump: 254,
+        reserved: [0; LOTTERY_STATE_RESERVED_BYTES],
+    };
+    assert_eq!(serialized_len(&state), 8 + LotteryState::INIT_SPACE);
+}
+
+#[test]
+fn lottery_round_fills_its_space_exactly() {
+    let round = LotteryRound {
+        round_number: u64::MAX,
+        max_hash_values: u128::MAX,
+        winner: Pubkey::new_unique(),
+        total_lp: u64::MAX,
+        round_start_time: i64::MAX,
+        round_end_time: Some(i64::MAX),
+        is_active: true,
+        is_unstake: true,
+        unclaim_lp_number: u64::MAX,
+        reward_claimed: true,
+        version: LOTTERY_ROUND_VERSION,
+        bump: 255,
+        lp_authority_bump: 254,
+        reserved: [0; LOTTERY_ROUND_RESERVED_BYTES],
+    };
+    assert_eq!(serialized_len(&round), 8 + LotteryRound::INIT_SPACE);
+}
+
+#[test]
+fn user_lottery_state_fills_its_space_exactly() {
+    let state = UserLotteryState {
+        user_address: Pubkey::new_unique(),
+        participated_rounds: core::array::from_fn(|i| ParticipatedRound {
+            round_number: i as u64,
+            is_unstaked: true,
+            is_exist: true,
+        }),
+        version: USER_LOTTERY_STATE_VERSION,
+        reserved: [0; LOTTERY_RESERVED_BYTES],
+    };
+    assert_eq!(serialized_len(&state), 8 + UserLotteryState::INIT_SPACE);
+}
@@ -0,0 +1,236 @@
+use std::collections::BTreeSet;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::Bumps;
+use anchor_spl::token::spl_token::state::{Account as SplTokenAccount, AccountState};
+use gdtc_lottery::constants::{
+    LOTTERY_ROUND_RESERVED_BYTES, LOTTERY_ROUND_SEED, LOTTERY_ROUND_VERSION, LOTTERY_SEED,
+    LOTTERY_STATE_RESERVED_BYTES, LOTTERY_STATE_VERSION, LPTOKEN_SEED,
+};
+use gdtc_lottery::structures::admin_claim_reward::{self, AdminClaimReward};
+use gdtc_lottery::structures::set_authority::{self, SetLotteryAuthority};
+use gdtc_lottery::structures::{LotteryRound, LotteryState};
+use gdtc_stake::constants::{
+    ADMIN_AUTHORITY_SEED, ADMIN_SET_RESERVED_BYTES, ADMIN_SET_SEED, ADMIN_SET_VERSION,
+    MAX_ADMIN_MEMBERS, MIN_TIMELOCK_DELAY_SECS,
+};
+use gdtc_stake::structures::AdminSet;
+
+// 直接调用 Anchor 生成的 try_accounts，只检查账户约束，不执行指令本身
+
+struct Fixture {
+    key: Pubkey,
+    owner: Pubkey,
+    data: Vec<u8>,
+    is_signer: bool,
+    executable: bool,
+}
+
+fn program_account<T: AccountSerialize>(key: Pubkey, owner: Pubkey, value: &T) -> Fixture {
+    let mut data = Vec::new();
+    value.try_serialize(&mut data).unwrap();
+    Fixture {
+        key,
+        owner,
+        data,
+        is_signer: false,
+        executable: false,
+    }
+}
+
+fn plain(key: Pubkey, is_signer: bool) -> Fixture {
+    Fixture {
+        key,
+        owner: anchor_lang::system_program::ID,
+        data: Vec::new(),
+        is_signer,
+        executable: false,
+    }
+}
+
+fn program(key: Pubkey) -> Fixture {
+    Fixture {
+        key,
+        owner: Pubkey::default(),
+        data: Vec::new(),
+        is_signer: false,
+        executable: true,
+    }
+}
+
+// 带 #[instruction(...)] 的账户结构需要指令参数（不含鉴别符）参与种子计算
+fn try_accounts<T>(fixtures: Vec<Fixture>, ix_data: &[u8]) -> Result<()>
+where
+    T: Accounts<'static, <T as Bumps>::Bumps> + Bumps,
+    <T as Bumps>::Bumps: Default,
+{
+    let infos: Vec<AccountInfo<'static>> = fixtures
+        .into_iter()
+        .map(|fixture| {
+            AccountInfo::new(
+                Box::leak(Box::new(fixture.key)),
+                fixture.is_signer,
+                true,
+                Box::leak(Box::new(1_000_000_000)),
+                Box::leak(fixture.data.into_boxed_slice()),
+                Box::leak(Box::new(fixture.owner)),
+                fixture.executable,
+                0,
+            )
+        })
+        .collect();
+    let mut infos: &'static [AccountInfo<'static>] = Box::leak(infos.into_boxed_slice());
+    T::try_accounts(
+        &gdtc_lottery::ID,
+        &mut infos,
+        ix_data,
+        &mut Default::default(),
+        &mut BTreeSet::new(),
+    )
+    .map(|_| ())
+}
+
+fn token_account() -> Fixture {
+    let mut data = vec![0; SplTokenAccount::LEN];
+    SplTokenAccount {
+        mint: Pubkey::new_unique(),
+        owner: Pubkey::new_unique(),
+        amount: 0,
+        delegate: COption::None,
+        state: AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    }
+    .pack_into_slice(&mut data);
+    Fixture {
+        key: Pubkey::new_unique(),
+        owner: anchor_spl::token::ID,
+        data,
+        is_signer: false,
+        executable: false,
+    }
+}
+
+fn lottery_state(authority: Pubkey) -> Fixture {
+    let (address, bump) = Pubkey::find_program_address(&[LOTTERY_SEED], &gdtc_lottery::ID);
+    let (_, lp_authority_bump) = Pubkey::find_program_address(&[LPTOKEN_SEED], &gdtc_lottery::ID);
+    let state = LotteryState {
+        authority,
+        fund_foundation: Pubkey::new_unique(),
+        reward_token_mint: Pubkey::new_unique(),
+        staking_token_mint: Pubkey::new_unique(),
+        lottery_number: 1,
+        version: LOTTERY_STATE_VERSION,
+        bump,
+        lp_authority_bump,
+        reserved: [0; LOTTERY_STATE_RESERVED_BYTES],
+    };
+    program_account(address, gdtc_lottery::ID, &state)
+}
+
+fn lottery_round(round_number: u64) -> Fixture {
+    let (address, bump) = Pubkey::find_program_address(
+        &[LOTTERY_ROUND_SEED, &round_number.to_le_bytes()],
+        &gdtc_lottery::ID,
+    );
+    let round = LotteryRound {
+        round_number,
+        max_hash_values: 0,
+        winner: Pubkey::default(),
+        total_lp: 0,
+        round_start_time: 0,
+        round_end_time: None,
+        is_active: true,
+        is_unstake: true,
+        unclaim_lp_number: 50,
+        reward_claimed: false,
+        version: LOTTERY_ROUND_VERSION,
+        bump,
+        lp_authority_bump: 0,
+        reserved: [0; LOTTERY_ROUND_RESERVED_BYTES],
+    };
+    program_account(address, gdtc_lottery::ID, &round)
+}
+
+// 质押合约中的管理员集合及其 admin_authority PDA
+fn admin_set() -> (Fixture, Pubkey) {
+    let create_key = Pubkey::new_unique();
+    let (address, bump) =
+        Pubkey::find_program_address(&[ADMIN_SET_SEED, create_key.as_ref()], &gdtc_stake::ID);
+    let (admin_authority, authority_bump) =
+        Pubkey::find_program_address(&[ADMIN_AUTHORITY_SEED, address.as_ref()], &gdtc_stake::ID);
+    let mut members = [Pubkey::default(); MAX_ADMIN_MEMBERS];
+    members[0] = Pubkey::new_unique();
+    let admin_set = AdminSet {
+        create_key,
+        members,
+        member_count: 1,
+        threshold: 1,
+        generation: 0,
+        proposal_count: 0,
+        version: ADMIN_SET_VERSION,
+        bump,
+        authority_bump,
+        delay_secs: MIN_TIMELOCK_DELAY_SECS,
+        reserved: [0; ADMIN_SET_RESERVED_BYTES],
+    };
+    (
+        program_account(address, gdtc_stake::ID, &admin_set),
+        admin_authority,
+    )
+}
+
+fn set_authority(current: Pubkey, signer: Pubkey) -> Result<()> {
+    let (admin_set, admin_authority) = admin_set();
+    try_accounts::<SetLotteryAuthority>(
+        vec![
+            lottery_state(current),
+            plain(signer, true),
+            admin_set,
+            plain(admin_authority, false),
+        ],
+        &[],
+    )
+}
+
+fn admin_claim_reward(current: Pubkey, signer: Pubkey) -> Result<()> {
+    let round_number = 3u64;
+    let (pda_account, _) = Pubkey::find_program_address(&[LPTOKEN_SEED], &gdtc_lottery::ID);
+    try_accounts::<AdminClaimReward>(
+        vec![
+            lottery_state(current),
+            plain(signer, true),
+            lottery_round(round_number),
+            token_account(),
+            token_account(),
+            plain(pda_account, false),
+            program(anchor_lang::system_program::ID),
+            program(anchor_spl::token::ID),
+        ],
+        &round_number.to_le_bytes(),
+    )
+}
+
+#[test]
+fn only_the_lottery_authority_can_hand_over() {
+    let authority = Pubkey::new_unique();
+    assert!(set_authority(authority, authority).is_ok());
+    assert_eq!(
+        set_authority(authority, Pubkey::new_unique()).unwrap_err(),
+        set_authority::ErrorCode::Unauthorized.into()
+    );
+}
+
+#[test]
+fn admin_instructions_require_the_lottery_authority() {
+    // 移交后管理员为 admin_authority PDA，只能通过时间锁提案签名
+    let (_, admin_authority) = admin_set();
+    assert!(admin_claim_reward(admin_authority, admin_authority).is_ok());
+    assert_eq!(
+        admin_claim_reward(admin_authority, Pubkey::new_unique()).unwrap_err(),
+        admin_claim_reward::ErrorCode::Unauthorized.into()
+    );
+}
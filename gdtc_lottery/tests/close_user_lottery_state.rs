@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use gdtc_lottery::constants::{LOTTERY_RESERVED_BYTES, USER_LOTTERY_STATE_VERSION};
+use gdtc_lottery::structures::close_user_lottery_state::{
+    check_user_lottery_state_closable, ErrorCode,
+};
+use gdtc_lottery::structures::{ParticipatedRound, UserLotteryState};
+
+fn user_lottery_state() -> UserLotteryState {
+    UserLotteryState {
+        user_address: Pubkey::new_unique(),
+        participated_rounds: core::array::from_fn(|_| ParticipatedRound {
+            round_number: 0,
+            is_unstaked: false,
+            is_exist: false,
+        }),
+        version: USER_LOTTERY_STATE_VERSION,
+        reserved: [0; LOTTERY_RESERVED_BYTES],
+    }
+}
+
+#[test]
+fn state_without_participations_can_close() {
+    assert!(check_user_lottery_state_closable(&user_lottery_state()).is_ok());
+}
+
+#[test]
+fn state_with_all_lp_claimed_can_close() {
+    let mut state = user_lottery_state();
+    for (i, round) in state.participated_rounds.iter_mut().take(3).enumerate() {
+        round.round_number = i as u64 + 1;
+        round.is_exist = true;
+        round.is_unstaked = true;
+    }
+    assert!(check_user_lottery_state_closable(&state).is_ok());
+}
+
+#[test]
+fn unclaimed_lp_blocks_closing() {
+    let mut state = user_lottery_state();
+    state.participated_rounds[29].round_number = 7;
+    state.participated_rounds[29].is_exist = true;
+    assert_eq!(
+        check_user_lottery_state_closable(&state).unwrap_err(),
+        ErrorCode::ActiveParticipation.into()
+    );
+}
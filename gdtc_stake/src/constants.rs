@@ -1,6 +1,60 @@
+use crate::structures::LockTerm;
+
 pub static TOKEN_PROGRAM_BYTES: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
 pub static NFT_TOKEN_PROGRAM_BYTES: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
 pub static COMPUTATION_DECIMALS: u64 = 10u64.pow(12);
 pub static STAKING_SEED: &[u8] = b"staking_instance";
 pub static USER_SEED: &[u8] = b"user_deposit";
 pub static LPTOKEN_SEED: &[u8] = b"lp_token";
+pub static ALLOW_ENTRY_SEED: &[u8] = b"allow_entry";
+pub static DEPOSIT_APPROVAL_SEED: &[u8] = b"deposit_approval";
+pub static ADMIN_SET_SEED: &[u8] = b"admin_set";
+pub static ADMIN_AUTHORITY_SEED: &[u8] = b"admin_authority";
+pub static PROPOSAL_SEED: &[u8] = b"proposal";
+pub const LEGACY_INSTANCE_ID: u64 = u64::MAX; // 最初部署的单例实例，地址只由 STAKING_SEED 派生，不能再用于新实例
+pub const DEFAULT_UTC_OFFSET_SECONDS: i32 = 8 * 60 * 60; // 默认 UTC+8 (东八区)
+pub const MAX_UTC_OFFSET_SECONDS: i32 = 14 * 60 * 60; // 时区偏移上限 ±14 小时
+pub const MAX_LOCK_MONTHS: u64 = 120; // 日历月锁仓上限
+pub const MAX_LOCK_SECONDS: u64 = 10 * 366 * 24 * 60 * 60; // 固定时长锁仓上限，约 10 年
+// `fast-time` 特性下替换的分钟级锁仓期限（30/60/120 分钟），仅用于 devnet/测试构建
+pub const FAST_TIME_LOCK_TERMS: [LockTerm; 3] = [
+    LockTerm::FixedSeconds(30 * 60),
+    LockTerm::FixedSeconds(60 * 60),
+    LockTerm::FixedSeconds(120 * 60),
+];
+// 各池停止接受新质押的时间，锁仓 3/6/12 个月的质押都在同一天 (2044-12) 之前到期
+pub const STAKING_DEADLINES: [u64; 3] = [2358810461, 2350861661, 2335136861];
+// `fast-time` 特性下按 3 小时循环：每个周期内各池在开始后 150/120/60 分钟停止质押，所有质押在周期结束前到期
+pub const FAST_TIME_CYCLE_SECONDS: u64 = 180 * 60;
+pub const FAST_TIME_STAKING_DEADLINES: [u64; 3] = [150 * 60, 120 * 60, 60 * 60];
+pub const BPS_DENOMINATOR: u64 = 10_000; // 基点分母，10000 = 100%
+pub const REFERRAL_MIN_SUPERIOR_DEPOSIT: u64 = 2_000_000_000; // 上级质押超过该数量时才发放 10% 推荐奖励
+pub const MAX_REWARD_STREAMS: usize = 2; // 每个质押池最多可挂载的合作方奖励流数量
+pub const MAX_ADMIN_MEMBERS: usize = 10; // 多签管理员集合的成员上限，批准记录为 u16 位图
+pub const MAX_PROPOSAL_ACCOUNTS: usize = 16; // 提案指令最多引用的账户数
+pub const MAX_PROPOSAL_DATA_LEN: usize = 256; // 提案指令数据的最大字节数
+pub const MIN_TIMELOCK_DELAY_SECS: u64 = 24 * 60 * 60; // 提案排队后至少等待 1 天才能执行
+pub const MAX_TIMELOCK_DELAY_SECS: u64 = 30 * 24 * 60 * 60; // 延迟上限 30 天，避免管理指令被永久锁住
+pub const VOTE_MAX_LOCK_SECS: u64 = 365 * 24 * 60 * 60; // 剩余锁仓达到 1 年即为满投票权
+// 账户布局版本：新增字段从末尾的保留字节中划出，递增版本号并在 migrate_* 中补齐默认值
+// version 2: 新增 PDA bump 字段
+// version 3: StakingInstance 新增白名单开关
+// version 4: StakingInstance 新增各池的质押数量限制
+// version 5: StakingInstance 新增各池的排放计划
+// version 6: StakingInstance 新增各池的收益率曲线，末尾扩充到 256 字节（旧账户由 migrate_staking_instance 扩容）
+// version 7: StakingInstance 新增尚未支付的奖励总额（含推荐奖励）
+// User version 3: 新增各槽位的质押 slot，末尾扩充到 128 字节（旧账户由 migrate_user 扩容）
+pub const STAKING_INSTANCE_VERSION: u8 = 7;
+pub const USER_VERSION: u8 = 3;
+pub const ALLOW_ENTRY_VERSION: u8 = 1;
+pub const DEPOSIT_APPROVAL_VERSION: u8 = 1;
+// AdminSet / Proposal version 2: 新增时间锁延迟和提案的可执行时间，取值 0 即为正确的默认值，无需迁移
+pub const ADMIN_SET_VERSION: u8 = 2;
+pub const PROPOSAL_VERSION: u8 = 2;
+pub const STAKING_INSTANCE_RESERVED_BYTES: usize = 256 - 3 - 3 * 24 - 3 * 14 - 3 * 20 - 8; // StakingInstance 剩余预留字节数（已用 2 字节存放 bump，1 字节存放白名单开关，72 字节存放质押数量限制，42 字节存放排放计划，60 字节存放收益率曲线，8 字节存放未支付奖励）
+pub const EMISSION_DECAY_SCALE: u128 = 1_000_000_000_000; // 排放衰减系数的定点精度
+pub const USER_RESERVED_BYTES: usize = 128 - 1 - 10 * 8; // User 剩余预留字节数（已用 1 字节存放 bump，80 字节存放质押 slot）
+pub const ALLOW_ENTRY_RESERVED_BYTES: usize = 32; // AllowEntry 预留字节数
+pub const DEPOSIT_APPROVAL_RESERVED_BYTES: usize = 32; // DepositApproval 预留字节数
+pub const ADMIN_SET_RESERVED_BYTES: usize = 64 - 8; // AdminSet 剩余预留字节数（已用 8 字节存放时间锁延迟）
+pub const PROPOSAL_RESERVED_BYTES: usize = 64 - 8; // Proposal 剩余预留字节数（已用 8 字节存放可执行时间）
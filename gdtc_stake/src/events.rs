@@ -0,0 +1,87 @@
+// 程序发出的事件，供 keeper 和看板订阅
+use anchor_lang::prelude::*;
+
+use crate::reward_math::{curve_rate, scheduled_rate};
+use crate::structures::{EmissionSchedule, ProposalAccount, RateCurve, StakingPool};
+
+// 单个质押池在某一时刻的奖励累计状态
+#[derive(Debug, Clone, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct PoolSnapshot {
+    pub stake_type: u64,
+    pub reward_token_per_sec: u64,
+    pub effective_reward_per_sec: u64, // 收益率曲线和排放计划生效后实际排放的速率
+    pub accumulated_reward_per_share: u128, // 放大 COMPUTATION_DECIMALS 倍
+    pub last_reward_timestamp: u64,
+    pub total_shares: u64,
+}
+
+impl PoolSnapshot {
+    /// 池子在 timestamp 时的快照，实际速率与 update_reward_pool 使用的相同
+    pub fn new(
+        pool: &StakingPool,
+        schedule: &EmissionSchedule,
+        curve: &RateCurve,
+        timestamp: u64,
+    ) -> Self {
+        PoolSnapshot {
+            stake_type: pool.stake_type,
+            reward_token_per_sec: pool.reward_token_per_sec,
+            effective_reward_per_sec: scheduled_rate(
+                curve_rate(pool.reward_token_per_sec, curve, pool.total_shares),
+                schedule,
+                timestamp,
+            ),
+            accumulated_reward_per_share: pool.accumulated_reward_per_share,
+            last_reward_timestamp: pool.last_reward_timestamp,
+            total_shares: pool.total_shares,
+        }
+    }
+}
+
+// crank_pools 更新全部质押池后发出
+#[event]
+pub struct PoolsCranked {
+    pub staking_instance: Pubkey,
+    pub timestamp: u64,
+    pub pools: Vec<PoolSnapshot>,
+}
+
+// sweep 从 LP 权限 PDA 名下账户取回代币后发出
+#[event]
+pub struct Swept {
+    pub staking_instance: Pubkey,
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+// 提案达到门限并进入时间锁后发出，公布将要执行的指令和最早执行时间
+#[event]
+pub struct ProposalQueued {
+    pub admin_set: Pubkey,
+    pub proposal: Pubkey,
+    pub id: u64,
+    pub program_id: Pubkey,
+    pub accounts: Vec<ProposalAccount>,
+    pub data: Vec<u8>,
+    pub eta: u64,
+}
+
+// 提案执行后发出
+#[event]
+pub struct ProposalExecuted {
+    pub admin_set: Pubkey,
+    pub proposal: Pubkey,
+    pub id: u64,
+}
+
+// 提案被成员取消后发出
+#[event]
+pub struct ProposalCancelled {
+    pub admin_set: Pubkey,
+    pub proposal: Pubkey,
+    pub id: u64,
+    pub cancelled_by: Pubkey,
+}
@@ -1,61 +1,176 @@
 pub mod constants;
+pub mod events;
+pub mod reward_math;
 pub mod structures;
 pub mod tools;
 
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::program::invoke_signed;
-use anchor_spl::token;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_spl::token_interface;
 use constants::*;
+use events::{
+    PoolSnapshot, PoolsCranked, ProposalCancelled, ProposalExecuted, ProposalQueued, Swept,
+};
 use structures::{
-    cancel_staking::*, claim_rewards::*, enter_staking::*, initialize_staking::*,
-    initialize_user::*, Staked, StakingInstance, StakingPool, User,
+    add_reward_stream::*,
+    admin_set::*,
+    allowlist::*,
+    cancel_staking::*,
+    claim_rewards::*,
+    claim_stream_rewards::*,
+    close_user::*,
+    crank_pools::*,
+    deposit_approval::*,
+    deposit_for::*,
+    early_unstake::*,
+    enter_staking::*,
+    initialize_staking::*,
+    initialize_user::*,
+    legacy::{StakingInstanceV1, UserV1, STAKING_INSTANCE_V1_SPACE, USER_V1_SPACE},
+    migrate_accounts::*,
+    set_early_unstake_policy::*,
+    set_pool_config::*,
+    sweep::*,
+    voting_power::*,
+    AdminSet, AllowEntry, EmissionSchedule, LockTerm, Proposal, ProposalAccount, RateCurve,
+    RewardStream, StakeLimits, Staked, StakingInstance, StakingPool, User,
 };
-use tools::{generate_release_timestamps, test_generate_release_timestamp};
+use tools::{effective_lock_term, lock_end_timestamp};
 
 declare_id!("H79TrubVu9ParAtDtuYqzKVZP3TR531sPxoDqaeA8KXK");
 
-pub fn update_reward_pool(current_timestamp: u64, staking_instance: &mut StakingInstance) {
+pub fn update_reward_pool(
+    current_timestamp: u64,
+    staking_instance: &mut StakingInstance,
+) -> Result<()> {
+    let mut emitted = 0u128;
     // 遍历每个质押池
-    for pool in staking_instance.pools.iter_mut() {
-        // 如果没有份额，跳过此池
+    for ((pool, schedule), curve) in staking_instance
+        .pools
+        .iter_mut()
+        .zip(staking_instance.emission_schedules.iter())
+        .zip(staking_instance.rate_curves.iter())
+    {
+        // 如果没有份额，跳过此池；空池期间的奖励不发放，时间戳照常推进，避免补发给下一个进入的质押者
         if pool.total_shares == 0 {
+            pool.last_reward_timestamp = pool.last_reward_timestamp.max(current_timestamp);
             continue;
         }
-        // 计算时间差（当前时间戳 - 上次奖励时间戳）
-        let time_diff = current_timestamp
-            .checked_sub(pool.last_reward_timestamp)
-            .unwrap_or(0);
+        // 计算时间差（当前时间戳 - 上次奖励时间戳），奖励开始时间之前为 0
+        let time_diff = current_timestamp.saturating_sub(pool.last_reward_timestamp);
 
         // 如果时间差为 0，跳过此池
         if time_diff == 0 {
             continue;
         }
 
-        // 计算池子的总奖励收入（奖励速率 * 时间差）
-        let income = pool
-            .reward_token_per_sec
-            .checked_mul(time_diff)
-            .unwrap_or(0);
-
-        // 更新 `accumulated_reward_per_share`
-        if pool.total_shares > 0 {
-            // 每份奖励计算
-            let reward_per_share = (income as u128)
-                .checked_mul(COMPUTATION_DECIMALS as u128) // 精度调整
-                .unwrap_or(0)
-                .checked_div(pool.total_shares as u128) // 每份奖励
-                .unwrap_or(0) as u64;
+        // 基础速率由收益率曲线按当前总份额给出，再按排放计划累加每份奖励的累计值，
+        // 跨越多个衰减周期时分段积分
+        let income = reward_math::scheduled_emission(
+            reward_math::curve_rate(pool.reward_token_per_sec, curve, pool.total_shares),
+            schedule,
+            pool.last_reward_timestamp,
+            current_timestamp,
+        )?;
+        pool.accumulated_reward_per_share = reward_math::accrue_income(
+            pool.accumulated_reward_per_share,
+            income,
+            pool.total_shares,
+        )?;
+        emitted = emitted.checked_add(income).ok_or(ErrorCode::Overflow)?;
 
-            // 累加每份奖励的累计值
-            pool.accumulated_reward_per_share = pool
-                .accumulated_reward_per_share
-                .checked_add(reward_per_share)
-                .unwrap_or(pool.accumulated_reward_per_share); // 防止溢出
+        // 合作方奖励流按各自速率累加
+        for stream in pool.reward_streams.iter_mut() {
+            if !stream.is_active() {
+                continue;
+            }
+            stream.accumulated_reward_per_share = reward_math::accrue(
+                stream.accumulated_reward_per_share,
+                stream.reward_token_per_sec,
+                time_diff,
+                pool.total_shares,
+            )?;
         }
 
         // 更新最后奖励时间戳为当前时间戳
         pool.last_reward_timestamp = current_timestamp;
     }
+
+    // 新排放的奖励计入未支付总额，取整误差也一并计入，只会多留不会少留
+    let emitted = u64::try_from(emitted).map_err(|_| ErrorCode::Overflow)?;
+    staking_instance.reward_liability = staking_instance
+        .reward_liability
+        .checked_add(with_referral_allowance(emitted))
+        .ok_or(ErrorCode::Overflow)?;
+    Ok(())
+}
+
+/// 奖励加上领取时可能额外支付给上级的 10% 推荐奖励
+pub fn with_referral_allowance(amount: u64) -> u64 {
+    amount.saturating_add(amount / 10)
+}
+
+/// 奖励已支付或被放弃时，从未支付总额中扣除（连同推荐奖励的预留）
+pub fn release_reward_liability(staking_instance: &mut StakingInstance, amount: u64) {
+    staking_instance.reward_liability = staking_instance
+        .reward_liability
+        .saturating_sub(with_referral_allowance(amount));
+}
+
+/// 迁移前没有记录未支付总额，用各池 总份额 × 每份累计奖励 作为上界：
+/// 每个仓位未领取的奖励不超过 份额 × 当前累计值 / 精度，已解押仓位的未领取奖励已清零
+pub fn reward_liability_bound(staking_instance: &StakingInstance) -> Result<u64> {
+    let mut bound = 0u64;
+    for pool in staking_instance.pools.iter() {
+        let owed =
+            reward_math::accumulated_reward(pool.total_shares, pool.accumulated_reward_per_share)?;
+        bound = bound.saturating_add(u64::try_from(owed).unwrap_or(u64::MAX));
+    }
+    Ok(with_referral_allowance(bound))
+}
+
+/// 所有池子都没有份额时已不欠任何用户奖励（解押时未领取的奖励随质押清零），
+/// 把迁移时按上界估算的部分以及记账取整遗留的零头一并清零
+pub fn clear_reward_liability(staking_instance: &mut StakingInstance) -> Result<()> {
+    if staking_instance
+        .pools
+        .iter()
+        .any(|pool| pool.total_shares > 0)
+    {
+        return Err(ErrorCode::ActiveStakesRemain.into());
+    }
+    staking_instance.reward_liability = 0;
+    Ok(())
+}
+
+/// sweep 可以从 source 账户取走的数量：质押代币账户保留所有池的总份额，奖励代币账户保留未支付奖励，
+/// 合作方奖励流的金库不记录未支付总额，奖励流启用期间不能取回
+pub fn sweepable_amount(
+    staking_instance: &StakingInstance,
+    source: &Pubkey,
+    source_mint: &Pubkey,
+    balance: u64,
+) -> Result<u64> {
+    let is_stream_vault = staking_instance.pools.iter().any(|pool| {
+        pool.reward_streams
+            .iter()
+            .any(|stream| stream.is_active() && stream.reward_vault == *source)
+    });
+    if is_stream_vault {
+        return Err(ErrorCode::RewardStreamVaultNotSweepable.into());
+    }
+
+    let mut protected = 0u64;
+    if *source_mint == staking_instance.staking_token_mint {
+        protected = staking_instance
+            .pools
+            .iter()
+            .fold(0u64, |total, pool| total.saturating_add(pool.total_shares));
+    }
+    if *source_mint == staking_instance.reward_token_mint {
+        protected = protected.saturating_add(staking_instance.reward_liability);
+    }
+    Ok(balance.saturating_sub(protected))
 }
 
 pub fn store_pending_reward(
@@ -82,13 +197,17 @@ pub fn store_pending_reward(
     // 获取对应池子
     let pool = &staking_instance.pools[stake_type];
 
+    // 合作方奖励流的待领取奖励
+    store_pending_stream_rewards(pool, staked_info)?;
+
     // 计算用户在该池子的待领取奖励
-    let pending_reward = (staked_info.deposited_amount as u128)
-        .checked_mul(pool.accumulated_reward_per_share as u128)
-        .and_then(|v| v.checked_div(COMPUTATION_DECIMALS as u128))
-        .and_then(|v| v.checked_sub(staked_info.reward_debt as u128))
-        .unwrap_or(0) as u64; // 最终将结果转换回 u64 类型，如果需要
-                              // 如果待领取奖励为 0，直接返回
+    let pending_reward = reward_math::pending_reward(
+        staked_info.deposited_amount,
+        pool.accumulated_reward_per_share,
+        staked_info.reward_debt,
+    )?;
+
+    // 如果待领取奖励为 0，直接返回
     if pending_reward == 0 {
         return Ok(());
     }
@@ -97,13 +216,88 @@ pub fn store_pending_reward(
     staked_info.accumulated_reward = staked_info
         .accumulated_reward
         .checked_add(pending_reward)
-        .unwrap_or(staked_info.accumulated_reward); // 防止溢出
+        .ok_or(ErrorCode::Overflow)?;
 
     // 更新用户的 reward_debt 为最新的池子状态
-    staked_info.reward_debt = (staked_info.deposited_amount as u128)
-        .checked_mul(pool.accumulated_reward_per_share as u128)
-        .and_then(|v| v.checked_div(COMPUTATION_DECIMALS as u128))
-        .unwrap_or(staked_info.reward_debt as u128) as u64;
+    staked_info.reward_debt = reward_math::accumulated_reward(
+        staked_info.deposited_amount,
+        pool.accumulated_reward_per_share,
+    )?;
+    Ok(())
+}
+
+pub fn store_pending_stream_rewards(pool: &StakingPool, staked_info: &mut Staked) -> Result<()> {
+    for (stream_index, stream) in pool.reward_streams.iter().enumerate() {
+        if !stream.is_active() {
+            continue;
+        }
+        let pending_reward = reward_math::pending_reward(
+            staked_info.deposited_amount,
+            stream.accumulated_reward_per_share,
+            staked_info.stream_reward_debt[stream_index],
+        )?;
+
+        staked_info.stream_accumulated_reward[stream_index] = staked_info.stream_accumulated_reward
+            [stream_index]
+            .checked_add(pending_reward)
+            .ok_or(ErrorCode::Overflow)?;
+        staked_info.stream_reward_debt[stream_index] = reward_math::accumulated_reward(
+            staked_info.deposited_amount,
+            stream.accumulated_reward_per_share,
+        )?;
+    }
+    Ok(())
+}
+
+/// 取出一条奖励流的待领取奖励并清零，没有可领取的奖励时报错
+pub fn take_stream_reward(staked_info: &mut Staked, stream_index: usize) -> Result<u64> {
+    let reward = staked_info.stream_accumulated_reward[stream_index];
+    if reward == 0 {
+        return Err(ErrorCode::NoRewardsToClaim.into());
+    }
+    staked_info.stream_accumulated_reward[stream_index] = 0;
+    Ok(reward)
+}
+
+/// 解除质押时放弃尚未领取的合作方奖励并清零，返回各奖励流放弃的数量。
+/// 合作方金库余额不足时用户仍能取回本金；想要领取的用户在同一笔交易中先调用 claim_all_stream_rewards 即可
+pub fn forfeit_stream_rewards(staked_info: &mut Staked) -> [u64; MAX_REWARD_STREAMS] {
+    core::mem::take(&mut staked_info.stream_accumulated_reward)
+}
+
+/// 停用奖励流：先结算到当前时间，再清空 mint、金库和速率，之后不再累计也不能再领取。
+/// 累计值保留，旧质押者的奖励债务仍按它计算，所以该槽位不能再挂载新的奖励流
+pub fn deactivate_stream(
+    staking_instance: &mut StakingInstance,
+    stake_type: u64,
+    stream_index: u64,
+    current_timestamp: u64,
+) -> Result<()> {
+    if stake_type >= staking_instance.pools.len() as u64 {
+        return Err(ErrorCode::InvalidStakeType.into());
+    }
+    if stream_index >= MAX_REWARD_STREAMS as u64 {
+        return Err(ErrorCode::InvalidRewardStreamIndex.into());
+    }
+    if !staking_instance.pools[stake_type as usize].reward_streams[stream_index as usize]
+        .is_active()
+    {
+        return Err(ErrorCode::RewardStreamNotActive.into());
+    }
+    update_reward_pool(current_timestamp, staking_instance)?;
+    let stream =
+        &mut staking_instance.pools[stake_type as usize].reward_streams[stream_index as usize];
+    stream.reward_token_mint = Pubkey::default();
+    stream.reward_vault = Pubkey::default();
+    stream.reward_token_per_sec = 0;
+    Ok(())
+}
+
+/// 停用过的槽位累计值不为 0，挂载新的奖励流会把旧奖励流的未结算部分按新代币支付
+pub fn check_stream_slot_reusable(stream: &RewardStream) -> Result<()> {
+    if !stream.is_active() && stream.accumulated_reward_per_share > 0 {
+        return Err(ErrorCode::RewardStreamRetired.into());
+    }
     Ok(())
 }
 
@@ -111,54 +305,749 @@ pub fn update_reward_debt(
     staking_instance: &mut StakingInstance,
     user_instance: &mut User,
     staked_info_number: u64, // 用户质押池的索引
-) {
+) -> Result<()> {
     // 获取用户对应的质押信息
     let staked_info = &mut user_instance.staked_info[staked_info_number as usize];
 
     // 确保该质押池已被质押
     if !staked_info.is_staked {
-        return; // 如果该质押池没有质押，直接返回
+        return Ok(()); // 如果该质押池没有质押，直接返回
     }
     // 获取质押类型对应的池子
     let stake_type = staked_info.stake_type as usize;
     // 检查 stake_type 是否为有效池子索引
     if stake_type >= staking_instance.pools.len() {
-        return; // 无效的池子索引，直接返回
+        return Err(ErrorCode::InvalidStakeType.into());
     }
 
     // 获取对应池子
     let pool = &staking_instance.pools[stake_type];
 
-    // msg!(
-    //     "Hello world!!",
-    //     pool.accumulated_reward_per_share,
-    //     staked_info.deposited_amount
-    // );
     // 更新该质押池的 reward_debt
-    // msg!(
-    //     "staked_info
-    //     .deposited_amount",
-    //     staked_info.deposited_amount
-    // );
-    // msg!("accumulated_reward_per_share", accumulated_reward_per_share);
+    staked_info.reward_debt = reward_math::accumulated_reward(
+        staked_info.deposited_amount,
+        pool.accumulated_reward_per_share,
+    )?;
+
+    // 更新各合作方奖励流的 reward_debt
+    for (stream_index, stream) in pool.reward_streams.iter().enumerate() {
+        staked_info.stream_reward_debt[stream_index] = reward_math::accumulated_reward(
+            staked_info.deposited_amount,
+            stream.accumulated_reward_per_share,
+        )?;
+    }
+    Ok(())
+}
+
+/// 计算质押实例的派生地址，种子为 (staking_token_mint, reward_token_mint, instance_id)；最初的单例实例只有 STAKING_SEED
+pub fn find_staking_instance_address(
+    staking_instance: &StakingInstance,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            crate::STAKING_SEED,
+            staking_instance.staking_token_mint_seed(),
+            staking_instance.reward_token_mint_seed(),
+            &staking_instance.instance_id_seed(),
+        ],
+        program_id,
+    )
+}
 
-    staked_info.reward_debt = (staked_info.deposited_amount as u128)
-        .checked_mul(pool.accumulated_reward_per_share as u128)
-        .and_then(|v| v.checked_div(COMPUTATION_DECIMALS as u128))
-        .unwrap_or(0) as u64;
+/// 计算实例专属的 LP/奖励金库权限 PDA，每个实例的金库互不共享；最初的单例实例沿用全局的 LPTOKEN_SEED 地址
+pub fn find_lp_authority_address(
+    staking_instance: &StakingInstance,
+    staking_instance_key: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            crate::LPTOKEN_SEED,
+            staking_instance.lp_authority_seed(staking_instance_key),
+        ],
+        program_id,
+    )
+}
+
+/// 用实例中保存的 bump 直接计算 LP 权限 PDA，只需一次 create_program_address
+pub fn lp_authority_address(
+    staking_instance: &StakingInstance,
+    staking_instance_key: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<Pubkey> {
+    Pubkey::create_program_address(
+        &[
+            crate::LPTOKEN_SEED,
+            staking_instance.lp_authority_seed(staking_instance_key),
+            &[staking_instance.lp_authority_bump],
+        ],
+        program_id,
+    )
+    .map_err(|_| ErrorCode::PdaAccountIsNotMatch.into())
+}
+
+/// 按旧布局读取账户：先校验新结构的判别符（判别符只取决于结构名，迁移前后不变），再按旧结构反序列化
+pub fn read_legacy_account<T: anchor_lang::Discriminator, L: AnchorDeserialize>(
+    account: &AccountInfo,
+) -> Result<L> {
+    let data = account.try_borrow_data()?;
+    if data.len() < 8 || data[..8] != T::DISCRIMINATOR {
+        return Err(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+    }
+    L::deserialize(&mut &data[8..])
+        .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+}
+
+/// 把读出的实例逐版本升级到当前布局，并校验账户地址与升级后的种子一致
+pub fn upgrade_staking_instance(
+    staking_instance: &mut StakingInstance,
+    staking_instance_key: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<()> {
+    if staking_instance.version >= STAKING_INSTANCE_VERSION {
+        return Err(ErrorCode::AccountAlreadyMigrated.into());
+    }
+
+    let (expected_staking_address, bump) =
+        find_staking_instance_address(staking_instance, program_id);
+    if *staking_instance_key != expected_staking_address {
+        return Err(ErrorCode::InvalidStakingInstance.into());
+    }
+
+    // 版本 0 -> 1：新增的 version/reserved 均为 0，无需补齐其他字段
+    // 版本 1 -> 2：补齐 bump，迁移只执行一次，这里的 find_program_address 不影响日常指令
+    // 版本 2 -> 3：新增的白名单开关为 0，即默认关闭
+    // 版本 3 -> 4：新增的质押数量限制为 0，即不限制
+    // 版本 4 -> 5：新增的排放计划为 0，即保持恒定速率
+    // 版本 5 -> 6：账户扩容，新增的收益率曲线为 0，即不启用
+    // 版本 6 -> 7：未支付奖励总额无法从实例中还原，按各池份额和累计值取上界
+    if staking_instance.version < 7 {
+        staking_instance.reward_liability = reward_liability_bound(staking_instance)?;
+    }
+    staking_instance.bump = bump;
+    staking_instance.lp_authority_bump =
+        find_lp_authority_address(staking_instance, staking_instance_key, program_id).1;
+    staking_instance.version = STAKING_INSTANCE_VERSION;
+    Ok(())
+}
+
+/// 把读出的用户账户升级到当前布局，并校验账户地址由所属实例派生
+pub fn upgrade_user(
+    user_instance: &mut User,
+    staking_instance_key: &Pubkey,
+    user_key: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<()> {
+    if user_instance.version >= USER_VERSION {
+        return Err(ErrorCode::AccountAlreadyMigrated.into());
+    }
+
+    let (expected_user_address, bump) = Pubkey::find_program_address(
+        &[
+            crate::USER_SEED,
+            staking_instance_key.as_ref(),
+            user_instance.user_address.as_ref(),
+        ],
+        program_id,
+    );
+    if *user_key != expected_user_address {
+        return Err(ErrorCode::InvalidUserInstance.into());
+    }
+
+    // 版本 1 -> 2：补齐 bump
+    // 版本 2 -> 3：账户扩容，新增的质押 slot 为 0。未迁移的账户无法参与任何指令，
+    // 所以 slot 为 0 的质押都早于升级，对之后的投票快照均有效
+    user_instance.bump = bump;
+    user_instance.version = USER_VERSION;
+    Ok(())
+}
+
+/// 调整账户空间，新增字节清零，新空间所需的租金差额由 payer 补足
+pub fn realloc_account<'info>(
+    account: &AccountInfo<'info>,
+    new_space: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let lamports_required = Rent::get()?
+        .minimum_balance(new_space)
+        .saturating_sub(account.lamports());
+    if lamports_required > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            lamports_required,
+        )?;
+    }
+    account.realloc(new_space, true)?;
+    Ok(())
+}
+
+/// 读取待迁移的账户：先扩容到 new_space（只增不减，新增字节为 0），再按当前结构反序列化。
+/// 新字段只追加在末尾（或从预留字节中划出），所以旧账户读出的 version 为 0，新字段为默认值 0
+pub fn load_for_migration<'info, T: AccountDeserialize>(
+    account: &AccountInfo<'info>,
+    new_space: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<T> {
+    realloc_account(
+        account,
+        new_space.max(account.data_len()),
+        payer,
+        system_program,
+    )?;
+    let data = account.try_borrow_data()?;
+    T::try_deserialize(&mut &data[..])
+}
+
+/// 把迁移后的账户（含判别符）写回账户数据
+pub fn write_account<T: AccountSerialize>(account: &AccountInfo, value: &T) -> Result<()> {
+    let mut data = account.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data[..];
+    value.try_serialize(&mut writer)
+}
+
+/// 校验领取奖励流时传入的 mint、金库和用户 token 账户是否与奖励流配置一致
+pub fn check_reward_stream_accounts(
+    stream: &RewardStream,
+    reward_token_mint: &Pubkey,
+    reward_vault: &Pubkey,
+    user_token_mint: &Pubkey,
+) -> Result<()> {
+    if !stream.is_active() {
+        return Err(ErrorCode::RewardStreamNotActive.into());
+    }
+    if stream.reward_token_mint != *reward_token_mint
+        || stream.reward_token_mint != *user_token_mint
+    {
+        return Err(ErrorCode::MintAccountIsNotMatch.into());
+    }
+    if stream.reward_vault != *reward_vault {
+        return Err(ErrorCode::RewardVaultIsNotMatch.into());
+    }
+    Ok(())
+}
+
+/// 校验时区偏移不超过 ±MAX_UTC_OFFSET_SECONDS
+pub fn check_utc_offset(utc_offset_seconds: i32) -> Result<()> {
+    if utc_offset_seconds.abs() > MAX_UTC_OFFSET_SECONDS {
+        return Err(ErrorCode::InvalidUtcOffset.into());
+    }
+    Ok(())
 }
 
 pub fn is_authorized(user: &Pubkey, authority: &Pubkey) -> bool {
     user == authority
 }
+
+/// 计算多签管理员集合的执行权限 PDA，把它设为 authority 后管理指令只能通过提案执行
+pub fn find_admin_authority_address(admin_set: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[crate::ADMIN_AUTHORITY_SEED, admin_set.as_ref()],
+        program_id,
+    )
+}
+
+/// 校验成员列表和门限：1 <= 门限 <= 成员数 <= MAX_ADMIN_MEMBERS，成员不能重复，也不能是默认公钥
+pub fn check_admin_members(members: &[Pubkey], threshold: u8) -> Result<()> {
+    if members.is_empty() || members.len() > MAX_ADMIN_MEMBERS {
+        return Err(ErrorCode::InvalidAdminMembers.into());
+    }
+    for (index, member) in members.iter().enumerate() {
+        if *member == Pubkey::default() || members[..index].contains(member) {
+            return Err(ErrorCode::InvalidAdminMembers.into());
+        }
+    }
+    if threshold == 0 || threshold as usize > members.len() {
+        return Err(ErrorCode::InvalidAdminThreshold.into());
+    }
+    Ok(())
+}
+
+/// 写入成员和门限，未使用的位置清零
+pub fn store_admin_members(
+    admin_set: &mut AdminSet,
+    members: &[Pubkey],
+    threshold: u8,
+) -> Result<()> {
+    check_admin_members(members, threshold)?;
+    admin_set.members = [Pubkey::default(); MAX_ADMIN_MEMBERS];
+    admin_set.members[..members.len()].copy_from_slice(members);
+    admin_set.member_count = members.len() as u8;
+    admin_set.threshold = threshold;
+    Ok(())
+}
+
+/// 成员在集合中的下标，即批准位图中对应的位
+pub fn admin_member_index(admin_set: &AdminSet, key: &Pubkey) -> Option<usize> {
+    admin_set.members[..admin_set.member_count as usize]
+        .iter()
+        .position(|member| member == key)
+}
+
+pub fn approval_count(approvals: u16) -> u32 {
+    approvals.count_ones()
+}
+
+/// 记录成员的批准，重复批准返回错误
+pub fn approve(proposal: &mut Proposal, member_index: usize) -> Result<()> {
+    let bit = 1u16 << member_index;
+    if proposal.approvals & bit != 0 {
+        return Err(ErrorCode::ProposalAlreadyApproved.into());
+    }
+    proposal.approvals |= bit;
+    Ok(())
+}
+
+/// 把提案指令写入定长数组，超过上限返回错误
+pub fn store_proposal_instruction(
+    proposal: &mut Proposal,
+    program_id: Pubkey,
+    accounts: &[ProposalAccount],
+    data: &[u8],
+) -> Result<()> {
+    if accounts.len() > MAX_PROPOSAL_ACCOUNTS || data.len() > MAX_PROPOSAL_DATA_LEN {
+        return Err(ErrorCode::ProposalTooLarge.into());
+    }
+    proposal.program_id = program_id;
+    proposal.accounts = [ProposalAccount::default(); MAX_PROPOSAL_ACCOUNTS];
+    proposal.accounts[..accounts.len()].copy_from_slice(accounts);
+    proposal.account_count = accounts.len() as u8;
+    proposal.data = [0; MAX_PROPOSAL_DATA_LEN];
+    proposal.data[..data.len()].copy_from_slice(data);
+    proposal.data_len = data.len() as u16;
+    Ok(())
+}
+
+/// 还原提案指令，admin_authority 总是标记为签名者
+pub fn proposal_instruction(proposal: &Proposal, admin_authority: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: proposal.program_id,
+        accounts: proposal.accounts[..proposal.account_count as usize]
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.pubkey,
+                is_signer: account.is_signer || account.pubkey == *admin_authority,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: proposal.data[..proposal.data_len as usize].to_vec(),
+    }
+}
+
+/// 提案可以排队和执行的条件：创建后成员和门限未变更，且批准数达到门限
+pub fn check_proposal_executable(admin_set: &AdminSet, proposal: &Proposal) -> Result<()> {
+    if proposal.generation != admin_set.generation {
+        return Err(ErrorCode::StaleProposal.into());
+    }
+    if approval_count(proposal.approvals) < admin_set.threshold as u32 {
+        return Err(ErrorCode::NotEnoughApprovals.into());
+    }
+    Ok(())
+}
+
+/// 只有提案人可以撤回自己的提案，单个成员不能否决其他人已获批准的提案。
+/// 成员变更后旧提案已无法执行，任何成员都可以关闭它并把租金退回提案人；
+/// 要否决他人的提案，需要达到门限的成员通过 set_admin_members 提案使其过期
+pub fn check_proposal_cancellable(
+    admin_set: &AdminSet,
+    proposal: &Proposal,
+    signer: &Pubkey,
+) -> Result<()> {
+    if *signer == proposal.proposer {
+        return Ok(());
+    }
+    if proposal.generation != admin_set.generation
+        && admin_member_index(admin_set, signer).is_some()
+    {
+        return Ok(());
+    }
+    Err(ErrorCode::NotProposer.into())
+}
+
+/// 实际使用的时间锁延迟，version 1 的集合读出 0，同样按最小延迟处理
+pub fn timelock_delay(admin_set: &AdminSet) -> u64 {
+    admin_set.delay_secs.max(MIN_TIMELOCK_DELAY_SECS)
+}
+
+pub fn check_timelock_delay(delay_secs: u64) -> Result<()> {
+    if !(MIN_TIMELOCK_DELAY_SECS..=MAX_TIMELOCK_DELAY_SECS).contains(&delay_secs) {
+        return Err(ErrorCode::InvalidTimelockDelay.into());
+    }
+    Ok(())
+}
+
+/// 把达到门限的提案放入时间锁队列，返回最早执行时间
+pub fn enqueue_proposal(
+    admin_set: &AdminSet,
+    proposal: &mut Proposal,
+    current_timestamp: u64,
+) -> Result<u64> {
+    check_proposal_executable(admin_set, proposal)?;
+    if proposal.eta != 0 {
+        return Err(ErrorCode::ProposalAlreadyQueued.into());
+    }
+    proposal.eta = current_timestamp
+        .checked_add(timelock_delay(admin_set))
+        .ok_or(ErrorCode::Overflow)?;
+    Ok(proposal.eta)
+}
+
+/// 时间锁到期后才能执行：必须已排队且当前时间不早于 eta
+pub fn check_timelock_elapsed(proposal: &Proposal, current_timestamp: u64) -> Result<()> {
+    if proposal.eta == 0 {
+        return Err(ErrorCode::ProposalNotQueued.into());
+    }
+    if current_timestamp < proposal.eta {
+        return Err(ErrorCode::TimelockNotElapsed.into());
+    }
+    Ok(())
+}
+/// 白名单模式下必须传入钱包的白名单条目，条目与钱包的对应关系由账户约束中的 seeds 校验
+pub fn check_allowlisted(
+    staking_instance: &StakingInstance,
+    allow_entry: Option<&AllowEntry>,
+) -> Result<()> {
+    if staking_instance.allowlist_enabled && allow_entry.is_none() {
+        return Err(ErrorCode::WalletNotAllowlisted.into());
+    }
+    Ok(())
+}
+/// 各池在固定时间之后停止接受新的质押，无效的 stake_type 视为已经结束。
+/// 启用 `fast-time` 特性时改为每个周期内的分钟级截止时间
+pub fn staking_ended(stake_type: u64, current_timestamp: u64) -> bool {
+    let index = stake_type as usize;
+    if index >= STAKING_DEADLINES.len() {
+        return true;
+    }
+    if cfg!(feature = "fast-time") {
+        current_timestamp % FAST_TIME_CYCLE_SECONDS > FAST_TIME_STAKING_DEADLINES[index]
+    } else {
+        current_timestamp > STAKING_DEADLINES[index]
+    }
+}
+/// 各质押池当前的奖励累计状态，按 stake_type 顺序
+pub fn pool_snapshots(staking_instance: &StakingInstance, timestamp: u64) -> Vec<PoolSnapshot> {
+    staking_instance
+        .pools
+        .iter()
+        .zip(staking_instance.emission_schedules.iter())
+        .zip(staking_instance.rate_curves.iter())
+        .map(|((pool, schedule), curve)| PoolSnapshot::new(pool, schedule, curve, timestamp))
+        .collect()
+}
+/// 用户第一个空闲的 staked_info 槽位
+pub fn first_free_slot(user: &User) -> Option<u64> {
+    user.staked_info
+        .iter()
+        .position(|staked| !staked.is_staked)
+        .map(|index| index as u64)
+}
+/// 把已到账的质押记入用户的槽位：检查数量限制，写入仓位，再更新池子份额和奖励债务
+pub fn record_stake(
+    staking_instance: &mut StakingInstance,
+    user_instance: &mut User,
+    staked_info_index: u64,
+    stake_type: u64,
+    amount: u64,
+    current_timestamp: u64,
+) -> Result<()> {
+    let index = staked_info_index as usize;
+
+    // 按实际到账数量检查池子的质押数量限制
+    check_stake_limits(
+        &staking_instance.stake_limits[stake_type as usize],
+        staking_instance.pools[stake_type as usize].total_shares,
+        user_pool_deposits(user_instance, stake_type),
+        amount,
+    )?;
+
+    let stake_end_time = lock_end_timestamp(
+        current_timestamp,
+        effective_lock_term(
+            stake_type,
+            staking_instance.pools[stake_type as usize].lock_term,
+        ),
+        staking_instance.utc_offset_seconds,
+    );
+
+    // 更新用户账户
+    user_instance.total_deposited_amount = user_instance
+        .total_deposited_amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    let staked_info = &mut user_instance.staked_info[index];
+
+    staked_info.deposited_amount = staked_info
+        .deposited_amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+    staked_info.stake_type = stake_type;
+    staked_info.is_staked = true;
+    staked_info.stake_start_time = current_timestamp;
+    staked_info.stake_end_time = stake_end_time;
+
+    // 先按旧份额结算奖励池，新份额只参与之后的奖励，结果与同一秒内的交易顺序无关
+    update_reward_pool(current_timestamp, staking_instance)?;
+
+    // 更新质押池的总份额
+    let pool = &mut staking_instance.pools[stake_type as usize];
+    pool.total_shares = pool
+        .total_shares
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    // 更新用户奖励债务
+    update_reward_debt(staking_instance, user_instance, staked_info_index)
+}
+/// 单个质押在 current_timestamp 的投票权：按剩余锁仓时间加权，剩余达到 VOTE_MAX_LOCK_SECS 为满权重，
+/// 到 stake_end_time 线性衰减为 0
+pub fn staked_voting_power(staked: &Staked, current_timestamp: u64) -> u64 {
+    if !staked.is_staked || staked.stake_end_time <= current_timestamp {
+        return 0;
+    }
+    let remaining = (staked.stake_end_time - current_timestamp).min(VOTE_MAX_LOCK_SECS);
+    // 权重不超过 1，结果不会超过质押数量
+    (staked.deposited_amount as u128 * remaining as u128 / VOTE_MAX_LOCK_SECS as u128) as u64
+}
+
+/// 用户在 current_timestamp 的投票权，只计入 snapshot_slot 及之前进入的质押，
+/// 快照之后临时质押的 LP 不能参与投票。slot 为 0 的槽位在 version 3 之前质押，早于任何快照
+pub fn user_voting_power(user: &User, snapshot_slot: u64, current_timestamp: u64) -> Result<u64> {
+    user.staked_info
+        .iter()
+        .zip(user.stake_slots.iter())
+        .filter(|(_, stake_slot)| **stake_slot <= snapshot_slot)
+        .try_fold(0u64, |total, (staked, _)| {
+            total
+                .checked_add(staked_voting_power(staked, current_timestamp))
+                .ok_or(ErrorCode::Overflow.into())
+        })
+}
+
+/// 用户在某个池中进行中的质押总量
+pub fn user_pool_deposits(user: &User, stake_type: u64) -> u64 {
+    user.staked_info
+        .iter()
+        .filter(|staked| staked.is_staked && staked.stake_type == stake_type)
+        .fold(0u64, |total, staked| {
+            total.saturating_add(staked.deposited_amount)
+        })
+}
+/// 检查一笔质押是否满足池子的数量限制，上限为 0 表示不限制
+pub fn check_stake_limits(
+    limits: &StakeLimits,
+    pool_total_shares: u64,
+    user_pool_deposits: u64,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Err(ErrorCode::ZeroStakeAmount.into());
+    }
+    if amount < limits.min_stake {
+        return Err(ErrorCode::StakeBelowMinimum.into());
+    }
+    if limits.max_stake_per_user > 0
+        && user_pool_deposits
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?
+            > limits.max_stake_per_user
+    {
+        return Err(ErrorCode::UserStakeCapExceeded.into());
+    }
+    if limits.pool_capacity > 0
+        && pool_total_shares
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?
+            > limits.pool_capacity
+    {
+        return Err(ErrorCode::PoolCapacityExceeded.into());
+    }
+    Ok(())
+}
+/// 用户账户可以关闭的条件：没有进行中的质押，也没有未领取的 GDTC 或合作方奖励
+pub fn check_user_closable(user: &User) -> Result<()> {
+    for staked in user.staked_info.iter() {
+        if staked.is_staked {
+            return Err(ErrorCode::UserHasActiveStaking.into());
+        }
+        if staked.accumulated_reward > 0 || staked.stream_accumulated_reward.iter().any(|r| *r > 0)
+        {
+            return Err(ErrorCode::UserHasUnclaimedRewards.into());
+        }
+    }
+    Ok(())
+}
 pub fn can_unstake(staked: &Staked, current_timestamp: u64) -> bool {
     staked.is_staked && staked.stake_end_time <= current_timestamp
 }
 
-pub fn calculate_referral_reward(user: &User, amount: u64) -> u64 {
-    // 计算推荐奖励，假设为10%
-    let referral_reward = amount * 10 / 100;
-    referral_reward
+/// 计算提前解押时实际适用的基点，启用递减时按剩余锁定时间线性递减，到期为 0
+pub fn early_unstake_bps(
+    bps: u16,
+    decays: bool,
+    stake_start_time: u64,
+    stake_end_time: u64,
+    current_timestamp: u64,
+) -> u64 {
+    if current_timestamp >= stake_end_time {
+        return 0;
+    }
+    if !decays || stake_end_time <= stake_start_time {
+        return bps as u64;
+    }
+    let remaining = stake_end_time - current_timestamp.max(stake_start_time);
+    let duration = stake_end_time - stake_start_time;
+    (bps as u128 * remaining as u128 / duration as u128) as u64
+}
+
+/// 将提前解押放弃的奖励按份额计入池子的 `accumulated_reward_per_share`，
+/// 由池内剩余质押者按比例分享，返回实际分配出去的数量（向下取整后的余数留在奖励账户中）。
+/// 池内已无份额时无人可分，返回 0，放弃的奖励同样留在奖励账户中。
+pub fn redistribute_to_pool(pool: &mut StakingPool, amount: u64) -> Result<u64> {
+    if amount == 0 || pool.total_shares == 0 {
+        return Ok(0);
+    }
+    let (accumulated_reward_per_share, redistributed) =
+        reward_math::distribute(pool.accumulated_reward_per_share, amount, pool.total_shares)?;
+    pool.accumulated_reward_per_share = accumulated_reward_per_share;
+    Ok(redistributed)
+}
+
+/// 按基点计算份额，向下取整
+pub fn apply_bps(amount: u64, bps: u64) -> u64 {
+    (amount as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
+/// 领取奖励时额外支付给上级的推荐奖励：上级质押超过 REFERRAL_MIN_SUPERIOR_DEPOSIT 时为奖励的 10%，否则为 0
+pub fn calculate_referral_reward(superior: &User, amount: u64) -> u64 {
+    if superior.total_deposited_amount > REFERRAL_MIN_SUPERIOR_DEPOSIT {
+        amount / 10
+    } else {
+        0
+    }
+}
+
+/// 提前解押的结算结果，early_unstake 据此转账
+#[derive(Debug, PartialEq, Eq)]
+pub struct EarlyUnstakeSettlement {
+    pub lp_returned: u64,                                    // 退还给用户的 LP
+    pub lp_penalty: u64,                                     // 转入国库的 LP 罚金
+    pub reward_payout: u64,                                  // 支付给用户的 GDTC 奖励
+    pub referral_reward: u64,                                // 支付给上级的推荐奖励
+    pub forfeited_reward: u64,                               // 放弃的 GDTC 奖励
+    pub redistributed_reward: u64,                           // 放弃的奖励中分给剩余质押者的部分
+    pub forfeited_stream_rewards: [u64; MAX_REWARD_STREAMS], // 放弃的各合作方奖励流奖励
+}
+
+/// 提前解押的记账部分（不含转账）：结算奖励，按池子配置计算 LP 罚金和放弃的奖励，
+/// 扣除池子份额后分配放弃的奖励，最后重置质押槽位
+pub fn record_early_unstake(
+    staking_instance: &mut StakingInstance,
+    user_instance: &mut User,
+    superior: &User,
+    staked_info_index: u64,
+    current_timestamp: u64,
+) -> Result<EarlyUnstakeSettlement> {
+    let index = staked_info_index as usize;
+    if !user_instance.staked_info[index].is_staked {
+        return Err(ErrorCode::NoStakingToCancel.into());
+    }
+    // 已到期的质押走 claim_rewards + cancel_staking 的正常流程
+    if current_timestamp >= user_instance.staked_info[index].stake_end_time {
+        return Err(ErrorCode::StakingAlreadyMatured.into());
+    }
+
+    let stake_type = user_instance.staked_info[index].stake_type as usize;
+    let pool = &staking_instance.pools[stake_type];
+    if !pool.early_unstake_enabled {
+        return Err(ErrorCode::EarlyUnstakeDisabled.into());
+    }
+    let staked_info = &user_instance.staked_info[index];
+    let penalty_bps = early_unstake_bps(
+        pool.penalty_bps,
+        pool.penalty_decays,
+        staked_info.stake_start_time,
+        staked_info.stake_end_time,
+        current_timestamp,
+    );
+    let reward_forfeit_bps = early_unstake_bps(
+        pool.reward_forfeit_bps,
+        pool.penalty_decays,
+        staked_info.stake_start_time,
+        staked_info.stake_end_time,
+        current_timestamp,
+    );
+
+    // 结算到当前时间的奖励
+    update_reward_pool(current_timestamp, staking_instance)?;
+    store_pending_reward(staking_instance, user_instance, staked_info_index)?;
+    let forfeited_stream_rewards = forfeit_stream_rewards(&mut user_instance.staked_info[index]);
+
+    let amount = user_instance.staked_info[index].deposited_amount;
+    let lp_penalty = apply_bps(amount, penalty_bps);
+    let lp_returned = amount - lp_penalty;
+
+    let unpaid_reward = user_instance.staked_info[index].accumulated_reward;
+    let forfeited_reward = apply_bps(unpaid_reward, reward_forfeit_bps);
+    let reward_payout = unpaid_reward - forfeited_reward;
+    // 与 claim_rewards 相同，上级按实际支付的奖励另得 10%
+    let referral_reward = calculate_referral_reward(superior, reward_payout);
+
+    // 更新池子份额并重置用户质押状态
+    let pool = &mut staking_instance.pools[stake_type];
+    pool.total_shares = pool
+        .total_shares
+        .checked_sub(amount)
+        .ok_or(ErrorCode::Underflow)?;
+
+    // 扣除离场者份额后再分配，离场者不参与分享自己放弃的奖励。
+    // LP 罚金不参与分配：LP 是本金，total_shares 必须与金库中的本金一一对应，
+    // 把罚金记给剩余质押者需要另一套按份额累计的本金账目，因此罚金总是转入国库
+    let redistributed_reward = if pool.redistribute_forfeits {
+        redistribute_to_pool(pool, forfeited_reward)?
+    } else {
+        0
+    };
+
+    user_instance.total_deposited_amount = user_instance
+        .total_deposited_amount
+        .checked_sub(amount)
+        .ok_or(ErrorCode::Underflow)?;
+
+    // 支付的和未重新分配的放弃奖励都不再欠用户，重新分配的部分仍计入未支付总额
+    release_reward_liability(staking_instance, unpaid_reward - redistributed_reward);
+
+    let staked_info = &mut user_instance.staked_info[index];
+    staked_info.receivedReward = 0;
+    staked_info.deposited_amount = 0;
+    staked_info.accumulated_reward = 0;
+    staked_info.is_staked = false;
+    staked_info.stake_type = 0;
+    staked_info.reward_debt = 0;
+    staked_info.stake_start_time = 0;
+    staked_info.stake_end_time = 0;
+    staked_info.can_cancel_stake = false;
+    staked_info.stream_reward_debt = [0; MAX_REWARD_STREAMS];
+    staked_info.stream_accumulated_reward = [0; MAX_REWARD_STREAMS];
+
+    Ok(EarlyUnstakeSettlement {
+        lp_returned,
+        lp_penalty,
+        reward_payout,
+        referral_reward,
+        forfeited_reward,
+        redistributed_reward,
+        forfeited_stream_rewards,
+    })
 }
 
 #[program]
@@ -166,27 +1055,46 @@ pub mod gdtc_staking {
     use super::*;
     pub fn initialize_staking(
         ctx: Context<InitializeStaking>,
+        instance_id: u64,
         reward_per_sec_3_months: u64,
         reward_per_sec_6_months: u64,
         reward_per_sec_12_months: u64,
         start_reward_timestamp: u64,
+        utc_offset_seconds: i32, // 到期日按该时区的日历计算，如东八区为 28800
     ) -> Result<()> {
         let staking_instance = &mut ctx.accounts.staking_instance;
 
+        check_utc_offset(utc_offset_seconds)?;
+        // 该编号表示最初的单例实例，种子与其他实例不同
+        if instance_id == LEGACY_INSTANCE_ID {
+            return Err(ErrorCode::ReservedInstanceId.into());
+        }
+
         // 设置基础字段
         staking_instance.authority = ctx.accounts.authority.key();
         staking_instance.reward_token_mint = ctx.accounts.reward_token_mint.key();
         staking_instance.staking_token_mint = ctx.accounts.staking_token_mint.key();
         staking_instance.lp_token_account = ctx.accounts.lp_token_account.key();
+        staking_instance.instance_id = instance_id;
+        staking_instance.utc_offset_seconds = utc_offset_seconds;
+        staking_instance.version = STAKING_INSTANCE_VERSION;
 
         let program_id = ctx.program_id; // 获取当前合约的程序ID
-                                         // 计算 staking_instance 的派生地址
+                                         // 计算本实例 LP 金库权限的派生地址
         let (expected_pda_address, bump_seed) =
-            Pubkey::find_program_address(&[crate::LPTOKEN_SEED.as_ref()], program_id);
+            find_lp_authority_address(staking_instance, &staking_instance.key(), program_id);
 
         if expected_pda_address != ctx.accounts.lp_token_account.owner.key() {
             return Err(ErrorCode::PdaAccountIsNotMatch.into());
         }
+        // 保存 bump，之后的指令直接用 `seeds + bump` 校验，省去 find_program_address
+        staking_instance.bump = ctx.bumps.staking_instance;
+        staking_instance.lp_authority_bump = bump_seed;
+        staking_instance.allowlist_enabled = false;
+        staking_instance.stake_limits = [StakeLimits::default(); 3];
+        staking_instance.emission_schedules = [EmissionSchedule::default(); 3];
+        staking_instance.rate_curves = [RateCurve::default(); 3];
+        staking_instance.reward_liability = 0;
 
         // 初始化 3 个质押池
         staking_instance.pools = [
@@ -196,6 +1104,13 @@ pub mod gdtc_staking {
                 accumulated_reward_per_share: 0,
                 last_reward_timestamp: start_reward_timestamp,
                 total_shares: 0,
+                reward_streams: Default::default(),
+                early_unstake_enabled: false,
+                penalty_bps: 0,
+                reward_forfeit_bps: 0,
+                penalty_decays: false,
+                redistribute_forfeits: false,
+                lock_term: LockTerm::CalendarMonths(3),
             },
             StakingPool {
                 stake_type: 1, // 6 个月
@@ -203,6 +1118,13 @@ pub mod gdtc_staking {
                 accumulated_reward_per_share: 0,
                 last_reward_timestamp: start_reward_timestamp,
                 total_shares: 0,
+                reward_streams: Default::default(),
+                early_unstake_enabled: false,
+                penalty_bps: 0,
+                reward_forfeit_bps: 0,
+                penalty_decays: false,
+                redistribute_forfeits: false,
+                lock_term: LockTerm::CalendarMonths(6),
             },
             StakingPool {
                 stake_type: 2, // 12 个月
@@ -210,6 +1132,13 @@ pub mod gdtc_staking {
                 accumulated_reward_per_share: 0,
                 last_reward_timestamp: start_reward_timestamp,
                 total_shares: 0,
+                reward_streams: Default::default(),
+                early_unstake_enabled: false,
+                penalty_bps: 0,
+                reward_forfeit_bps: 0,
+                penalty_decays: false,
+                redistribute_forfeits: false,
+                lock_term: LockTerm::CalendarMonths(12),
             },
         ];
         Ok(())
@@ -221,11 +1150,14 @@ pub mod gdtc_staking {
         if staking_instance.reward_token_mint != ctx.accounts.user_superior_token_account.mint {
             return Err(ErrorCode::MintAccountIsNotMatch.into());
         }
+        check_allowlisted(staking_instance, ctx.accounts.allow_entry.as_deref())?;
         user_instance.user_address = ctx.accounts.authority.key();
         // 初始化 User 结构体的字段
         user_instance.total_deposited_amount = 0; // 初始化为 0，表示用户没有存入任何质押
         user_instance.user_superior_token_account = ctx.accounts.user_superior_token_account.key(); // 设置上级 Token 账户地址
         user_instance.isinit = true; // 标记为已初始化
+        user_instance.version = USER_VERSION;
+        user_instance.bump = ctx.bumps.user_instance;
 
         // 初始化 staked_info 数组，所有的质押池信息都设为默认值
         for staked in user_instance.staked_info.iter_mut() {
@@ -238,6 +1170,8 @@ pub mod gdtc_staking {
             staked.stake_end_time = 0; // 初始质押结束时间为 0
             staked.receivedReward = 0; //初始化已领取收益
             staked.can_cancel_stake = false;
+            staked.stream_reward_debt = [0; MAX_REWARD_STREAMS];
+            staked.stream_accumulated_reward = [0; MAX_REWARD_STREAMS];
         }
 
         Ok(())
@@ -254,47 +1188,15 @@ pub mod gdtc_staking {
         let staking_instance = &mut ctx.accounts.staking_instance;
         let user_instance = &mut ctx.accounts.user_instance;
         let user_lp_token_account = &ctx.accounts.user_lp_token_account;
-        let gdtc_lp_in_account = &ctx.accounts.gdtc_lp_in_account;
 
         let clock = Clock::get().expect("Failed to get clock");
 
-        let program_id = ctx.program_id; // 获取当前合约的程序ID
-                                         // 计算 staking_instance 的派生地址
-        let (expected_staking_address, bump_seed) =
-            Pubkey::find_program_address(&[crate::STAKING_SEED.as_ref()], program_id);
-
-        // 确保 staking_instance 是由合约程序派生的
-        if staking_instance.key() != expected_staking_address {
-            return Err(ErrorCode::InvalidStakingInstance.into());
-        }
-        //用户账户验证
-        let (expected_user_address, bump_seed) = Pubkey::find_program_address(
-            &[
-                crate::USER_SEED.as_ref(),
-                staking_instance.key().as_ref(),
-                ctx.accounts.authority.key().as_ref(),
-            ],
-            program_id,
-        );
-        msg!(
-            "expected_user_address is: {},user_instance: {}",
-            expected_user_address.key(),
-            user_instance.key()
-        );
-        // 确保 staking_instance 是由合约程序派生的
-        if user_instance.key() != expected_user_address {
-            return Err(ErrorCode::InvalidUserInstance.into());
-        }
+        // 派生地址、Mint、金库和账户归属已由 EnterStaking 的账户约束校验
+        check_allowlisted(staking_instance, ctx.accounts.allow_entry.as_deref())?;
 
-        // 检查用户 LP Token 账户的 Mint 是否与质押池的 Mint 匹配
-        if staking_instance.staking_token_mint != user_lp_token_account.mint {
-            return Err(ErrorCode::MintAccountIsNotMatch.into());
-        }
-        if staking_instance.lp_token_account != gdtc_lp_in_account.key() {
-            return Err(ErrorCode::MintAccountIsNotMatch.into());
-        }
-        if user_instance.user_address != ctx.accounts.authority.key() {
-            return Err(ErrorCode::UserAccountIsNotMatch.into());
+        // 数量为 0 的质押会占用一个槽位且不产生份额
+        if lp_staking_number == 0 {
+            return Err(ErrorCode::ZeroStakeAmount.into());
         }
         // 检查用户 LP Token 账户余额是否足够
         if user_lp_token_account.amount < lp_staking_number {
@@ -311,32 +1213,7 @@ pub mod gdtc_staking {
         let current_timestamp = clock.unix_timestamp as u64;
 
         msg!("staking time is :{}", current_timestamp);
-        let is_end = match stake_type {
-            0 => {
-                if current_timestamp > 2358810461 {
-                    true
-                } else {
-                    false
-                }
-            }
-            1 => {
-                if current_timestamp > 2350861661 {
-                    true
-                } else {
-                    false
-                }
-            }
-            2 => {
-                if current_timestamp > 2335136861 {
-                    true
-                } else {
-                    false
-                }
-            }
-            _ => true, // 对于无效的 stake_type，直接认为已经结束
-        };
-
-        if is_end {
+        if staking_ended(stake_type, current_timestamp) {
             return Err(ErrorCode::StakingEnded.into());
         }
 
@@ -356,91 +1233,121 @@ pub mod gdtc_staking {
             return Err(ErrorCode::InvalidStakeType.into());
         }
 
-        let stake_end_time = generate_release_timestamps(current_timestamp, stake_type);
-
-        // 更新用户账户
-        user_instance.total_deposited_amount = user_instance
-            .total_deposited_amount
-            .checked_add(lp_staking_number)
-            .ok_or(ErrorCode::Overflow)?;
-
-        let staked_info = &mut user_instance.staked_info[index];
-
-        staked_info.deposited_amount = staked_info
-            .deposited_amount
-            .checked_add(lp_staking_number)
-            .ok_or(ErrorCode::Overflow)?;
-        staked_info.stake_type = stake_type;
-        staked_info.is_staked = true;
-        staked_info.stake_start_time = current_timestamp;
-        staked_info.stake_end_time = stake_end_time;
-
-        // 更新质押池的总份额
-        let pool = &mut staking_instance.pools[stake_type as usize];
-        pool.total_shares = pool
-            .total_shares
-            .checked_add(lp_staking_number)
-            .ok_or(ErrorCode::Overflow)?;
-
-        // 更新奖励池
-        update_reward_pool(current_timestamp, staking_instance);
-
-        // 更新用户奖励债务
-        update_reward_debt(staking_instance, user_instance, staked_info_index);
-
         // 转移 LP Token 到合约的 Vault
-        token::transfer(
+        // 以金库实际到账数量记账，兼容带转账手续费扩展的 Token-2022 mint
+        let vault_balance_before = ctx.accounts.gdtc_lp_in_account.amount;
+        token_interface::transfer_checked(
             ctx.accounts.into_transfer_to_vault_context(),
             lp_staking_number,
+            ctx.accounts.staking_token_mint.decimals,
         )?;
+        ctx.accounts.gdtc_lp_in_account.reload()?;
+        let received_amount = ctx
+            .accounts
+            .gdtc_lp_in_account
+            .amount
+            .checked_sub(vault_balance_before)
+            .ok_or(ErrorCode::Underflow)?;
 
+        // 记录进入质押的 slot，投票快照据此排除之后的质押
+        ctx.accounts.user_instance.stake_slots[staked_info_index as usize] = clock.slot;
+        record_stake(
+            &mut ctx.accounts.staking_instance,
+            &mut ctx.accounts.user_instance,
+            staked_info_index,
+            stake_type,
+            received_amount,
+            current_timestamp,
+        )
+    }
+
+    /// 受益人授权出资人为自己 deposit_for，未经授权的出资人无法占用受益人的槽位和质押额度
+    pub fn approve_depositor(ctx: Context<ApproveDepositor>, depositor: Pubkey) -> Result<()> {
+        let deposit_approval = &mut ctx.accounts.deposit_approval;
+        deposit_approval.staking_instance = ctx.accounts.staking_instance.key();
+        deposit_approval.beneficiary = ctx.accounts.beneficiary.key();
+        deposit_approval.depositor = depositor;
+        deposit_approval.version = DEPOSIT_APPROVAL_VERSION;
+        deposit_approval.bump = ctx.bumps.deposit_approval;
         Ok(())
     }
 
-    pub fn cancel_staking(ctx: Context<CancelStaking>, staked_info_index: u64) -> Result<()> {
-        // 获取相关账户
-        let staking_instance = &mut ctx.accounts.staking_instance;
-        let user_instance = &mut ctx.accounts.user_instance;
-        let user_lp_token_account = &mut ctx.accounts.user_lp_token_account;
-        let gdtc_lp_in_account = &ctx.accounts.gdtc_lp_in_account;
+    /// 受益人撤销对出资人的授权，只影响之后的 deposit_for
+    pub fn revoke_depositor(_ctx: Context<RevokeDepositor>) -> Result<()> {
+        // 由 `close = beneficiary` 在指令结束时转出租金并清空账户
+        Ok(())
+    }
 
-        let program_id = ctx.program_id; // 获取当前合约的程序ID
+    /// 为受益人质押：签名者出资，仓位记入受益人用户账户的第一个空闲槽位，
+    /// 之后的领取奖励和解押都由受益人自己完成。出资人须先经受益人 approve_depositor 授权
+    pub fn deposit_for(
+        ctx: Context<DepositFor>,
+        beneficiary: Pubkey, // 受益人钱包地址
+        amount: u64,         // 出资的 LP Token 数量
+        stake_type: u64,     // 质押池类型
+    ) -> Result<()> {
+        let staking_instance = &ctx.accounts.staking_instance;
 
-        let (expected_staking_address, bump_seed) =
-            Pubkey::find_program_address(&[crate::STAKING_SEED.as_ref()], program_id);
+        // 派生地址、Mint、金库、受益人账户和受益人的授权已由 DepositFor 的账户约束校验，白名单按受益人检查
+        check_allowlisted(staking_instance, ctx.accounts.allow_entry.as_deref())?;
 
-        // 确保 staking_instance 是由合约程序派生的
-        if staking_instance.key() != expected_staking_address {
-            return Err(ErrorCode::InvalidStakingInstance.into());
+        if amount == 0 {
+            return Err(ErrorCode::ZeroStakeAmount.into());
         }
-        //用户账户验证
-        let (expected_user_address, bump_seed) = Pubkey::find_program_address(
-            &[
-                crate::USER_SEED.as_ref(),
-                staking_instance.key().as_ref(),
-                ctx.accounts.authority.key().as_ref(),
-            ],
-            program_id,
-        );
-        msg!(
-            "expected_user_address is: {},user_instance: {}",
-            expected_user_address.key(),
-            user_instance.key()
-        );
-        // 确保 staking_instance 是由合约程序派生的
-        if user_instance.key() != expected_user_address {
-            return Err(ErrorCode::InvalidUserInstance.into());
-        }
-
-        if staking_instance.staking_token_mint != user_lp_token_account.mint {
-            return Err(ErrorCode::MintAccountIsNotMatch.into());
+        if ctx.accounts.payer_lp_token_account.amount < amount {
+            return Err(ErrorCode::TokenAccountBalanceInsufficient.into());
         }
-        if staking_instance.lp_token_account != gdtc_lp_in_account.key() {
-            return Err(ErrorCode::MintAccountIsNotMatch.into());
+        if stake_type >= staking_instance.pools.len() as u64 {
+            return Err(ErrorCode::InvalidStakeType.into());
         }
-        if user_instance.user_address != ctx.accounts.authority.key() {
-            return Err(ErrorCode::UserAccountIsNotMatch.into());
+        let clock = Clock::get().map_err(|_| ErrorCode::ClockUnavailable)?;
+        let current_timestamp = clock.unix_timestamp as u64;
+        if staking_ended(stake_type, current_timestamp) {
+            return Err(ErrorCode::StakingEnded.into());
         }
+        let staked_info_index =
+            first_free_slot(&ctx.accounts.user_instance).ok_or(ErrorCode::NoFreeStakeSlot)?;
+
+        // 以金库实际到账数量记账，与 enter_staking 一致
+        let vault_balance_before = ctx.accounts.gdtc_lp_in_account.amount;
+        token_interface::transfer_checked(
+            ctx.accounts.into_transfer_to_vault_context(),
+            amount,
+            ctx.accounts.staking_token_mint.decimals,
+        )?;
+        ctx.accounts.gdtc_lp_in_account.reload()?;
+        let received_amount = ctx
+            .accounts
+            .gdtc_lp_in_account
+            .amount
+            .checked_sub(vault_balance_before)
+            .ok_or(ErrorCode::Underflow)?;
+
+        msg!(
+            "deposit for {} into slot {}: {}",
+            beneficiary,
+            staked_info_index,
+            received_amount
+        );
+        // 记录进入质押的 slot，投票快照据此排除之后的质押
+        ctx.accounts.user_instance.stake_slots[staked_info_index as usize] = clock.slot;
+        record_stake(
+            &mut ctx.accounts.staking_instance,
+            &mut ctx.accounts.user_instance,
+            staked_info_index,
+            stake_type,
+            received_amount,
+            current_timestamp,
+        )
+    }
+
+    /// 到期后解除质押并取回 LP。尚未领取的合作方奖励随解押放弃，需要的话在同一笔交易中先领取
+    pub fn cancel_staking(ctx: Context<CancelStaking>, staked_info_index: u64) -> Result<()> {
+        // 获取相关账户
+        let staking_instance = &mut ctx.accounts.staking_instance;
+        let user_instance = &mut ctx.accounts.user_instance;
+
+        // 派生地址、Mint、金库和账户归属（包括用户 LP Token 账户的 owner）已由 CancelStaking 的账户约束校验
 
         let index = staked_info_index as usize;
 
@@ -464,36 +1371,39 @@ pub mod gdtc_staking {
         }
 
         // 更新奖励池并计算用户的奖励
-        update_reward_pool(current_timestamp, staking_instance);
+        update_reward_pool(current_timestamp, staking_instance)?;
 
         // 存储用户的待领取奖励
         store_pending_reward(staking_instance, user_instance, staked_info_index)?;
+        let forfeited_stream_rewards =
+            forfeit_stream_rewards(&mut user_instance.staked_info[index]);
+        if forfeited_stream_rewards.iter().any(|reward| *reward > 0) {
+            msg!(
+                "cancel staking: stream rewards forfeited {:?}",
+                forfeited_stream_rewards
+            );
+        }
 
         // 获取 PDA 签名者
-        let bump_seed = ctx.bumps.pda_account;
-        let signer_seeds: &[&[&[u8]]] = &[&[crate::LPTOKEN_SEED.as_ref(), &[bump_seed]]];
-
-        // 生成从 GDTC 托管账户到用户 LP Token 账户的转账指令
-        let transfer_instruction = spl_token::instruction::transfer(
-            &ctx.accounts.token_program.key(),
-            &ctx.accounts.gdtc_lp_in_account.key(),
-            &ctx.accounts.user_lp_token_account.key(),
-            &ctx.accounts.pda_account.key(),
-            &[],
+        let bump_seed = staking_instance.lp_authority_bump;
+        let staking_instance_key = staking_instance.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            crate::LPTOKEN_SEED,
+            staking_instance.lp_authority_seed(&staking_instance_key),
+            &[bump_seed],
+        ]];
+
+        // 从 GDTC 托管账户转回用户 LP Token 账户，执行带签名的 CPI 调用
+        token_interface::transfer_checked(
+            ctx.accounts
+                .into_transfer_to_user_context()
+                .with_signer(signer_seeds),
             amount,
+            ctx.accounts.staking_token_mint.decimals,
         )?;
 
-        // 执行带签名的 CPI 调用
-        invoke_signed(
-            &transfer_instruction,
-            &[
-                ctx.accounts.token_program.to_account_info(),
-                ctx.accounts.gdtc_lp_in_account.to_account_info(),
-                ctx.accounts.user_lp_token_account.to_account_info(),
-                ctx.accounts.pda_account.to_account_info(),
-            ],
-            signer_seeds,
-        )?;
+        let staking_instance = &mut ctx.accounts.staking_instance;
+        let user_instance = &mut ctx.accounts.user_instance;
 
         // // 更新质押池的总份额
         let pool =
@@ -505,7 +1415,13 @@ pub mod gdtc_staking {
             .ok_or(ErrorCode::Underflow)?;
 
         // 更新奖励债务
-        update_reward_debt(staking_instance, user_instance, staked_info_index);
+        update_reward_debt(staking_instance, user_instance, staked_info_index)?;
+
+        // 解押时未领取的奖励随质押清零，不再计入未支付总额
+        release_reward_liability(
+            staking_instance,
+            user_instance.staked_info[index].accumulated_reward,
+        );
 
         // 获取用户对应的质押信息
         let staked_info = &mut user_instance.staked_info[index];
@@ -518,6 +1434,9 @@ pub mod gdtc_staking {
         staked_info.stake_start_time = 0; // 重置质押开始时间
         staked_info.stake_end_time = 0; // 重置质押结束时间
         staked_info.receivedReward = 0;
+        // 合作方奖励已领取或已放弃
+        staked_info.stream_reward_debt = [0; MAX_REWARD_STREAMS];
+        staked_info.stream_accumulated_reward = [0; MAX_REWARD_STREAMS];
         user_instance.staked_info[index].can_cancel_stake = false;
 
         Ok(())
@@ -531,77 +1450,13 @@ pub mod gdtc_staking {
 
         // let user_gdtc_token_account = &mut ctx.accounts.user_gdtc_token_account;
         let gdtc_reward_out_account = &ctx.accounts.gdtc_reward_out_account;
-        let user_super_gdtc_token_account = &mut ctx.accounts.user_super_gdtc_token_account;
 
-        let program_id = ctx.program_id; // 获取当前合约的程序ID
-                                         // 计算 staking_instance 的派生地址
-        let (expected_staking_address, bump_seed) =
-            Pubkey::find_program_address(&[crate::STAKING_SEED.as_ref()], program_id);
-
-        // 确保 staking_instance 是由合约程序派生的
-        if staking_instance.key() != expected_staking_address {
-            return Err(ErrorCode::InvalidStakingInstance.into());
-        }
-
-        let (expected_pda_address, bump_seed) =
-            Pubkey::find_program_address(&[crate::LPTOKEN_SEED.as_ref()], program_id);
-
-        if expected_pda_address != gdtc_reward_out_account.owner.key() {
-            return Err(ErrorCode::PdaAccountIsNotMatch.into());
-        }
-
-        //用户账户验证
-        let (expected_user_address, bump_seed) = Pubkey::find_program_address(
-            &[
-                crate::USER_SEED.as_ref(),
-                staking_instance.key().as_ref(),
-                user_instance.user_address.key().as_ref(),
-            ],
-            program_id,
-        );
-        msg!(
-            "expected_user_address is: {},user_instance: {}",
-            expected_user_address.key(),
-            user_instance.key()
-        );
-        // 确保 staking_instance 是由合约程序派生的
-        if user_instance.key() != expected_user_address {
-            return Err(ErrorCode::InvalidUserInstance.into());
-        }
-
-        //用户上级账户验证
-        let (expected_user_superior_address, bump_seed) = Pubkey::find_program_address(
-            &[
-                crate::USER_SEED.as_ref(),
-                staking_instance.key().as_ref(),
-                user_super_gdtc_token_account.owner.key().as_ref(),
-            ],
-            program_id,
-        );
-        msg!(
-            "expected_user_superior_address is: {},user_instance: {}",
-            expected_user_superior_address.key(),
-            super_instance.key()
-        );
-        // 确保 staking_instance 是由合约程序派生的
-        if super_instance.key() != expected_user_superior_address {
-            return Err(ErrorCode::InvalidUserInstance.into());
-        }
-
-        if staking_instance.reward_token_mint != gdtc_reward_out_account.mint {
-            return Err(ErrorCode::MintAccountIsNotMatch.into());
-        }
+        // 派生地址、奖励 Mint、奖励金库、用户与上级的 token 账户已由 ClaimRewards 的账户约束校验
 
         // 获取当前时间戳
         let clock = Clock::get().map_err(|_| ErrorCode::ClockUnavailable)?;
         let current_timestamp = clock.unix_timestamp as u64;
         let index = staked_info_index as usize;
-        if user_instance.user_address != ctx.accounts.user_gdtc_token_account.owner.key() {
-            return Err(ErrorCode::UserAccountIsNotMatch.into());
-        }
-        if super_instance.user_address != user_super_gdtc_token_account.owner.key() {
-            return Err(ErrorCode::UserAccountIsNotMatch.into());
-        }
 
         // 检查用户是否有质押
         if !user_instance.staked_info[index].is_staked {
@@ -618,12 +1473,9 @@ pub mod gdtc_staking {
             index,
             user_instance.staked_info[index].is_staked
         );
-        if user_instance.user_superior_token_account != user_super_gdtc_token_account.key() {
-            return Err(ErrorCode::MintAccountIsNotMatch.into());
-        }
 
         // 更新奖励池并计算用户的奖励
-        update_reward_pool(current_timestamp, staking_instance);
+        update_reward_pool(current_timestamp, staking_instance)?;
 
         store_pending_reward(staking_instance, user_instance, staked_info_index)?;
 
@@ -652,55 +1504,44 @@ pub mod gdtc_staking {
             return Ok(());
         }
 
-        let bump_seed = ctx.bumps.pda_account;
-        let signer_seeds: &[&[&[u8]]] = &[&[crate::LPTOKEN_SEED.as_ref(), &[bump_seed]]];
+        let bump_seed = staking_instance.lp_authority_bump;
+        let staking_instance_key = staking_instance.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            crate::LPTOKEN_SEED,
+            staking_instance.lp_authority_seed(&staking_instance_key),
+            &[bump_seed],
+        ]];
 
-        if super_instance.total_deposited_amount > 2000000000 {
-            let transfer_instruction = spl_token::instruction::transfer(
-                &ctx.accounts.token_program.key(),
-                &ctx.accounts.gdtc_reward_out_account.key(),
-                &ctx.accounts.user_super_gdtc_token_account.key(),
-                &ctx.accounts.pda_account.key(),
-                &[],
-                accumulated_reward / 10,
-            )?;
+        let reward_decimals = ctx.accounts.reward_token_mint.decimals;
 
+        let referral_reward = calculate_referral_reward(super_instance, accumulated_reward);
+        if referral_reward > 0 {
             // 执行带签名的 CPI 调用
-            invoke_signed(
-                &transfer_instruction,
-                &[
-                    ctx.accounts.token_program.to_account_info(),
-                    ctx.accounts.gdtc_reward_out_account.to_account_info(),
-                    ctx.accounts.user_super_gdtc_token_account.to_account_info(),
-                    ctx.accounts.pda_account.to_account_info(),
-                ],
-                signer_seeds,
+            token_interface::transfer_checked(
+                ctx.accounts
+                    .into_transfer_reward_context(
+                        ctx.accounts.user_super_gdtc_token_account.to_account_info(),
+                    )
+                    .with_signer(signer_seeds),
+                referral_reward,
+                reward_decimals,
             )?;
             //取消上级百分之十从挖矿者奖励中拿出
             // accumulated_reward = accumulated_reward - (accumulated_reward / 10);
         }
 
-        // 生成从 GDTC 托管账户到用户 LP Token 账户的转账指令
-        let transfer_instruction = spl_token::instruction::transfer(
-            &ctx.accounts.token_program.key(),
-            &ctx.accounts.gdtc_reward_out_account.key(),
-            &ctx.accounts.user_gdtc_token_account.key(),
-            &ctx.accounts.pda_account.key(),
-            &[],
+        // 从 GDTC 托管账户转出奖励到用户 GDTC Token 账户，执行带签名的 CPI 调用
+        token_interface::transfer_checked(
+            ctx.accounts
+                .into_transfer_reward_context(
+                    ctx.accounts.user_gdtc_token_account.to_account_info(),
+                )
+                .with_signer(signer_seeds),
             accumulated_reward,
+            reward_decimals,
         )?;
 
-        // 执行带签名的 CPI 调用
-        invoke_signed(
-            &transfer_instruction,
-            &[
-                ctx.accounts.token_program.to_account_info(),
-                ctx.accounts.gdtc_reward_out_account.to_account_info(),
-                ctx.accounts.user_gdtc_token_account.to_account_info(),
-                ctx.accounts.pda_account.to_account_info(),
-            ],
-            signer_seeds,
-        )?;
+        let user_instance = &mut ctx.accounts.user_instance;
 
         if current_timestamp >= user_instance.staked_info[index].stake_end_time {
             if user_instance.staked_info[index].can_cancel_stake == true {
@@ -718,7 +1559,11 @@ pub mod gdtc_staking {
                 .ok_or(ErrorCode::Overflow)?;
         }
 
+        // 已支付的奖励从未支付总额中扣除
+        release_reward_liability(&mut ctx.accounts.staking_instance, accumulated_reward);
+
         // 重置用户累计奖励
+        let user_instance = &mut ctx.accounts.user_instance;
         user_instance.staked_info[index].accumulated_reward = 0;
 
         user_instance.staked_info[index].receivedReward = user_instance.staked_info[index]
@@ -728,6 +1573,834 @@ pub mod gdtc_staking {
         Ok(())
     }
 
+    /// 修改池子的锁仓期限，只影响之后新进入的质押
+    pub fn set_lock_term(
+        ctx: Context<SetPoolConfig>,
+        stake_type: u64,
+        lock_term: LockTerm,
+    ) -> Result<()> {
+        let staking_instance = &mut ctx.accounts.staking_instance;
+
+        if !is_authorized(&ctx.accounts.authority.key(), &staking_instance.authority) {
+            return Err(ErrorCode::Unauthorized.into());
+        }
+        if stake_type >= staking_instance.pools.len() as u64 {
+            return Err(ErrorCode::InvalidStakeType.into());
+        }
+        let is_valid = match lock_term {
+            LockTerm::CalendarMonths(months) => (1..=MAX_LOCK_MONTHS).contains(&months),
+            LockTerm::FixedSeconds(seconds) => (1..=MAX_LOCK_SECONDS).contains(&seconds),
+        };
+        if !is_valid {
+            return Err(ErrorCode::InvalidLockTerm.into());
+        }
+
+        staking_instance.pools[stake_type as usize].lock_term = lock_term;
+        Ok(())
+    }
+
+    /// 修改计算到期日所用的时区，只影响之后新进入的质押，已有质押的到期时间不变
+    pub fn set_utc_offset(ctx: Context<SetPoolConfig>, utc_offset_seconds: i32) -> Result<()> {
+        let staking_instance = &mut ctx.accounts.staking_instance;
+
+        if !is_authorized(&ctx.accounts.authority.key(), &staking_instance.authority) {
+            return Err(ErrorCode::Unauthorized.into());
+        }
+        check_utc_offset(utc_offset_seconds)?;
+
+        staking_instance.utc_offset_seconds = utc_offset_seconds;
+        Ok(())
+    }
+
+    pub fn set_early_unstake_policy(
+        ctx: Context<SetEarlyUnstakePolicy>,
+        stake_type: u64,
+        enabled: bool,
+        penalty_bps: u16,            // LP 罚金比例
+        reward_forfeit_bps: u16,     // 放弃的未领取奖励比例
+        penalty_decays: bool,        // 是否随到期线性递减
+        redistribute_forfeits: bool, // 放弃的 GDTC 奖励是否分给剩余质押者，LP 罚金始终转入国库
+    ) -> Result<()> {
+        let staking_instance = &mut ctx.accounts.staking_instance;
+        let treasury_lp_token_account = &ctx.accounts.treasury_lp_token_account;
+
+        if !is_authorized(&ctx.accounts.authority.key(), &staking_instance.authority) {
+            return Err(ErrorCode::Unauthorized.into());
+        }
+        if stake_type >= staking_instance.pools.len() as u64 {
+            return Err(ErrorCode::InvalidStakeType.into());
+        }
+        if penalty_bps as u64 > BPS_DENOMINATOR || reward_forfeit_bps as u64 > BPS_DENOMINATOR {
+            return Err(ErrorCode::InvalidPenaltyBps.into());
+        }
+        if treasury_lp_token_account.mint != staking_instance.staking_token_mint {
+            return Err(ErrorCode::MintAccountIsNotMatch.into());
+        }
+        // 罚金不能回流到 LP 本金金库，否则会被记成无主余额
+        if treasury_lp_token_account.key() == staking_instance.lp_token_account {
+            return Err(ErrorCode::InvalidTreasuryAccount.into());
+        }
+
+        staking_instance.treasury_lp_token_account = treasury_lp_token_account.key();
+        let pool = &mut staking_instance.pools[stake_type as usize];
+        pool.early_unstake_enabled = enabled;
+        pool.penalty_bps = penalty_bps;
+        pool.reward_forfeit_bps = reward_forfeit_bps;
+        pool.penalty_decays = penalty_decays;
+        pool.redistribute_forfeits = redistribute_forfeits;
+        Ok(())
+    }
+
+    /// 到期前解除质押：按池子配置扣除部分 LP 转入国库，并放弃部分未领取的 GDTC 奖励，
+    /// 其余奖励随本金一并发放，上级与 claim_rewards 一样另得 10% 推荐奖励。放弃的奖励按池子配置留在奖励账户或分给剩余质押者。尚未领取的合作方奖励随解押放弃，需要的话在同一笔交易中先通过 `claim_all_stream_rewards` 领取。
+    pub fn early_unstake(ctx: Context<EarlyUnstake>, staked_info_index: u64) -> Result<()> {
+        let staking_instance = &mut ctx.accounts.staking_instance;
+        let user_instance = &mut ctx.accounts.user_instance;
+
+        // 实例、用户和 LP 权限 PDA 的派生地址已由账户约束中的 seeds + bump 校验
+        if staked_info_index > 9 {
+            return Err(ErrorCode::InvalidStakedInfoIndex.into());
+        }
+
+        let clock = Clock::get().map_err(|_| ErrorCode::ClockUnavailable)?;
+        let EarlyUnstakeSettlement {
+            lp_returned,
+            lp_penalty,
+            reward_payout,
+            referral_reward,
+            forfeited_reward,
+            redistributed_reward,
+            forfeited_stream_rewards,
+        } = record_early_unstake(
+            staking_instance,
+            user_instance,
+            &ctx.accounts.super_instance,
+            staked_info_index,
+            clock.unix_timestamp as u64,
+        )?;
+        let total_reward = reward_payout
+            .checked_add(referral_reward)
+            .ok_or(ErrorCode::Overflow)?;
+        if ctx.accounts.gdtc_reward_out_account.amount < total_reward {
+            return Err(ErrorCode::InsufficientRewardBalance.into());
+        }
+
+        msg!(
+            "early unstake: lp returned {}, lp penalty {}, reward paid {}, referral {}, reward forfeited {}, redistributed {}, stream rewards forfeited {:?}",
+            lp_returned,
+            lp_penalty,
+            reward_payout,
+            referral_reward,
+            forfeited_reward,
+            redistributed_reward,
+            forfeited_stream_rewards
+        );
+
+        let staking_instance_key = ctx.accounts.staking_instance.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            crate::LPTOKEN_SEED,
+            ctx.accounts
+                .staking_instance
+                .lp_authority_seed(&staking_instance_key),
+            &[ctx.accounts.staking_instance.lp_authority_bump],
+        ]];
+        let lp_decimals = ctx.accounts.staking_token_mint.decimals;
+
+        if lp_returned > 0 {
+            token_interface::transfer_checked(
+                ctx.accounts
+                    .into_transfer_lp_context(ctx.accounts.user_lp_token_account.to_account_info())
+                    .with_signer(signer_seeds),
+                lp_returned,
+                lp_decimals,
+            )?;
+        }
+        if lp_penalty > 0 {
+            token_interface::transfer_checked(
+                ctx.accounts
+                    .into_transfer_lp_context(
+                        ctx.accounts.treasury_lp_token_account.to_account_info(),
+                    )
+                    .with_signer(signer_seeds),
+                lp_penalty,
+                lp_decimals,
+            )?;
+        }
+        // 放弃的奖励留在奖励账户中，不再分配给该用户（若已重新分配，则由其他质押者领取）
+        if referral_reward > 0 {
+            token_interface::transfer_checked(
+                ctx.accounts
+                    .into_transfer_reward_context(
+                        ctx.accounts.user_super_gdtc_token_account.to_account_info(),
+                    )
+                    .with_signer(signer_seeds),
+                referral_reward,
+                ctx.accounts.reward_token_mint.decimals,
+            )?;
+        }
+        if reward_payout > 0 {
+            token_interface::transfer_checked(
+                ctx.accounts
+                    .into_transfer_reward_context(
+                        ctx.accounts.user_gdtc_token_account.to_account_info(),
+                    )
+                    .with_signer(signer_seeds),
+                reward_payout,
+                ctx.accounts.reward_token_mint.decimals,
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn add_reward_stream(
+        ctx: Context<AddRewardStream>,
+        stake_type: u64,           // 挂载奖励流的质押池
+        stream_index: u64,         // 奖励流槽位
+        reward_token_per_sec: u64, // 每秒奖励代币数量
+    ) -> Result<()> {
+        let staking_instance = &mut ctx.accounts.staking_instance;
+        let reward_vault = &ctx.accounts.reward_vault;
+        let program_id = ctx.program_id;
+
+        if !is_authorized(&ctx.accounts.authority.key(), &staking_instance.authority) {
+            return Err(ErrorCode::Unauthorized.into());
+        }
+        if stake_type >= staking_instance.pools.len() as u64 {
+            return Err(ErrorCode::InvalidStakeType.into());
+        }
+        if stream_index >= MAX_REWARD_STREAMS as u64 {
+            return Err(ErrorCode::InvalidRewardStreamIndex.into());
+        }
+
+        // 奖励金库必须是该 mint 的账户，且由本实例的 LP 权限 PDA 持有
+        if reward_vault.mint != ctx.accounts.reward_token_mint.key() {
+            return Err(ErrorCode::MintAccountIsNotMatch.into());
+        }
+        let expected_pda_address =
+            lp_authority_address(staking_instance, &staking_instance.key(), program_id)?;
+        if expected_pda_address != reward_vault.owner {
+            return Err(ErrorCode::PdaAccountIsNotMatch.into());
+        }
+        // 不允许与 LP 本金金库共用账户
+        if reward_vault.key() == staking_instance.lp_token_account {
+            return Err(ErrorCode::PdaAccountIsNotMatch.into());
+        }
+
+        let stream =
+            &staking_instance.pools[stake_type as usize].reward_streams[stream_index as usize];
+        if stream.is_active() && stream.reward_token_mint != ctx.accounts.reward_token_mint.key() {
+            return Err(ErrorCode::RewardStreamAlreadyActive.into());
+        }
+        check_stream_slot_reusable(stream)?;
+
+        // 先按旧速率结算到当前时间，新速率只对之后生效
+        let clock = Clock::get().map_err(|_| ErrorCode::ClockUnavailable)?;
+        update_reward_pool(clock.unix_timestamp as u64, staking_instance)?;
+
+        let stream =
+            &mut staking_instance.pools[stake_type as usize].reward_streams[stream_index as usize];
+        stream.reward_token_mint = ctx.accounts.reward_token_mint.key();
+        stream.reward_vault = reward_vault.key();
+        stream.reward_token_per_sec = reward_token_per_sec;
+        Ok(())
+    }
+
+    /// 停用合作方奖励流，例如合作方金库无法继续注资时。停用前已结算但未领取的奖励不再可领取
+    pub fn deactivate_reward_stream(
+        ctx: Context<SetPoolConfig>,
+        stake_type: u64,
+        stream_index: u64,
+    ) -> Result<()> {
+        let staking_instance = &mut ctx.accounts.staking_instance;
+        if !is_authorized(&ctx.accounts.authority.key(), &staking_instance.authority) {
+            return Err(ErrorCode::Unauthorized.into());
+        }
+        let clock = Clock::get().map_err(|_| ErrorCode::ClockUnavailable)?;
+        deactivate_stream(
+            staking_instance,
+            stake_type,
+            stream_index,
+            clock.unix_timestamp as u64,
+        )
+    }
+
+    pub fn claim_stream_rewards(
+        ctx: Context<ClaimStreamRewards>,
+        staked_info_index: u64,
+        stream_index: u64,
+    ) -> Result<()> {
+        let staking_instance = &mut ctx.accounts.staking_instance;
+        let user_instance = &mut ctx.accounts.user_instance;
+
+        // 实例、用户和 LP 权限 PDA 的派生地址已由账户约束中的 seeds + bump 校验
+        if ctx.accounts.user_reward_token_account.owner != user_instance.user_address {
+            return Err(ErrorCode::UserAccountIsNotMatch.into());
+        }
+        if staked_info_index > 9 {
+            return Err(ErrorCode::InvalidStakedInfoIndex.into());
+        }
+        if stream_index >= MAX_REWARD_STREAMS as u64 {
+            return Err(ErrorCode::InvalidRewardStreamIndex.into());
+        }
+        let index = staked_info_index as usize;
+        if !user_instance.staked_info[index].is_staked {
+            return Err(ErrorCode::NoStakingToClaimRewards.into());
+        }
+
+        let stake_type = user_instance.staked_info[index].stake_type as usize;
+        let stream = &staking_instance.pools[stake_type].reward_streams[stream_index as usize];
+        check_reward_stream_accounts(
+            stream,
+            &ctx.accounts.reward_token_mint.key(),
+            &ctx.accounts.reward_vault.key(),
+            &ctx.accounts.user_reward_token_account.mint,
+        )?;
+
+        let clock = Clock::get().map_err(|_| ErrorCode::ClockUnavailable)?;
+        update_reward_pool(clock.unix_timestamp as u64, staking_instance)?;
+        store_pending_reward(staking_instance, user_instance, staked_info_index)?;
+
+        let reward =
+            take_stream_reward(&mut user_instance.staked_info[index], stream_index as usize)?;
+        if ctx.accounts.reward_vault.amount < reward {
+            return Err(ErrorCode::InsufficientRewardBalance.into());
+        }
+
+        let staking_instance_key = ctx.accounts.staking_instance.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            crate::LPTOKEN_SEED,
+            ctx.accounts
+                .staking_instance
+                .lp_authority_seed(&staking_instance_key),
+            &[ctx.accounts.staking_instance.lp_authority_bump],
+        ]];
+        token_interface::transfer_checked(
+            ctx.accounts
+                .into_transfer_to_user_context()
+                .with_signer(signer_seeds),
+            reward,
+            ctx.accounts.reward_token_mint.decimals,
+        )?;
+        Ok(())
+    }
+
+    pub fn claim_all_stream_rewards<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimAllStreamRewards<'info>>,
+        staked_info_index: u64,
+    ) -> Result<()> {
+        let staking_instance = &mut ctx.accounts.staking_instance;
+        let user_instance = &mut ctx.accounts.user_instance;
+
+        // 实例、用户和 LP 权限 PDA 的派生地址已由账户约束中的 seeds + bump 校验
+        if staked_info_index > 9 {
+            return Err(ErrorCode::InvalidStakedInfoIndex.into());
+        }
+        let index = staked_info_index as usize;
+        if !user_instance.staked_info[index].is_staked {
+            return Err(ErrorCode::NoStakingToClaimRewards.into());
+        }
+
+        let clock = Clock::get().map_err(|_| ErrorCode::ClockUnavailable)?;
+        update_reward_pool(clock.unix_timestamp as u64, staking_instance)?;
+        store_pending_reward(staking_instance, user_instance, staked_info_index)?;
+
+        let staking_instance_key = staking_instance.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            crate::LPTOKEN_SEED,
+            staking_instance.lp_authority_seed(&staking_instance_key),
+            &[staking_instance.lp_authority_bump],
+        ]];
+
+        let stake_type = user_instance.staked_info[index].stake_type as usize;
+        let mut remaining_accounts = ctx.remaining_accounts.chunks(3);
+        let mut total_claimed = 0u64;
+        for (stream_index, stream) in staking_instance.pools[stake_type]
+            .reward_streams
+            .iter()
+            .enumerate()
+        {
+            if !stream.is_active() {
+                continue;
+            }
+            let accounts = remaining_accounts
+                .next()
+                .filter(|accounts| accounts.len() == 3)
+                .ok_or(ErrorCode::MissingRewardStreamAccounts)?;
+            let reward_token_mint =
+                InterfaceAccount::<token_interface::Mint>::try_from(&accounts[0])?;
+            let reward_vault =
+                InterfaceAccount::<token_interface::TokenAccount>::try_from(&accounts[1])?;
+            let user_reward_token_account =
+                InterfaceAccount::<token_interface::TokenAccount>::try_from(&accounts[2])?;
+            check_reward_stream_accounts(
+                stream,
+                &reward_token_mint.key(),
+                &reward_vault.key(),
+                &user_reward_token_account.mint,
+            )?;
+            if user_reward_token_account.owner != user_instance.user_address {
+                return Err(ErrorCode::UserAccountIsNotMatch.into());
+            }
+
+            if user_instance.staked_info[index].stream_accumulated_reward[stream_index] == 0 {
+                continue;
+            }
+            let reward = take_stream_reward(&mut user_instance.staked_info[index], stream_index)?;
+            if reward_vault.amount < reward {
+                return Err(ErrorCode::InsufficientRewardBalance.into());
+            }
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_interface::TransferChecked {
+                        from: accounts[1].clone(),
+                        mint: accounts[0].clone(),
+                        to: accounts[2].clone(),
+                        authority: ctx.accounts.pda_account.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                reward,
+                reward_token_mint.decimals,
+            )?;
+            total_claimed = total_claimed
+                .checked_add(reward)
+                .ok_or(ErrorCode::Overflow)?;
+        }
+
+        if total_claimed == 0 {
+            return Err(ErrorCode::NoRewardsToClaim.into());
+        }
+        Ok(())
+    }
+
+    /// 把旧账户升级到当前布局，扩容所需租金由管理员支付。
+    /// 同时支持累计奖励分摊为 u64 的最早布局，以及 version 为 0（尚无版本字段）的布局
+    pub fn migrate_staking_instance(ctx: Context<MigrateStakingInstance>) -> Result<()> {
+        let account = ctx.accounts.staking_instance.to_account_info();
+        let authority = ctx.accounts.authority.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        if account.owner != ctx.program_id {
+            return Err(ErrorCode::InvalidStakingInstance.into());
+        }
+        let space = 8 + StakingInstance::INIT_SPACE;
+
+        // 最早的布局以账户空间识别，需要逐字段转换
+        let mut staking_instance = if account.data_len() == STAKING_INSTANCE_V1_SPACE {
+            let legacy = read_legacy_account::<StakingInstance, StakingInstanceV1>(&account)?;
+            realloc_account(&account, space, &authority, &system_program)?;
+            StakingInstance::from(legacy)
+        } else {
+            load_for_migration::<StakingInstance>(&account, space, &authority, &system_program)?
+        };
+        if !is_authorized(&ctx.accounts.authority.key(), &staking_instance.authority) {
+            return Err(ErrorCode::Unauthorized.into());
+        }
+        upgrade_staking_instance(&mut staking_instance, &account.key(), ctx.program_id)?;
+        write_account(&account, &staking_instance)
+    }
+
+    /// 设置池子的质押数量限制，只影响之后新进入的质押；上限为 0 表示不限制
+    pub fn set_stake_limits(
+        ctx: Context<SetPoolConfig>,
+        stake_type: u64,
+        limits: StakeLimits,
+    ) -> Result<()> {
+        let staking_instance = &mut ctx.accounts.staking_instance;
+
+        if !is_authorized(&ctx.accounts.authority.key(), &staking_instance.authority) {
+            return Err(ErrorCode::Unauthorized.into());
+        }
+        if stake_type >= staking_instance.pools.len() as u64 {
+            return Err(ErrorCode::InvalidStakeType.into());
+        }
+        // 最小数量超过任一上限时该池无法再质押
+        if (limits.max_stake_per_user > 0 && limits.min_stake > limits.max_stake_per_user)
+            || (limits.pool_capacity > 0 && limits.min_stake > limits.pool_capacity)
+        {
+            return Err(ErrorCode::InvalidStakeLimits.into());
+        }
+
+        staking_instance.stake_limits[stake_type as usize] = limits;
+        Ok(())
+    }
+
+    /// 设置池子主奖励的排放计划。先按旧计划结算到当前时间，新计划只影响之后的排放
+    pub fn set_emission_schedule(
+        ctx: Context<SetPoolConfig>,
+        stake_type: u64,
+        schedule: EmissionSchedule,
+    ) -> Result<()> {
+        let staking_instance = &mut ctx.accounts.staking_instance;
+
+        if !is_authorized(&ctx.accounts.authority.key(), &staking_instance.authority) {
+            return Err(ErrorCode::Unauthorized.into());
+        }
+        if stake_type >= staking_instance.pools.len() as u64 {
+            return Err(ErrorCode::InvalidStakeType.into());
+        }
+        // 衰减比例达到 100% 时速率不会下降，应改用恒定速率
+        if !schedule.is_constant() && schedule.decay_bps as u64 >= BPS_DENOMINATOR {
+            return Err(ErrorCode::InvalidEmissionSchedule.into());
+        }
+
+        let clock = Clock::get().map_err(|_| ErrorCode::ClockUnavailable)?;
+        update_reward_pool(clock.unix_timestamp as u64, staking_instance)?;
+
+        staking_instance.emission_schedules[stake_type as usize] = schedule;
+        Ok(())
+    }
+
+    /// 设置池子的收益率曲线，target_apr_bps 为 0 表示关闭。先按旧速率结算到当前时间，新曲线只影响之后的排放
+    pub fn set_rate_curve(
+        ctx: Context<SetPoolConfig>,
+        stake_type: u64,
+        curve: RateCurve,
+    ) -> Result<()> {
+        let staking_instance = &mut ctx.accounts.staking_instance;
+
+        if !is_authorized(&ctx.accounts.authority.key(), &staking_instance.authority) {
+            return Err(ErrorCode::Unauthorized.into());
+        }
+        if stake_type >= staking_instance.pools.len() as u64 {
+            return Err(ErrorCode::InvalidStakeType.into());
+        }
+        if curve.is_enabled() && curve.min_reward_per_sec > curve.max_reward_per_sec {
+            return Err(ErrorCode::InvalidRateCurve.into());
+        }
+
+        let clock = Clock::get().map_err(|_| ErrorCode::ClockUnavailable)?;
+        update_reward_pool(clock.unix_timestamp as u64, staking_instance)?;
+
+        staking_instance.rate_curves[stake_type as usize] = curve;
+        Ok(())
+    }
+
+    /// 开启或关闭白名单模式，开启后只有白名单中的钱包可以 initialize_user 和 enter_staking
+    pub fn set_allowlist_mode(ctx: Context<SetAllowlistMode>, enabled: bool) -> Result<()> {
+        let staking_instance = &mut ctx.accounts.staking_instance;
+        if !is_authorized(&ctx.accounts.authority.key(), &staking_instance.authority) {
+            return Err(ErrorCode::Unauthorized.into());
+        }
+        staking_instance.allowlist_enabled = enabled;
+        Ok(())
+    }
+
+    /// 把钱包加入白名单
+    pub fn add_allow_entry(ctx: Context<AddAllowEntry>, wallet: Pubkey) -> Result<()> {
+        if !is_authorized(
+            &ctx.accounts.authority.key(),
+            &ctx.accounts.staking_instance.authority,
+        ) {
+            return Err(ErrorCode::Unauthorized.into());
+        }
+        let allow_entry = &mut ctx.accounts.allow_entry;
+        allow_entry.staking_instance = ctx.accounts.staking_instance.key();
+        allow_entry.wallet = wallet;
+        allow_entry.version = ALLOW_ENTRY_VERSION;
+        allow_entry.bump = ctx.bumps.allow_entry;
+        Ok(())
+    }
+
+    /// 把钱包移出白名单，只影响之后的 initialize_user 和 enter_staking
+    pub fn remove_allow_entry(ctx: Context<RemoveAllowEntry>) -> Result<()> {
+        if !is_authorized(
+            &ctx.accounts.authority.key(),
+            &ctx.accounts.staking_instance.authority,
+        ) {
+            return Err(ErrorCode::Unauthorized.into());
+        }
+        // 由 `close = authority` 在指令结束时转出租金并清空账户
+        Ok(())
+    }
+
+    /// 关闭空的用户账户并把租金退还给用户，之后可重新 initialize_user
+    pub fn close_user(ctx: Context<CloseUser>) -> Result<()> {
+        let user_instance = &ctx.accounts.user_instance;
+
+        // 实例和用户的派生地址已由账户约束中的 seeds + bump 校验
+        if user_instance.user_address != ctx.accounts.authority.key() {
+            return Err(ErrorCode::UserAccountIsNotMatch.into());
+        }
+
+        // 校验通过后由 `close = authority` 在指令结束时转出租金并清空账户
+        check_user_closable(user_instance)
+    }
+
+    /// 任何人都可以调用：把所有质押池的奖励累计值推进到当前时间，并发出快照事件。
+    /// 结果与用户操作时的结算相同，只是不再依赖用户操作来刷新
+    pub fn crank_pools(ctx: Context<CrankPools>) -> Result<()> {
+        let clock = Clock::get().map_err(|_| ErrorCode::ClockUnavailable)?;
+        let current_timestamp = clock.unix_timestamp as u64;
+
+        let staking_instance = &mut ctx.accounts.staking_instance;
+        update_reward_pool(current_timestamp, staking_instance)?;
+
+        emit!(PoolsCranked {
+            staking_instance: staking_instance.key(),
+            timestamp: current_timestamp,
+            pools: pool_snapshots(staking_instance, current_timestamp),
+        });
+        Ok(())
+    }
+
+    /// 所有用户都已解押后，把未支付奖励总额对账清零，之后 sweep 可以取回剩余的全部奖励代币
+    pub fn reconcile_reward_liability(ctx: Context<SetPoolConfig>) -> Result<()> {
+        let staking_instance = &mut ctx.accounts.staking_instance;
+        if !is_authorized(&ctx.accounts.authority.key(), &staking_instance.authority) {
+            return Err(ErrorCode::Unauthorized.into());
+        }
+        clear_reward_liability(staking_instance)
+    }
+
+    /// 管理员从 LP 权限 PDA 名下的账户取回代币，不能动用质押本金和已欠用户的奖励
+    pub fn sweep(ctx: Context<Sweep>, amount: u64) -> Result<()> {
+        let staking_instance = &ctx.accounts.staking_instance;
+        if !is_authorized(&ctx.accounts.authority.key(), &staking_instance.authority) {
+            return Err(ErrorCode::Unauthorized.into());
+        }
+        if amount == 0 {
+            return Err(ErrorCode::ZeroSweepAmount.into());
+        }
+
+        // LP 权限 PDA 和账户的 mint 已由 Sweep 的账户约束校验
+        let source = &ctx.accounts.source;
+        let sweepable =
+            sweepable_amount(staking_instance, &source.key(), &source.mint, source.amount)?;
+        if amount > sweepable {
+            return Err(ErrorCode::SweepExceedsSurplus.into());
+        }
+
+        let staking_instance_key = staking_instance.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            crate::LPTOKEN_SEED,
+            staking_instance.lp_authority_seed(&staking_instance_key),
+            &[staking_instance.lp_authority_bump],
+        ]];
+        token_interface::transfer_checked(
+            ctx.accounts
+                .into_transfer_context()
+                .with_signer(signer_seeds),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        emit!(Swept {
+            staking_instance: staking_instance_key,
+            authority: ctx.accounts.authority.key(),
+            mint: ctx.accounts.mint.key(),
+            source: ctx.accounts.source.key(),
+            destination: ctx.accounts.destination.key(),
+            amount,
+        });
+        Ok(())
+    }
+
+    /// 把质押实例的管理权限交给管理员集合的 admin_authority PDA，之后所有管理指令都只能通过时间锁提案执行
+    pub fn set_authority(ctx: Context<SetAuthority>) -> Result<()> {
+        let staking_instance = &mut ctx.accounts.staking_instance;
+        if !is_authorized(&ctx.accounts.authority.key(), &staking_instance.authority) {
+            return Err(ErrorCode::Unauthorized.into());
+        }
+        // 新管理员的派生地址已由账户约束校验
+        staking_instance.authority = ctx.accounts.new_admin_authority.key();
+        Ok(())
+    }
+
+    /// 创建多签管理员集合
+    pub fn create_admin_set(
+        ctx: Context<CreateAdminSet>,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        let admin_set = &mut ctx.accounts.admin_set;
+        store_admin_members(admin_set, &members, threshold)?;
+        admin_set.create_key = ctx.accounts.create_key.key();
+        admin_set.generation = 0;
+        admin_set.proposal_count = 0;
+        admin_set.version = ADMIN_SET_VERSION;
+        admin_set.bump = ctx.bumps.admin_set;
+        admin_set.authority_bump = ctx.bumps.admin_authority;
+        admin_set.delay_secs = MIN_TIMELOCK_DELAY_SECS;
+        Ok(())
+    }
+
+    /// 修改成员和门限，只能通过提案由集合自己执行；之前未执行的提案全部失效
+    pub fn set_admin_members(
+        ctx: Context<SetAdminConfig>,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        let admin_set = &mut ctx.accounts.admin_set;
+        store_admin_members(admin_set, &members, threshold)?;
+        admin_set.generation = admin_set
+            .generation
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    /// 成员发起提案，提案人自动计为一票批准
+    pub fn propose(
+        ctx: Context<Propose>,
+        program_id: Pubkey,
+        accounts: Vec<ProposalAccount>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        let admin_set = &mut ctx.accounts.admin_set;
+        let member_index = admin_member_index(admin_set, &ctx.accounts.proposer.key())
+            .ok_or(ErrorCode::NotAdminMember)?;
+
+        let proposal = &mut ctx.accounts.proposal;
+        store_proposal_instruction(proposal, program_id, &accounts, &data)?;
+        proposal.admin_set = admin_set.key();
+        proposal.id = admin_set.proposal_count;
+        proposal.proposer = ctx.accounts.proposer.key();
+        proposal.generation = admin_set.generation;
+        proposal.approvals = 0;
+        approve(proposal, member_index)?;
+        proposal.version = PROPOSAL_VERSION;
+        proposal.bump = ctx.bumps.proposal;
+        proposal.eta = 0;
+
+        admin_set.proposal_count = admin_set
+            .proposal_count
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    /// 成员批准提案
+    pub fn approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+        let admin_set = &ctx.accounts.admin_set;
+        let member_index = admin_member_index(admin_set, &ctx.accounts.member.key())
+            .ok_or(ErrorCode::NotAdminMember)?;
+        let proposal = &mut ctx.accounts.proposal;
+        if proposal.generation != admin_set.generation {
+            return Err(ErrorCode::StaleProposal.into());
+        }
+        approve(proposal, member_index)
+    }
+
+    /// 修改时间锁延迟，只能通过提案由集合自己执行，因此修改本身也要等待旧的延迟
+    pub fn set_timelock_delay(ctx: Context<SetAdminConfig>, delay_secs: u64) -> Result<()> {
+        check_timelock_delay(delay_secs)?;
+        ctx.accounts.admin_set.delay_secs = delay_secs;
+        Ok(())
+    }
+
+    /// 任何人都可以把达到门限的提案放入时间锁队列，并发出事件公布将要执行的指令
+    pub fn queue_proposal(ctx: Context<QueueProposal>) -> Result<()> {
+        let clock = Clock::get().map_err(|_| ErrorCode::ClockUnavailable)?;
+        let admin_set = &ctx.accounts.admin_set;
+        let proposal = &mut ctx.accounts.proposal;
+        let eta = enqueue_proposal(admin_set, proposal, clock.unix_timestamp as u64)?;
+
+        emit!(ProposalQueued {
+            admin_set: admin_set.key(),
+            proposal: proposal.key(),
+            id: proposal.id,
+            program_id: proposal.program_id,
+            accounts: proposal.accounts[..proposal.account_count as usize].to_vec(),
+            data: proposal.data[..proposal.data_len as usize].to_vec(),
+            eta,
+        });
+        Ok(())
+    }
+
+    /// 提案人在执行前撤回提案（包括已排队的提案），或由成员关闭成员变更后过期的提案
+    pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+        let admin_set = &ctx.accounts.admin_set;
+        let member = ctx.accounts.member.key();
+        check_proposal_cancellable(admin_set, &ctx.accounts.proposal, &member)?;
+
+        emit!(ProposalCancelled {
+            admin_set: admin_set.key(),
+            proposal: ctx.accounts.proposal.key(),
+            id: ctx.accounts.proposal.id,
+            cancelled_by: member,
+        });
+        // 由 `close = proposer` 在指令结束时转出租金并清空提案
+        Ok(())
+    }
+
+    /// 执行时间锁已到期的提案：由 admin_authority PDA 签名调用提案中的指令，之后关闭提案并把租金退回提案人
+    pub fn execute_proposal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteProposal<'info>>,
+    ) -> Result<()> {
+        let clock = Clock::get().map_err(|_| ErrorCode::ClockUnavailable)?;
+        let admin_set = &ctx.accounts.admin_set;
+        let proposal = &ctx.accounts.proposal;
+        check_proposal_executable(admin_set, proposal)?;
+        check_timelock_elapsed(proposal, clock.unix_timestamp as u64)?;
+
+        let admin_authority = ctx.accounts.admin_authority.to_account_info();
+        let instruction = proposal_instruction(proposal, admin_authority.key);
+        let mut account_infos = ctx.remaining_accounts.to_vec();
+        account_infos.push(admin_authority);
+
+        let admin_set_key = admin_set.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            crate::ADMIN_AUTHORITY_SEED,
+            admin_set_key.as_ref(),
+            &[admin_set.authority_bump],
+        ]];
+        anchor_lang::solana_program::program::invoke_signed(
+            &instruction,
+            &account_infos,
+            signer_seeds,
+        )?;
+
+        emit!(ProposalExecuted {
+            admin_set: admin_set_key,
+            proposal: proposal.key(),
+            id: proposal.id,
+        });
+        // 由 `close = proposer` 在指令结束时转出租金并清空提案
+        Ok(())
+    }
+
+    /// 查询用户在 snapshot_slot 快照下的投票权，按剩余锁仓时间加权并随时间线性衰减。
+    /// 返回值写入 return data，其他程序可以通过 CPI 读取
+    pub fn voting_power(ctx: Context<VotingPower>, snapshot_slot: u64) -> Result<u64> {
+        let clock = Clock::get().map_err(|_| ErrorCode::ClockUnavailable)?;
+        // 未来的快照仍会计入之后临时进入的质押
+        if snapshot_slot > clock.slot {
+            return Err(ErrorCode::InvalidSnapshotSlot.into());
+        }
+        user_voting_power(
+            &ctx.accounts.user_instance,
+            snapshot_slot,
+            clock.unix_timestamp as u64,
+        )
+    }
+
+    /// 把旧用户账户升级到当前布局，任何人都可以代付租金，需先迁移所属的质押实例
+    pub fn migrate_user(ctx: Context<MigrateUser>) -> Result<()> {
+        let account = ctx.accounts.user_instance.to_account_info();
+        let payer = ctx.accounts.payer.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        if account.owner != ctx.program_id {
+            return Err(ErrorCode::InvalidUserInstance.into());
+        }
+        let space = 8 + User::INIT_SPACE;
+
+        let mut user_instance = if account.data_len() == USER_V1_SPACE {
+            let legacy = read_legacy_account::<User, UserV1>(&account)?;
+            realloc_account(&account, space, &payer, &system_program)?;
+            User::from(legacy)
+        } else {
+            load_for_migration::<User>(&account, space, &payer, &system_program)?
+        };
+        upgrade_user(
+            &mut user_instance,
+            &ctx.accounts.staking_instance.key(),
+            &account.key(),
+            ctx.program_id,
+        )?;
+        write_account(&account, &user_instance)
+    }
 }
 
 #[error_code]
@@ -791,4 +2464,136 @@ pub enum ErrorCode {
 
     #[msg("Pda address  does not match.")]
     PdaAccountIsNotMatch,
+
+    #[msg("Signer is not the staking instance authority.")]
+    Unauthorized,
+
+    #[msg("Invalid reward stream index.")]
+    InvalidRewardStreamIndex,
+
+    #[msg("Reward stream is not active.")]
+    RewardStreamNotActive,
+
+    #[msg("Reward stream slot is already used by another mint.")]
+    RewardStreamAlreadyActive,
+
+    #[msg("Reward vault does not match the reward stream.")]
+    RewardVaultIsNotMatch,
+
+    #[msg("Missing accounts for an active reward stream.")]
+    MissingRewardStreamAccounts,
+
+    #[msg("Early unstake is not enabled for this pool.")]
+    EarlyUnstakeDisabled,
+
+    #[msg("Staking has matured; use cancel_staking instead.")]
+    StakingAlreadyMatured,
+
+    #[msg("Penalty basis points must not exceed 10000.")]
+    InvalidPenaltyBps,
+
+    #[msg("Treasury account does not match.")]
+    InvalidTreasuryAccount,
+
+    #[msg("UTC offset must be within ±14 hours.")]
+    InvalidUtcOffset,
+
+    #[msg("Lock term is out of the supported range.")]
+    InvalidLockTerm,
+
+    #[msg("Account is already at the current layout version.")]
+    AccountAlreadyMigrated,
+
+    #[msg("User still has an active staking.")]
+    UserHasActiveStaking,
+
+    #[msg("User still has unclaimed rewards.")]
+    UserHasUnclaimedRewards,
+
+    #[msg("Wallet is not on the staking allowlist.")]
+    WalletNotAllowlisted,
+
+    #[msg("Stake amount must be greater than zero.")]
+    ZeroStakeAmount,
+
+    #[msg("Stake amount is below the pool minimum.")]
+    StakeBelowMinimum,
+
+    #[msg("Stake would exceed the per-user limit of the pool.")]
+    UserStakeCapExceeded,
+
+    #[msg("Stake would exceed the pool capacity.")]
+    PoolCapacityExceeded,
+
+    #[msg("Minimum stake must not exceed the pool limits.")]
+    InvalidStakeLimits,
+
+    #[msg("User has no free staking slot.")]
+    NoFreeStakeSlot,
+
+    #[msg("Emission decay must be below 100% per period.")]
+    InvalidEmissionSchedule,
+
+    #[msg("Minimum reward rate must not exceed the maximum.")]
+    InvalidRateCurve,
+
+    #[msg("Sweep amount must be greater than zero.")]
+    ZeroSweepAmount,
+
+    #[msg("Sweep amount exceeds the balance not owed to stakers.")]
+    SweepExceedsSurplus,
+
+    #[msg("Active reward stream vaults cannot be swept.")]
+    RewardStreamVaultNotSweepable,
+
+    #[msg("New authority must not be the default public key.")]
+    InvalidAuthority,
+
+    #[msg("Admin members must be unique, non-default and at most MAX_ADMIN_MEMBERS.")]
+    InvalidAdminMembers,
+
+    #[msg("Threshold must be between 1 and the number of admin members.")]
+    InvalidAdminThreshold,
+
+    #[msg("Signer is not a member of the admin set.")]
+    NotAdminMember,
+
+    #[msg("Member has already approved this proposal.")]
+    ProposalAlreadyApproved,
+
+    #[msg("Admin members changed after the proposal was created.")]
+    StaleProposal,
+
+    #[msg("Proposal does not have enough approvals.")]
+    NotEnoughApprovals,
+
+    #[msg("Proposal instruction has too many accounts or too much data.")]
+    ProposalTooLarge,
+
+    #[msg("Timelock delay is out of the supported range.")]
+    InvalidTimelockDelay,
+
+    #[msg("Proposal is already queued.")]
+    ProposalAlreadyQueued,
+
+    #[msg("Proposal has not been queued.")]
+    ProposalNotQueued,
+
+    #[msg("Timelock delay has not elapsed yet.")]
+    TimelockNotElapsed,
+
+    #[msg("Snapshot slot must not be in the future.")]
+    InvalidSnapshotSlot,
+
+    #[msg("This instance id is reserved for the original staking instance.")]
+    ReservedInstanceId,
+
+    #[msg("Reward liability can only be reconciled once every pool is empty.")]
+    ActiveStakesRemain,
+
+    #[msg("This reward stream slot was deactivated and cannot take a new stream.")]
+    RewardStreamRetired,
+
+    #[msg("Only the proposer can cancel a proposal that is still current.")]
+    NotProposer,
 }
@@ -0,0 +1,319 @@
+// MasterChef 式奖励记账的纯计算部分，不读写任何账户，便于单独测试。
+// 每份奖励 `accumulated_reward_per_share` 放大 `COMPUTATION_DECIMALS` 倍保存；
+// 用户应得 = 份额 × 每份奖励 / 精度 - 奖励债务。所有计算向下取整，误差只会留在奖励账户中。
+
+use crate::constants::{BPS_DENOMINATOR, COMPUTATION_DECIMALS, EMISSION_DECAY_SCALE};
+use crate::structures::{EmissionSchedule, RateCurve};
+use crate::tools::SECONDS_IN_YEAR;
+use crate::ErrorCode;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathError {
+    Overflow,
+    Underflow,
+}
+
+impl From<MathError> for anchor_lang::error::Error {
+    fn from(error: MathError) -> Self {
+        match error {
+            MathError::Overflow => ErrorCode::Overflow.into(),
+            MathError::Underflow => ErrorCode::Underflow.into(),
+        }
+    }
+}
+
+pub type MathResult<T> = core::result::Result<T, MathError>;
+
+/// 把一笔奖励按总份额折算为每份奖励，没有份额时为 0
+pub fn reward_per_share(amount: u128, total_shares: u64) -> MathResult<u128> {
+    if total_shares == 0 {
+        return Ok(0);
+    }
+    amount
+        .checked_mul(COMPUTATION_DECIMALS as u128)
+        .map(|v| v / total_shares as u128)
+        .ok_or(MathError::Overflow)
+}
+
+/// 按速率累加 elapsed 秒的每份奖励，返回新的累计值
+pub fn accrue(
+    accumulated_reward_per_share: u128,
+    reward_per_sec: u64,
+    elapsed: u64,
+    total_shares: u64,
+) -> MathResult<u128> {
+    // u64 * u64 在 u128 内不会溢出
+    let income = reward_per_sec as u128 * elapsed as u128;
+    accrue_income(accumulated_reward_per_share, income, total_shares)
+}
+
+/// 把一段时间内排放的奖励总额累加到每份奖励上
+pub fn accrue_income(
+    accumulated_reward_per_share: u128,
+    income: u128,
+    total_shares: u64,
+) -> MathResult<u128> {
+    accumulated_reward_per_share
+        .checked_add(reward_per_share(income, total_shares)?)
+        .ok_or(MathError::Overflow)
+}
+
+/// 按收益率曲线计算的基础速率，曲线未启用时为 reward_per_sec。
+/// 两次结算之间总份额不变，速率在这段时间内也不变，所以结果与结算的次数和时机无关
+pub fn curve_rate(reward_per_sec: u64, curve: &RateCurve, total_shares: u64) -> u64 {
+    if !curve.is_enabled() {
+        return reward_per_sec;
+    }
+    // u64 * u32 在 u128 内不会溢出
+    let target = total_shares as u128 * curve.target_apr_bps as u128
+        / (BPS_DENOMINATOR as u128 * SECONDS_IN_YEAR as u128);
+    target.clamp(
+        curve.min_reward_per_sec as u128,
+        curve.max_reward_per_sec as u128,
+    ) as u64
+}
+
+/// 向下取整的 a * b / c。先把 a 按 c 拆成商和余数，b、c 不超过 u64 量级时中间结果不会溢出，
+/// 结果与直接相乘再相除完全相同
+fn mul_div(a: u128, b: u128, c: u128) -> MathResult<u128> {
+    (a / c)
+        .checked_mul(b)
+        .and_then(|v| (a % c).checked_mul(b).map(|rem| v.checked_add(rem / c)))
+        .flatten()
+        .ok_or(MathError::Overflow)
+}
+
+/// 经过 periods 个周期后的速率系数（放大 EMISSION_DECAY_SCALE 倍），即 (decay_bps / 10000)^periods，
+/// 用平方求幂计算，每步向下取整。结果随 periods 单调不增：periods 加 1 时，
+/// 被清零的低位连乘结果不小于新置位的平方结果，而每一步取整都保持大小关系
+pub fn decay_factor(decay_bps: u16, periods: u64) -> u128 {
+    let mut factor = EMISSION_DECAY_SCALE;
+    let mut base = decay_bps as u128 * EMISSION_DECAY_SCALE / BPS_DENOMINATOR as u128;
+    let mut remaining = periods;
+    // 两个系数都不超过 EMISSION_DECAY_SCALE，乘积在 u128 内不会溢出
+    while remaining > 0 && factor > 0 {
+        if remaining & 1 == 1 {
+            factor = factor * base / EMISSION_DECAY_SCALE;
+        }
+        base = base * base / EMISSION_DECAY_SCALE;
+        remaining >>= 1;
+    }
+    factor
+}
+
+/// 时间 t 处的瞬时速率（向下取整），用于展示
+pub fn scheduled_rate(reward_per_sec: u64, schedule: &EmissionSchedule, t: u64) -> u64 {
+    if !decays(schedule) || t < schedule.start_time {
+        return reward_per_sec;
+    }
+    let periods = (t - schedule.start_time) / schedule.period_secs as u64;
+    (reward_per_sec as u128 * decay_factor(schedule.decay_bps, periods) / EMISSION_DECAY_SCALE)
+        as u64
+}
+
+/// 起点之后前 periods 个完整周期的排放总额：rate * period * (1 - f^k) / (1 - f)。
+/// 系数随周期数单调不增，所以结果随周期数单调不减
+fn full_periods_emission(
+    reward_per_sec: u64,
+    schedule: &EmissionSchedule,
+    periods: u64,
+) -> MathResult<u128> {
+    let factor = decay_factor(schedule.decay_bps, periods);
+    // u64 * u32 在 u128 内不会溢出，再乘两个 1e16 量级的系数就会溢出，所以先除后乘
+    mul_div(
+        reward_per_sec as u128 * schedule.period_secs as u128,
+        (EMISSION_DECAY_SCALE - factor) * BPS_DENOMINATOR as u128,
+        EMISSION_DECAY_SCALE * (BPS_DENOMINATOR - schedule.decay_bps as u64) as u128,
+    )
+}
+
+/// 从时间 0 到 t 按排放计划累计排放的奖励总额。整段周期按等比数列求和，
+/// 所以无论跨越多少个周期都只需常数步计算。当前周期内在前后两个整周期累计值之间线性插值，
+/// 周期末恰好等于下一个整周期的累计值，因此累计值随时间单调不减
+pub fn cumulative_emission(
+    reward_per_sec: u64,
+    schedule: &EmissionSchedule,
+    t: u64,
+) -> MathResult<u128> {
+    let rate = reward_per_sec as u128;
+    if !decays(schedule) || t <= schedule.start_time {
+        return Ok(rate * t as u128);
+    }
+    let period = schedule.period_secs as u64;
+    let elapsed = t - schedule.start_time;
+    let periods = elapsed / period;
+
+    // 起点之前按原速率排放
+    let before_start = rate * schedule.start_time as u128;
+    let full_periods = full_periods_emission(reward_per_sec, schedule, periods)?;
+    // 当前周期的排放额，系数单调不增保证不会为负
+    let current_total = full_periods_emission(reward_per_sec, schedule, periods + 1)?
+        .checked_sub(full_periods)
+        .ok_or(MathError::Underflow)?;
+    let current_period = mul_div(current_total, (elapsed % period) as u128, period as u128)?;
+
+    before_start
+        .checked_add(full_periods)
+        .and_then(|v| v.checked_add(current_period))
+        .ok_or(MathError::Overflow)
+}
+
+/// (from, to] 内按排放计划排放的奖励总额。取累计值之差，分几次结算与一次结算的总额相同
+pub fn scheduled_emission(
+    reward_per_sec: u64,
+    schedule: &EmissionSchedule,
+    from: u64,
+    to: u64,
+) -> MathResult<u128> {
+    if to <= from {
+        return Ok(0);
+    }
+    if !decays(schedule) {
+        return Ok(reward_per_sec as u128 * (to - from) as u128);
+    }
+    cumulative_emission(reward_per_sec, schedule, to)?
+        .checked_sub(cumulative_emission(reward_per_sec, schedule, from)?)
+        .ok_or(MathError::Underflow)
+}
+
+// 周期为 0 或系数不小于 100% 时按恒定速率处理，后者已由 set_emission_schedule 拒绝
+fn decays(schedule: &EmissionSchedule) -> bool {
+    !schedule.is_constant() && (schedule.decay_bps as u64) < BPS_DENOMINATOR
+}
+
+/// 份额按当前累计值应得的全部奖励，也就是结算后的奖励债务
+pub fn accumulated_reward(shares: u64, accumulated_reward_per_share: u128) -> MathResult<u128> {
+    (shares as u128)
+        .checked_mul(accumulated_reward_per_share)
+        .map(|v| v / COMPUTATION_DECIMALS as u128)
+        .ok_or(MathError::Overflow)
+}
+
+/// 自上次结算以来的待领取奖励，累计值小于奖励债务说明账目已损坏
+pub fn pending_reward(
+    shares: u64,
+    accumulated_reward_per_share: u128,
+    reward_debt: u128,
+) -> MathResult<u64> {
+    let pending = accumulated_reward(shares, accumulated_reward_per_share)?
+        .checked_sub(reward_debt)
+        .ok_or(MathError::Underflow)?;
+    u64::try_from(pending).map_err(|_| MathError::Overflow)
+}
+
+/// 把一笔奖励一次性分给当前全部份额，返回新的累计值和实际分出去的数量（向下取整）
+pub fn distribute(
+    accumulated_reward_per_share: u128,
+    amount: u64,
+    total_shares: u64,
+) -> MathResult<(u128, u64)> {
+    let per_share = reward_per_share(amount as u128, total_shares)?;
+    let accumulated_reward_per_share = accumulated_reward_per_share
+        .checked_add(per_share)
+        .ok_or(MathError::Overflow)?;
+    let distributed = accumulated_reward(total_shares, per_share)?;
+    let distributed = u64::try_from(distributed).map_err(|_| MathError::Overflow)?;
+    Ok((accumulated_reward_per_share, distributed))
+}
+
+/// 单一奖励的池子状态，与 `StakingPool` 中的同名字段含义一致
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RewardPool {
+    pub reward_per_sec: u64,
+    pub accumulated_reward_per_share: u128,
+    pub last_reward_timestamp: u64,
+    pub total_shares: u64,
+}
+
+/// 单个质押仓位的记账状态，与 `Staked` 中的同名字段含义一致
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Position {
+    pub shares: u64,
+    pub reward_debt: u128,
+    pub accumulated_reward: u64,
+}
+
+impl RewardPool {
+    pub fn new(reward_per_sec: u64, start_reward_timestamp: u64) -> Self {
+        RewardPool {
+            reward_per_sec,
+            last_reward_timestamp: start_reward_timestamp,
+            ..Default::default()
+        }
+    }
+
+    /// 结算到 now；没有份额的时段不发放奖励，只推进时间戳
+    pub fn update(&mut self, now: u64) -> MathResult<()> {
+        let elapsed = now.saturating_sub(self.last_reward_timestamp);
+        if elapsed == 0 {
+            return Ok(());
+        }
+        if self.total_shares == 0 {
+            self.last_reward_timestamp = now;
+            return Ok(());
+        }
+        self.accumulated_reward_per_share = accrue(
+            self.accumulated_reward_per_share,
+            self.reward_per_sec,
+            elapsed,
+            self.total_shares,
+        )?;
+        self.last_reward_timestamp = now;
+        Ok(())
+    }
+
+    /// 把仓位的待领取奖励记入 `accumulated_reward`，并把奖励债务对齐到当前累计值
+    pub fn settle(&mut self, position: &mut Position, now: u64) -> MathResult<()> {
+        self.update(now)?;
+        let pending = pending_reward(
+            position.shares,
+            self.accumulated_reward_per_share,
+            position.reward_debt,
+        )?;
+        position.accumulated_reward = position
+            .accumulated_reward
+            .checked_add(pending)
+            .ok_or(MathError::Overflow)?;
+        position.reward_debt =
+            accumulated_reward(position.shares, self.accumulated_reward_per_share)?;
+        Ok(())
+    }
+
+    /// 先按旧份额结算，再增加份额
+    pub fn deposit(&mut self, position: &mut Position, amount: u64, now: u64) -> MathResult<()> {
+        self.settle(position, now)?;
+        position.shares = position
+            .shares
+            .checked_add(amount)
+            .ok_or(MathError::Overflow)?;
+        self.total_shares = self
+            .total_shares
+            .checked_add(amount)
+            .ok_or(MathError::Overflow)?;
+        position.reward_debt =
+            accumulated_reward(position.shares, self.accumulated_reward_per_share)?;
+        Ok(())
+    }
+
+    /// 先按旧份额结算，再减少份额
+    pub fn withdraw(&mut self, position: &mut Position, amount: u64, now: u64) -> MathResult<()> {
+        self.settle(position, now)?;
+        position.shares = position
+            .shares
+            .checked_sub(amount)
+            .ok_or(MathError::Underflow)?;
+        self.total_shares = self
+            .total_shares
+            .checked_sub(amount)
+            .ok_or(MathError::Underflow)?;
+        position.reward_debt =
+            accumulated_reward(position.shares, self.accumulated_reward_per_share)?;
+        Ok(())
+    }
+
+    /// 结算并取走仓位的全部已记奖励
+    pub fn claim(&mut self, position: &mut Position, now: u64) -> MathResult<u64> {
+        self.settle(position, now)?;
+        Ok(core::mem::take(&mut position.accumulated_reward))
+    }
+}
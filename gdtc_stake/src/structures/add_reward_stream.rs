@@ -0,0 +1,31 @@
+use super::{AdminSet, StakingInstance};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+pub struct AddRewardStream<'info> {
+    #[account(
+        mut,
+        seeds = [crate::ADMIN_AUTHORITY_SEED, admin_set.key().as_ref()],
+        bump = admin_set.authority_bump,
+    )]
+    pub authority: Signer<'info>, // 管理员集合的 admin_authority PDA，只能通过时间锁提案签名
+    #[account(
+        seeds = [crate::ADMIN_SET_SEED, admin_set.create_key.as_ref()],
+        bump = admin_set.bump,
+    )]
+    pub admin_set: Account<'info, AdminSet>, // 管理员集合
+    #[account(
+        mut,
+        seeds = [
+            crate::STAKING_SEED,
+            staking_instance.staking_token_mint_seed(),
+            staking_instance.reward_token_mint_seed(),
+            &staking_instance.instance_id_seed()
+        ],
+        bump = staking_instance.bump,
+    )]
+    pub staking_instance: Account<'info, StakingInstance>,
+    pub reward_token_mint: InterfaceAccount<'info, Mint>, // 合作方奖励代币 Mint
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>, // 合作方奖励金库，须由实例的 LP 权限 PDA 持有
+}
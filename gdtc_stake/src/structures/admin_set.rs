@@ -0,0 +1,171 @@
+use super::{AdminSet, Proposal};
+use anchor_lang::prelude::*;
+
+// 创建多签管理员集合。create_key 必须签名，避免他人抢先占用同一地址
+#[derive(Accounts)]
+pub struct CreateAdminSet<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>, // 支付集合租金的账户
+    pub create_key: Signer<'info>, // 派生集合地址的一次性公钥
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + AdminSet::INIT_SPACE,
+        seeds = [crate::ADMIN_SET_SEED, create_key.key().as_ref()],
+        bump,
+    )]
+    pub admin_set: Account<'info, AdminSet>,
+    /// CHECK: only the address is used; it signs proposals through `invoke_signed`
+    #[account(
+        seeds = [crate::ADMIN_AUTHORITY_SEED, admin_set.key().as_ref()],
+        bump,
+    )]
+    pub admin_authority: UncheckedAccount<'info>, // 集合的执行权限 PDA
+    pub system_program: Program<'info, System>,
+}
+
+// 修改成员、门限或时间锁延迟，只能由集合自己的 admin_authority PDA 通过提案调用
+#[derive(Accounts)]
+pub struct SetAdminConfig<'info> {
+    #[account(
+        mut,
+        seeds = [crate::ADMIN_SET_SEED, admin_set.create_key.as_ref()],
+        bump = admin_set.bump,
+    )]
+    pub admin_set: Account<'info, AdminSet>,
+    #[account(
+        seeds = [crate::ADMIN_AUTHORITY_SEED, admin_set.key().as_ref()],
+        bump = admin_set.authority_bump,
+    )]
+    pub admin_authority: Signer<'info>,
+}
+
+// 成员发起提案，提案人支付提案租金，执行后退回
+#[derive(Accounts)]
+pub struct Propose<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>, // 提案人，必须是集合成员
+    #[account(
+        mut,
+        seeds = [crate::ADMIN_SET_SEED, admin_set.create_key.as_ref()],
+        bump = admin_set.bump,
+    )]
+    pub admin_set: Account<'info, AdminSet>,
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + Proposal::INIT_SPACE,
+        seeds = [
+            crate::PROPOSAL_SEED,
+            admin_set.key().as_ref(),
+            &admin_set.proposal_count.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    pub system_program: Program<'info, System>,
+}
+
+// 成员批准提案
+#[derive(Accounts)]
+pub struct ApproveProposal<'info> {
+    pub member: Signer<'info>, // 集合成员
+    #[account(
+        seeds = [crate::ADMIN_SET_SEED, admin_set.create_key.as_ref()],
+        bump = admin_set.bump,
+    )]
+    pub admin_set: Account<'info, AdminSet>,
+    #[account(
+        mut,
+        has_one = admin_set,
+        seeds = [
+            crate::PROPOSAL_SEED,
+            admin_set.key().as_ref(),
+            &proposal.id.to_le_bytes()
+        ],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+// 任何人都可以把达到门限的提案放入时间锁队列
+#[derive(Accounts)]
+pub struct QueueProposal<'info> {
+    #[account(
+        seeds = [crate::ADMIN_SET_SEED, admin_set.create_key.as_ref()],
+        bump = admin_set.bump,
+    )]
+    pub admin_set: Account<'info, AdminSet>,
+    #[account(
+        mut,
+        has_one = admin_set,
+        seeds = [
+            crate::PROPOSAL_SEED,
+            admin_set.key().as_ref(),
+            &proposal.id.to_le_bytes()
+        ],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+// 提案人可以在执行前撤回提案；成员变更后过期的提案任何成员都可以关闭。租金退回提案人
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    pub member: Signer<'info>, // 提案人，或关闭过期提案的集合成员
+    #[account(
+        seeds = [crate::ADMIN_SET_SEED, admin_set.create_key.as_ref()],
+        bump = admin_set.bump,
+    )]
+    pub admin_set: Account<'info, AdminSet>,
+    #[account(
+        mut,
+        close = proposer,
+        has_one = admin_set,
+        has_one = proposer,
+        seeds = [
+            crate::PROPOSAL_SEED,
+            admin_set.key().as_ref(),
+            &proposal.id.to_le_bytes()
+        ],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    /// CHECK: bound to the proposal by `has_one`; only receives the rent
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>, // 提案人，接收退回的租金
+}
+
+// 任何人都可以执行时间锁已到期的提案。提案引用的账户和被调用的程序按顺序放在 remaining_accounts 中。
+// admin_set 不可写：提案可能通过 CPI 修改集合本身，指令结束时不能用旧数据覆盖
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(
+        seeds = [crate::ADMIN_SET_SEED, admin_set.create_key.as_ref()],
+        bump = admin_set.bump,
+    )]
+    pub admin_set: Account<'info, AdminSet>,
+    /// CHECK: `admin_authority` is a derived account from the program and signs through `invoke_signed`
+    #[account(
+        mut,
+        seeds = [crate::ADMIN_AUTHORITY_SEED, admin_set.key().as_ref()],
+        bump = admin_set.authority_bump,
+    )]
+    pub admin_authority: UncheckedAccount<'info>, // 提案指令的签名者，可能需要支付租金
+    #[account(
+        mut,
+        close = proposer,
+        has_one = admin_set,
+        has_one = proposer,
+        seeds = [
+            crate::PROPOSAL_SEED,
+            admin_set.key().as_ref(),
+            &proposal.id.to_le_bytes()
+        ],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    /// CHECK: bound to the proposal by `has_one`; only receives the rent
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>, // 提案人，接收退回的租金
+}
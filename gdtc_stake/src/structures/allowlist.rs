@@ -0,0 +1,105 @@
+use super::{AdminSet, AllowEntry, StakingInstance};
+use anchor_lang::prelude::*;
+
+// 管理员开关白名单模式
+#[derive(Accounts)]
+pub struct SetAllowlistMode<'info> {
+    #[account(
+        seeds = [crate::ADMIN_AUTHORITY_SEED, admin_set.key().as_ref()],
+        bump = admin_set.authority_bump,
+    )]
+    pub authority: Signer<'info>, // 管理员集合的 admin_authority PDA，只能通过时间锁提案签名
+    #[account(
+        seeds = [crate::ADMIN_SET_SEED, admin_set.create_key.as_ref()],
+        bump = admin_set.bump,
+    )]
+    pub admin_set: Account<'info, AdminSet>, // 管理员集合
+    #[account(
+        mut,
+        seeds = [
+            crate::STAKING_SEED,
+            staking_instance.staking_token_mint_seed(),
+            staking_instance.reward_token_mint_seed(),
+            &staking_instance.instance_id_seed()
+        ],
+        bump = staking_instance.bump,
+    )]
+    pub staking_instance: Account<'info, StakingInstance>,
+}
+
+// 管理员把钱包加入白名单，管理员支付条目的租金
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct AddAllowEntry<'info> {
+    #[account(
+        mut,
+        seeds = [crate::ADMIN_AUTHORITY_SEED, admin_set.key().as_ref()],
+        bump = admin_set.authority_bump,
+    )]
+    pub authority: Signer<'info>, // 管理员集合的 admin_authority PDA，只能通过时间锁提案签名
+    #[account(
+        seeds = [crate::ADMIN_SET_SEED, admin_set.create_key.as_ref()],
+        bump = admin_set.bump,
+    )]
+    pub admin_set: Account<'info, AdminSet>, // 管理员集合
+    #[account(
+        seeds = [
+            crate::STAKING_SEED,
+            staking_instance.staking_token_mint_seed(),
+            staking_instance.reward_token_mint_seed(),
+            &staking_instance.instance_id_seed()
+        ],
+        bump = staking_instance.bump,
+    )]
+    pub staking_instance: Account<'info, StakingInstance>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AllowEntry::INIT_SPACE,
+        seeds = [
+            crate::ALLOW_ENTRY_SEED,
+            staking_instance.key().as_ref(),
+            wallet.as_ref()
+        ],
+        bump,
+    )]
+    pub allow_entry: Account<'info, AllowEntry>,
+    pub system_program: Program<'info, System>,
+}
+
+// 管理员把钱包移出白名单，租金退回管理员；已有的质押不受影响
+#[derive(Accounts)]
+pub struct RemoveAllowEntry<'info> {
+    #[account(
+        mut,
+        seeds = [crate::ADMIN_AUTHORITY_SEED, admin_set.key().as_ref()],
+        bump = admin_set.authority_bump,
+    )]
+    pub authority: Signer<'info>, // 管理员集合的 admin_authority PDA，只能通过时间锁提案签名
+    #[account(
+        seeds = [crate::ADMIN_SET_SEED, admin_set.create_key.as_ref()],
+        bump = admin_set.bump,
+    )]
+    pub admin_set: Account<'info, AdminSet>, // 管理员集合
+    #[account(
+        seeds = [
+            crate::STAKING_SEED,
+            staking_instance.staking_token_mint_seed(),
+            staking_instance.reward_token_mint_seed(),
+            &staking_instance.instance_id_seed()
+        ],
+        bump = staking_instance.bump,
+    )]
+    pub staking_instance: Account<'info, StakingInstance>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            crate::ALLOW_ENTRY_SEED,
+            staking_instance.key().as_ref(),
+            allow_entry.wallet.as_ref()
+        ],
+        bump = allow_entry.bump,
+    )]
+    pub allow_entry: Account<'info, AllowEntry>,
+}
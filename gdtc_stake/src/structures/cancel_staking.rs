@@ -1,28 +1,75 @@
 use super::StakingInstance;
 use super::User;
+use crate::ErrorCode;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
 
 #[derive(Accounts)]
 pub struct CancelStaking<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [
+            crate::STAKING_SEED,
+            staking_instance.staking_token_mint_seed(),
+            staking_instance.reward_token_mint_seed(),
+            &staking_instance.instance_id_seed()
+        ],
+        bump = staking_instance.bump,
+        has_one = staking_token_mint @ ErrorCode::MintAccountIsNotMatch,
+    )]
     pub staking_instance: Account<'info, StakingInstance>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [
+            crate::USER_SEED,
+            staking_instance.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = user_instance.bump,
+        constraint = user_instance.user_address == authority.key() @ ErrorCode::UserAccountIsNotMatch,
+    )]
     pub user_instance: Account<'info, User>,
-    #[account(mut)]
-    pub user_lp_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub gdtc_lp_in_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = user_lp_token_account.mint == staking_instance.staking_token_mint @ ErrorCode::MintAccountIsNotMatch,
+        constraint = user_lp_token_account.owner == authority.key() @ ErrorCode::UserAccountIsNotMatch,
+    )]
+    pub user_lp_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        address = staking_instance.lp_token_account @ ErrorCode::MintAccountIsNotMatch,
+    )]
+    pub gdtc_lp_in_account: InterfaceAccount<'info, TokenAccount>,
+    pub staking_token_mint: InterfaceAccount<'info, Mint>, // 质押代币 Mint，transfer_checked 需要
 
     /// CHECK: `pda_account` is a derived account from the program, and we ensure it's valid at runtime
     #[account(
         mut,
-        seeds = [crate::LPTOKEN_SEED.as_ref()], 
-        bump,
+        seeds = [
+            crate::LPTOKEN_SEED,
+            staking_instance.lp_authority_seed(&staking_instance.key())
+        ],
+        bump = staking_instance.lp_authority_bump,
     )]
     pub pda_account: AccountInfo<'info>, // PDA 账户
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> CancelStaking<'info> {
+    pub fn into_transfer_to_user_context(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: self.gdtc_lp_in_account.to_account_info(),
+                mint: self.staking_token_mint.to_account_info(),
+                to: self.user_lp_token_account.to_account_info(),
+                authority: self.pda_account.to_account_info(),
+            },
+        )
+    }
 }
@@ -1,32 +1,94 @@
 use super::StakingInstance;
 use super::User;
+use crate::ErrorCode;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
 
 #[derive(Accounts)]
 pub struct ClaimRewards<'info> {
     #[account(mut)]
     pub authority: Signer<'info>, //签名用户
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [
+            crate::STAKING_SEED,
+            staking_instance.staking_token_mint_seed(),
+            staking_instance.reward_token_mint_seed(),
+            &staking_instance.instance_id_seed()
+        ],
+        bump = staking_instance.bump,
+        has_one = reward_token_mint @ ErrorCode::MintAccountIsNotMatch,
+    )]
     pub staking_instance: Account<'info, StakingInstance>, //程序状态账户
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [
+            crate::USER_SEED,
+            staking_instance.key().as_ref(),
+            user_instance.user_address.as_ref()
+        ],
+        bump = user_instance.bump,
+    )]
     pub user_instance: Box<Account<'info, User>>, // 用户状态账户
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [
+            crate::USER_SEED,
+            staking_instance.key().as_ref(),
+            user_super_gdtc_token_account.owner.as_ref()
+        ],
+        bump = super_instance.bump,
+        constraint = super_instance.user_address == user_super_gdtc_token_account.owner @ ErrorCode::UserAccountIsNotMatch,
+    )]
     pub super_instance: Box<Account<'info, User>>, //上级状态账户
-    #[account(mut)]
-    pub user_super_gdtc_token_account: Box<Account<'info, TokenAccount>>, //上级的gdtc token账户
-    #[account(mut)]
-    pub user_gdtc_token_account: Account<'info, TokenAccount>, // 用户gdtc token账户
-    #[account(mut)]
-    pub gdtc_reward_out_account: Account<'info, TokenAccount>, //合约转出gdtc 的token账户
-    
+    #[account(
+        mut,
+        address = user_instance.user_superior_token_account @ ErrorCode::MintAccountIsNotMatch,
+        constraint = user_super_gdtc_token_account.mint == staking_instance.reward_token_mint @ ErrorCode::MintAccountIsNotMatch,
+    )]
+    pub user_super_gdtc_token_account: Box<InterfaceAccount<'info, TokenAccount>>, //上级的gdtc token账户
+    #[account(
+        mut,
+        constraint = user_gdtc_token_account.mint == staking_instance.reward_token_mint @ ErrorCode::MintAccountIsNotMatch,
+        constraint = user_gdtc_token_account.owner == user_instance.user_address @ ErrorCode::UserAccountIsNotMatch,
+    )]
+    pub user_gdtc_token_account: Box<InterfaceAccount<'info, TokenAccount>>, // 用户gdtc token账户
+    #[account(
+        mut,
+        constraint = gdtc_reward_out_account.mint == staking_instance.reward_token_mint @ ErrorCode::MintAccountIsNotMatch,
+        constraint = gdtc_reward_out_account.owner == pda_account.key() @ ErrorCode::PdaAccountIsNotMatch,
+    )]
+    pub gdtc_reward_out_account: Box<InterfaceAccount<'info, TokenAccount>>, //合约转出gdtc 的token账户
+    pub reward_token_mint: Box<InterfaceAccount<'info, Mint>>, // 奖励代币 Mint，transfer_checked 需要
+
     /// CHECK: `pda_account` is a derived account from the program, and we ensure it's valid at runtime
     #[account(
         mut,
-        seeds = [crate::LPTOKEN_SEED.as_ref()], 
-        bump,
+        seeds = [
+            crate::LPTOKEN_SEED,
+            staking_instance.lp_authority_seed(&staking_instance.key())
+        ],
+        bump = staking_instance.lp_authority_bump,
     )]
     pub pda_account: AccountInfo<'info>, //合约pda账户
     pub system_program: Program<'info, System>, //系统账户 programid
-    pub token_program: Program<'info, Token>,   //token账户 可从sdk里导入
+    pub token_program: Interface<'info, TokenInterface>, //token账户 可从sdk里导入
+}
+
+impl<'info> ClaimRewards<'info> {
+    /// 从合约奖励账户转出 GDTC，`to` 为用户或上级的 token 账户
+    pub fn into_transfer_reward_context(
+        &self,
+        to: AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: self.gdtc_reward_out_account.to_account_info(),
+                mint: self.reward_token_mint.to_account_info(),
+                to,
+                authority: self.pda_account.to_account_info(),
+            },
+        )
+    }
 }
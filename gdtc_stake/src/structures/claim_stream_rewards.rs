@@ -0,0 +1,103 @@
+use super::StakingInstance;
+use super::User;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+
+#[derive(Accounts)]
+pub struct ClaimStreamRewards<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>, //签名用户
+    #[account(
+        mut,
+        seeds = [
+            crate::STAKING_SEED,
+            staking_instance.staking_token_mint_seed(),
+            staking_instance.reward_token_mint_seed(),
+            &staking_instance.instance_id_seed()
+        ],
+        bump = staking_instance.bump,
+    )]
+    pub staking_instance: Account<'info, StakingInstance>, //程序状态账户
+    #[account(
+        mut,
+        seeds = [
+            crate::USER_SEED,
+            staking_instance.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = user_instance.bump,
+    )]
+    pub user_instance: Box<Account<'info, User>>, // 用户状态账户
+    #[account(mut)]
+    pub user_reward_token_account: Box<InterfaceAccount<'info, TokenAccount>>, // 用户的合作方奖励 token 账户
+    #[account(mut)]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>, // 合作方奖励金库
+    pub reward_token_mint: Box<InterfaceAccount<'info, Mint>>, // 合作方奖励代币 Mint
+
+    /// CHECK: `pda_account` is a derived account from the program, and we ensure it's valid at runtime
+    #[account(
+        seeds = [
+            crate::LPTOKEN_SEED,
+            staking_instance.lp_authority_seed(&staking_instance.key())
+        ],
+        bump = staking_instance.lp_authority_bump,
+    )]
+    pub pda_account: AccountInfo<'info>, //合约pda账户
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// 一次领取全部奖励流时，remaining_accounts 按奖励流顺序依次传入
+// (reward_token_mint, reward_vault, user_reward_token_account)，未启用的奖励流跳过
+#[derive(Accounts)]
+pub struct ClaimAllStreamRewards<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>, //签名用户
+    #[account(
+        mut,
+        seeds = [
+            crate::STAKING_SEED,
+            staking_instance.staking_token_mint_seed(),
+            staking_instance.reward_token_mint_seed(),
+            &staking_instance.instance_id_seed()
+        ],
+        bump = staking_instance.bump,
+    )]
+    pub staking_instance: Account<'info, StakingInstance>, //程序状态账户
+    #[account(
+        mut,
+        seeds = [
+            crate::USER_SEED,
+            staking_instance.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = user_instance.bump,
+    )]
+    pub user_instance: Box<Account<'info, User>>, // 用户状态账户
+
+    /// CHECK: `pda_account` is a derived account from the program, and we ensure it's valid at runtime
+    #[account(
+        seeds = [
+            crate::LPTOKEN_SEED,
+            staking_instance.lp_authority_seed(&staking_instance.key())
+        ],
+        bump = staking_instance.lp_authority_bump,
+    )]
+    pub pda_account: AccountInfo<'info>, //合约pda账户
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> ClaimStreamRewards<'info> {
+    pub fn into_transfer_to_user_context(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: self.reward_vault.to_account_info(),
+                mint: self.reward_token_mint.to_account_info(),
+                to: self.user_reward_token_account.to_account_info(),
+                authority: self.pda_account.to_account_info(),
+            },
+        )
+    }
+}
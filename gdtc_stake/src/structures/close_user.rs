@@ -0,0 +1,29 @@
+use super::{StakingInstance, User};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseUser<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>, // 用户本人，账户关闭后租金退回此地址
+    #[account(
+        seeds = [
+            crate::STAKING_SEED,
+            staking_instance.staking_token_mint_seed(),
+            staking_instance.reward_token_mint_seed(),
+            &staking_instance.instance_id_seed()
+        ],
+        bump = staking_instance.bump,
+    )]
+    pub staking_instance: Account<'info, StakingInstance>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            crate::USER_SEED,
+            staking_instance.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = user_instance.bump,
+    )]
+    pub user_instance: Account<'info, User>,
+}
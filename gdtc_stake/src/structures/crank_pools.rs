@@ -0,0 +1,18 @@
+use super::StakingInstance;
+use anchor_lang::prelude::*;
+
+// 任何人都可以调用，只推进奖励累计值，不需要签名者
+#[derive(Accounts)]
+pub struct CrankPools<'info> {
+    #[account(
+        mut,
+        seeds = [
+            crate::STAKING_SEED,
+            staking_instance.staking_token_mint_seed(),
+            staking_instance.reward_token_mint_seed(),
+            &staking_instance.instance_id_seed()
+        ],
+        bump = staking_instance.bump,
+    )]
+    pub staking_instance: Account<'info, StakingInstance>,
+}
@@ -0,0 +1,63 @@
+use super::{DepositApproval, StakingInstance};
+use anchor_lang::prelude::*;
+
+// 受益人授权出资人为自己 deposit_for，受益人支付授权记录的租金
+#[derive(Accounts)]
+#[instruction(depositor: Pubkey)]
+pub struct ApproveDepositor<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>, // 受益人
+    #[account(
+        seeds = [
+            crate::STAKING_SEED,
+            staking_instance.staking_token_mint_seed(),
+            staking_instance.reward_token_mint_seed(),
+            &staking_instance.instance_id_seed()
+        ],
+        bump = staking_instance.bump,
+    )]
+    pub staking_instance: Account<'info, StakingInstance>,
+    #[account(
+        init,
+        payer = beneficiary,
+        space = 8 + DepositApproval::INIT_SPACE,
+        seeds = [
+            crate::DEPOSIT_APPROVAL_SEED,
+            staking_instance.key().as_ref(),
+            beneficiary.key().as_ref(),
+            depositor.as_ref()
+        ],
+        bump,
+    )]
+    pub deposit_approval: Account<'info, DepositApproval>,
+    pub system_program: Program<'info, System>,
+}
+
+// 受益人撤销授权，租金退回受益人；已记入的仓位不受影响
+#[derive(Accounts)]
+pub struct RevokeDepositor<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>, // 受益人
+    #[account(
+        seeds = [
+            crate::STAKING_SEED,
+            staking_instance.staking_token_mint_seed(),
+            staking_instance.reward_token_mint_seed(),
+            &staking_instance.instance_id_seed()
+        ],
+        bump = staking_instance.bump,
+    )]
+    pub staking_instance: Account<'info, StakingInstance>,
+    #[account(
+        mut,
+        close = beneficiary,
+        seeds = [
+            crate::DEPOSIT_APPROVAL_SEED,
+            staking_instance.key().as_ref(),
+            beneficiary.key().as_ref(),
+            deposit_approval.depositor.as_ref()
+        ],
+        bump = deposit_approval.bump,
+    )]
+    pub deposit_approval: Account<'info, DepositApproval>,
+}
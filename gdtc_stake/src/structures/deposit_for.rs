@@ -0,0 +1,86 @@
+use super::AllowEntry;
+use super::DepositApproval;
+use super::StakingInstance;
+use super::User;
+use crate::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+
+// 签名者出资，仓位、奖励和本金归受益人的用户账户所有。出资人必须先获得受益人的授权
+#[derive(Accounts)]
+#[instruction(beneficiary: Pubkey)]
+pub struct DepositFor<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>, // 出资账户，只负责转入 LP
+    #[account(
+        mut,
+        seeds = [
+            crate::STAKING_SEED,
+            staking_instance.staking_token_mint_seed(),
+            staking_instance.reward_token_mint_seed(),
+            &staking_instance.instance_id_seed()
+        ],
+        bump = staking_instance.bump,
+        has_one = staking_token_mint @ ErrorCode::MintAccountIsNotMatch,
+    )]
+    pub staking_instance: Account<'info, StakingInstance>,
+    #[account(
+        mut,
+        seeds = [
+            crate::USER_SEED,
+            staking_instance.key().as_ref(),
+            beneficiary.as_ref()
+        ],
+        bump = user_instance.bump,
+        constraint = user_instance.user_address == beneficiary @ ErrorCode::UserAccountIsNotMatch,
+    )]
+    pub user_instance: Account<'info, User>, // 受益人的用户账户，需由受益人先 initialize_user
+    #[account(
+        mut,
+        constraint = payer_lp_token_account.mint == staking_instance.staking_token_mint @ ErrorCode::MintAccountIsNotMatch,
+        constraint = payer_lp_token_account.owner == payer.key() @ ErrorCode::UserAccountIsNotMatch,
+    )]
+    pub payer_lp_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        address = staking_instance.lp_token_account @ ErrorCode::MintAccountIsNotMatch,
+    )]
+    pub gdtc_lp_in_account: InterfaceAccount<'info, TokenAccount>,
+    pub staking_token_mint: InterfaceAccount<'info, Mint>, // 质押代币 Mint，transfer_checked 需要
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [
+            crate::ALLOW_ENTRY_SEED,
+            staking_instance.key().as_ref(),
+            beneficiary.as_ref()
+        ],
+        bump = allow_entry.bump,
+    )]
+    pub allow_entry: Option<Account<'info, AllowEntry>>, // 受益人的白名单条目，仅在白名单模式下需要
+    #[account(
+        seeds = [
+            crate::DEPOSIT_APPROVAL_SEED,
+            staking_instance.key().as_ref(),
+            beneficiary.as_ref(),
+            payer.key().as_ref()
+        ],
+        bump = deposit_approval.bump,
+    )]
+    pub deposit_approval: Account<'info, DepositApproval>, // 受益人对出资人的授权，防止他人用小额质押占满槽位或额度
+}
+
+impl<'info> DepositFor<'info> {
+    pub fn into_transfer_to_vault_context(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: self.payer_lp_token_account.to_account_info(),
+                mint: self.staking_token_mint.to_account_info(),
+                to: self.gdtc_lp_in_account.to_account_info(),
+                authority: self.payer.to_account_info(),
+            },
+        )
+    }
+}
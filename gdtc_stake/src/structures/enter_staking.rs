@@ -1,30 +1,71 @@
+use super::AllowEntry;
 use super::StakingInstance;
 use super::User;
+use crate::ErrorCode;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
 
 #[derive(Accounts)]
 pub struct EnterStaking<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [
+            crate::STAKING_SEED,
+            staking_instance.staking_token_mint_seed(),
+            staking_instance.reward_token_mint_seed(),
+            &staking_instance.instance_id_seed()
+        ],
+        bump = staking_instance.bump,
+        has_one = staking_token_mint @ ErrorCode::MintAccountIsNotMatch,
+    )]
     pub staking_instance: Account<'info, StakingInstance>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [
+            crate::USER_SEED,
+            staking_instance.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = user_instance.bump,
+        constraint = user_instance.user_address == authority.key() @ ErrorCode::UserAccountIsNotMatch,
+    )]
     pub user_instance: Account<'info, User>,
-    #[account(mut)]
-    pub user_lp_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub gdtc_lp_in_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = user_lp_token_account.mint == staking_instance.staking_token_mint @ ErrorCode::MintAccountIsNotMatch,
+        constraint = user_lp_token_account.owner == authority.key() @ ErrorCode::UserAccountIsNotMatch,
+    )]
+    pub user_lp_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        address = staking_instance.lp_token_account @ ErrorCode::MintAccountIsNotMatch,
+    )]
+    pub gdtc_lp_in_account: InterfaceAccount<'info, TokenAccount>,
+    pub staking_token_mint: InterfaceAccount<'info, Mint>, // 质押代币 Mint，transfer_checked 需要
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [
+            crate::ALLOW_ENTRY_SEED,
+            staking_instance.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = allow_entry.bump,
+    )]
+    pub allow_entry: Option<Account<'info, AllowEntry>>, // 白名单条目，仅在白名单模式下需要
 }
 
 impl<'info> EnterStaking<'info> {
-    pub fn into_transfer_to_vault_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+    pub fn into_transfer_to_vault_context(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
         CpiContext::new(
             self.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: self.user_lp_token_account.to_account_info(),
+                mint: self.staking_token_mint.to_account_info(),
                 to: self.gdtc_lp_in_account.to_account_info(),
                 authority: self.authority.to_account_info(),
             },
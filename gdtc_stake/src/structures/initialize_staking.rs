@@ -1,21 +1,27 @@
-use super::{StakingInstance, StakingPool};
+use super::StakingInstance;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount};
 
 #[derive(Accounts)]
+#[instruction(instance_id: u64)]
 pub struct InitializeStaking<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
     #[account(
         init, 
-        seeds = [crate::STAKING_SEED.as_ref()],
+        seeds = [
+            crate::STAKING_SEED,
+            staking_token_mint.key().as_ref(),
+            reward_token_mint.key().as_ref(),
+            &instance_id.to_le_bytes()
+        ],
         bump,
-        space = 8 + core::mem::size_of::<StakingInstance>()+3 * core::mem::size_of::<StakingPool>(),
+        space = 8 + StakingInstance::INIT_SPACE,
         payer = authority
     )]
     pub staking_instance: Account<'info, StakingInstance>,
-    pub reward_token_mint: Account<'info, Mint>,
-    pub staking_token_mint: Account<'info, Mint>,
-    pub lp_token_account: Account<'info, TokenAccount>,
+    pub reward_token_mint: InterfaceAccount<'info, Mint>,
+    pub staking_token_mint: InterfaceAccount<'info, Mint>,
+    pub lp_token_account: InterfaceAccount<'info, TokenAccount>,
     pub system_program: Program<'info, System>,
 }
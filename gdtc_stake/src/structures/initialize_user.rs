@@ -1,6 +1,6 @@
-use super::{StakingInstance, User};
+use super::{AllowEntry, StakingInstance, User};
 use anchor_lang::prelude::*;
-use anchor_spl::token::TokenAccount;
+use anchor_spl::token_interface::TokenAccount;
 
 #[derive(Accounts)]
 pub struct InitializeUser<'info> {
@@ -11,9 +11,9 @@ pub struct InitializeUser<'info> {
     #[account(
         init,
         payer = authority,
-        space = 8 + core::mem::size_of::<User>(),
+        space = 8 + User::INIT_SPACE,
         seeds = [
-            crate::USER_SEED.as_ref(),
+            crate::USER_SEED,
             staking_instance.key().as_ref(),
             authority.key().as_ref()
         ],
@@ -21,6 +21,15 @@ pub struct InitializeUser<'info> {
     )]
     pub user_instance: Account<'info, User>,
     
-    pub user_superior_token_account: Account<'info, TokenAccount>,
+    pub user_superior_token_account: InterfaceAccount<'info, TokenAccount>,
     pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [
+            crate::ALLOW_ENTRY_SEED,
+            staking_instance.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = allow_entry.bump,
+    )]
+    pub allow_entry: Option<Account<'info, AllowEntry>>, // 白名单条目，仅在白名单模式下需要
 }
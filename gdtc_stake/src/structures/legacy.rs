@@ -0,0 +1,144 @@
+use super::{LockTerm, Staked, StakingInstance, StakingPool, User};
+use crate::constants::{
+    DEFAULT_UTC_OFFSET_SECONDS, LEGACY_INSTANCE_ID, MAX_REWARD_STREAMS,
+    STAKING_INSTANCE_RESERVED_BYTES, USER_RESERVED_BYTES,
+};
+use anchor_lang::prelude::*;
+
+// 最初部署的账户布局（尚无 version 字段，累计奖励分摊为 u64），仅供 migrate_staking_instance / migrate_user 读取旧账户。
+// 字段顺序与类型必须与旧版本保持一致，否则 Borsh 反序列化和旧账户空间计算都会出错。
+
+/// 旧版 StakingInstance 账户的分配空间（与旧版 initialize_staking 的 space 计算方式相同）
+pub const STAKING_INSTANCE_V1_SPACE: usize =
+    8 + core::mem::size_of::<StakingInstanceV1>() + 3 * core::mem::size_of::<StakingPoolV1>();
+
+/// 旧版 User 账户的分配空间（与旧版 initialize_user 的 space 计算方式相同）
+pub const USER_V1_SPACE: usize = 8 + core::mem::size_of::<UserV1>();
+
+/// 旧版按池子下标固定锁仓 3 / 6 / 12 个月
+pub const V1_LOCK_MONTHS: [u64; 3] = [3, 6, 12];
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct StakingInstanceV1 {
+    pub authority: Pubkey,
+    pub reward_token_mint: Pubkey,
+    pub staking_token_mint: Pubkey,
+    pub pools: [StakingPoolV1; 3],
+    pub lp_token_account: Pubkey,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct StakingPoolV1 {
+    pub stake_type: u64,
+    pub reward_token_per_sec: u64,
+    pub accumulated_reward_per_share: u64,
+    pub last_reward_timestamp: u64,
+    pub total_shares: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UserV1 {
+    pub total_deposited_amount: u64,
+    pub user_superior_token_account: Pubkey,
+    pub staked_info: [StakedV1; 10],
+    pub isinit: bool,
+    pub user_address: Pubkey,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct StakedV1 {
+    pub deposited_amount: u64,
+    pub reward_debt: u64,
+    pub accumulated_reward: u64,
+    pub is_staked: bool,
+    pub stake_type: u64,
+    pub stake_start_time: u64,
+    pub stake_end_time: u64,
+    pub received_reward: u64,
+    pub can_cancel_stake: bool,
+}
+
+impl From<StakingInstanceV1> for StakingInstance {
+    fn from(legacy: StakingInstanceV1) -> Self {
+        let [pool_0, pool_1, pool_2] = legacy.pools;
+        StakingInstance {
+            authority: legacy.authority,
+            reward_token_mint: legacy.reward_token_mint,
+            staking_token_mint: legacy.staking_token_mint,
+            pools: [
+                StakingPool::from_v1(pool_0, V1_LOCK_MONTHS[0]),
+                StakingPool::from_v1(pool_1, V1_LOCK_MONTHS[1]),
+                StakingPool::from_v1(pool_2, V1_LOCK_MONTHS[2]),
+            ],
+            lp_token_account: legacy.lp_token_account,
+            // 单例实例的地址只由 STAKING_SEED 派生，用保留编号标记
+            instance_id: LEGACY_INSTANCE_ID,
+            treasury_lp_token_account: Pubkey::default(),
+            // 旧版固定按东八区计算到期日
+            utc_offset_seconds: DEFAULT_UTC_OFFSET_SECONDS,
+            version: 0,
+            bump: 0,
+            lp_authority_bump: 0,
+            allowlist_enabled: false,
+            stake_limits: Default::default(),
+            emission_schedules: Default::default(),
+            rate_curves: Default::default(),
+            reward_liability: 0,
+            reserved: [0; STAKING_INSTANCE_RESERVED_BYTES],
+        }
+    }
+}
+
+impl StakingPool {
+    // 旧版没有奖励流和提前解押，新增的配置都取关闭时的默认值
+    fn from_v1(legacy: StakingPoolV1, lock_months: u64) -> Self {
+        StakingPool {
+            stake_type: legacy.stake_type,
+            reward_token_per_sec: legacy.reward_token_per_sec,
+            accumulated_reward_per_share: legacy.accumulated_reward_per_share as u128,
+            last_reward_timestamp: legacy.last_reward_timestamp,
+            total_shares: legacy.total_shares,
+            reward_streams: Default::default(),
+            early_unstake_enabled: false,
+            penalty_bps: 0,
+            reward_forfeit_bps: 0,
+            penalty_decays: false,
+            redistribute_forfeits: false,
+            lock_term: LockTerm::CalendarMonths(lock_months),
+        }
+    }
+}
+
+impl From<UserV1> for User {
+    fn from(legacy: UserV1) -> Self {
+        User {
+            total_deposited_amount: legacy.total_deposited_amount,
+            user_superior_token_account: legacy.user_superior_token_account,
+            staked_info: legacy.staked_info.map(Staked::from),
+            isinit: legacy.isinit,
+            user_address: legacy.user_address,
+            version: 0,
+            bump: 0,
+            stake_slots: [0; 10],
+            reserved: [0; USER_RESERVED_BYTES],
+        }
+    }
+}
+
+impl From<StakedV1> for Staked {
+    fn from(legacy: StakedV1) -> Self {
+        Staked {
+            deposited_amount: legacy.deposited_amount,
+            reward_debt: legacy.reward_debt as u128,
+            accumulated_reward: legacy.accumulated_reward,
+            is_staked: legacy.is_staked,
+            stake_type: legacy.stake_type,
+            stake_start_time: legacy.stake_start_time,
+            stake_end_time: legacy.stake_end_time,
+            receivedReward: legacy.received_reward,
+            can_cancel_stake: legacy.can_cancel_stake,
+            stream_reward_debt: [0; MAX_REWARD_STREAMS],
+            stream_accumulated_reward: [0; MAX_REWARD_STREAMS],
+        }
+    }
+}
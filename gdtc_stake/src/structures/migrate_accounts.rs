@@ -0,0 +1,34 @@
+use super::StakingInstance;
+use anchor_lang::prelude::*;
+
+// 旧布局的账户无法按新结构反序列化，这里以 UncheckedAccount 接收，在指令中手动校验
+
+#[derive(Accounts)]
+pub struct MigrateStakingInstance<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>, // 管理员账户，同时支付扩容所需的租金
+    /// CHECK: owner, discriminator, layout and PDA address are verified in the instruction
+    #[account(mut)]
+    pub staking_instance: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateUser<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>, // 任何人都可以代付租金完成迁移，迁移结果是确定的
+    #[account(
+        seeds = [
+            crate::STAKING_SEED,
+            staking_instance.staking_token_mint_seed(),
+            staking_instance.reward_token_mint_seed(),
+            &staking_instance.instance_id_seed()
+        ],
+        bump = staking_instance.bump,
+    )]
+    pub staking_instance: Account<'info, StakingInstance>, // 需先完成实例迁移
+    /// CHECK: owner, discriminator, layout and PDA address are verified in the instruction
+    #[account(mut)]
+    pub user_instance: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
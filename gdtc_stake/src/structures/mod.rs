@@ -1,30 +1,174 @@
+use crate::constants::{
+    ADMIN_SET_RESERVED_BYTES, ALLOW_ENTRY_RESERVED_BYTES, DEPOSIT_APPROVAL_RESERVED_BYTES,
+    LEGACY_INSTANCE_ID, MAX_ADMIN_MEMBERS,
+    MAX_PROPOSAL_ACCOUNTS, MAX_PROPOSAL_DATA_LEN, MAX_REWARD_STREAMS, PROPOSAL_RESERVED_BYTES,
+    STAKING_INSTANCE_RESERVED_BYTES, USER_RESERVED_BYTES,
+};
 use anchor_lang::prelude::*;
+pub mod add_reward_stream;
+pub mod admin_set;
+pub mod allowlist;
 pub mod cancel_staking;
 pub mod claim_rewards;
+pub mod claim_stream_rewards;
+pub mod close_user;
+pub mod crank_pools;
+pub mod deposit_approval;
+pub mod deposit_for;
+pub mod early_unstake;
 pub mod enter_staking;
 pub mod initialize_staking;
 pub mod initialize_user;
+pub mod legacy;
+pub mod migrate_accounts;
+pub mod set_early_unstake_policy;
+pub mod set_pool_config;
+pub mod sweep;
+pub mod voting_power;
 
 // staking structures
 #[account]
+#[derive(InitSpace)]
 pub struct StakingInstance {
     pub authority: Pubkey,          // 管理员账户
     pub reward_token_mint: Pubkey,  // 奖励代币 Mint 地址
     pub staking_token_mint: Pubkey, // 质押代币 Mint 地址
     pub pools: [StakingPool; 3],    // 固定3个质押池
     pub lp_token_account: Pubkey,   //合约接受lp的合约地址
+    pub instance_id: u64,           // 实例编号，同一对 mint 可部署多个实例
+    pub treasury_lp_token_account: Pubkey, // 提前解押 LP 罚金的接收账户
+    pub utc_offset_seconds: i32,    // 计算锁仓到期日所用的时区偏移（秒）
+    // 以下字段必须位于末尾：旧账户扩容后新增字节为 0，读出的 version 为 0 即表示待迁移
+    pub version: u8,                // 账户布局版本
+    pub bump: u8,                   // 实例 PDA 的 bump（version 2 起，取自预留字节）
+    pub lp_authority_bump: u8,      // LP/奖励金库权限 PDA 的 bump（version 2 起）
+    pub allowlist_enabled: bool,    // 是否只允许白名单钱包质押（version 3 起）
+    pub stake_limits: [StakeLimits; 3], // 各池的质押数量限制，与 pools 一一对应（version 4 起）
+    pub emission_schedules: [EmissionSchedule; 3], // 各池主奖励的排放计划，与 pools 一一对应（version 5 起）
+    pub rate_curves: [RateCurve; 3], // 各池按总份额计算主奖励速率的曲线，与 pools 一一对应（version 6 起）
+    pub reward_liability: u64,       // 已排放但尚未支付的 GDTC 奖励上界，含 10% 推荐奖励（version 7 起）
+    pub reserved: [u8; STAKING_INSTANCE_RESERVED_BYTES], // 预留给后续新增字段
 }
 
-#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+// 实例 PDA 和 LP 权限 PDA 的种子。最初部署的单例实例只用 STAKING_SEED / LPTOKEN_SEED 派生，
+// 其余种子取空切片：派生地址只取决于种子拼接后的字节，空种子不影响结果
+impl StakingInstance {
+    pub fn is_legacy(&self) -> bool {
+        self.instance_id == LEGACY_INSTANCE_ID
+    }
+
+    pub fn staking_token_mint_seed(&self) -> &[u8] {
+        if self.is_legacy() {
+            &[]
+        } else {
+            self.staking_token_mint.as_ref()
+        }
+    }
+
+    pub fn reward_token_mint_seed(&self) -> &[u8] {
+        if self.is_legacy() {
+            &[]
+        } else {
+            self.reward_token_mint.as_ref()
+        }
+    }
+
+    pub fn instance_id_seed(&self) -> Vec<u8> {
+        if self.is_legacy() {
+            Vec::new()
+        } else {
+            self.instance_id.to_le_bytes().to_vec()
+        }
+    }
+
+    pub fn lp_authority_seed<'a>(&self, staking_instance: &'a Pubkey) -> &'a [u8] {
+        if self.is_legacy() {
+            &[]
+        } else {
+            staking_instance.as_ref()
+        }
+    }
+}
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, InitSpace)]
 pub struct StakingPool {
     pub stake_type: u64, // 0 代表3个月，1 代表6个月，2代表12个月
     pub reward_token_per_sec: u64, // 每秒奖励代币数量
-    pub accumulated_reward_per_share: u64, // 累计奖励分摊
+    pub accumulated_reward_per_share: u128, // 累计奖励分摊（放大 COMPUTATION_DECIMALS 倍）
     pub last_reward_timestamp: u64, // 上次更新奖励的时间戳
     pub total_shares: u64, // 该池中质押的总份额
+    pub reward_streams: [RewardStream; MAX_REWARD_STREAMS], // 合作方追加的奖励流
+    pub early_unstake_enabled: bool, // 是否允许提前解押
+    pub penalty_bps: u16,            // 提前解押扣除的 LP 比例（基点）
+    pub reward_forfeit_bps: u16,     // 提前解押放弃的未领取奖励比例（基点）
+    pub penalty_decays: bool,        // 罚金是否随到期线性递减至 0
+    pub redistribute_forfeits: bool, // 放弃的奖励是否分给池内剩余质押者
+    pub lock_term: LockTerm,         // 锁仓期限
+}
+
+// 单个质押池的质押数量限制，上限为 0 表示不限制；旧账户迁移后全部为 0
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct StakeLimits {
+    pub min_stake: u64,          // 单笔质押的最小数量
+    pub max_stake_per_user: u64, // 单个用户在该池的质押总量上限
+    pub pool_capacity: u64,      // 该池总份额上限
+}
+
+// 池子主奖励的排放计划：start_time 之前按 reward_token_per_sec 排放，之后每过 period_secs 秒
+// 速率乘以 decay_bps / 10000（5000 即减半）；period_secs 为 0 表示恒定速率，旧账户迁移后即为此值
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct EmissionSchedule {
+    pub start_time: u64,  // 第一个衰减周期的起点
+    pub period_secs: u32, // 每个周期的秒数，0 表示不衰减
+    pub decay_bps: u16,   // 每个周期结束后速率保留的比例（基点），必须小于 10000
+}
+
+impl EmissionSchedule {
+    pub fn is_constant(&self) -> bool {
+        self.period_secs == 0
+    }
+}
+
+// 按目标年化计算主奖励速率：速率 = total_shares * target_apr_bps / 10000 / 一年秒数，再限制在
+// [min_reward_per_sec, max_reward_per_sec] 之间。年化按 1 份质押对应 1 单位奖励代币计算，两种代币价格
+// 不同时由管理员折算进 target_apr_bps；target_apr_bps 为 0 表示不启用，仍使用 reward_token_per_sec
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct RateCurve {
+    pub target_apr_bps: u32,     // 目标年化（基点）
+    pub min_reward_per_sec: u64, // 速率下限
+    pub max_reward_per_sec: u64, // 速率上限
+}
+
+impl RateCurve {
+    pub fn is_enabled(&self) -> bool {
+        self.target_apr_bps > 0
+    }
+}
+
+// 锁仓期限：按日历月（到期日随起始日期变化）或固定秒数（时长恒定，便于报价 APR）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub enum LockTerm {
+    CalendarMonths(u64),
+    FixedSeconds(u64),
+}
+
+// 合作方奖励流，与 GDTC 主奖励共享池子的份额和时间戳
+#[derive(Debug, Clone, Default, AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct RewardStream {
+    pub reward_token_mint: Pubkey, // 奖励代币 Mint 地址，默认值表示未启用
+    pub reward_vault: Pubkey,      // 奖励金库地址，由实例的 LP 权限 PDA 持有
+    pub reward_token_per_sec: u64, // 每秒奖励代币数量
+    pub accumulated_reward_per_share: u128, // 累计奖励分摊（放大 COMPUTATION_DECIMALS 倍）
+}
+
+impl RewardStream {
+    pub fn is_active(&self) -> bool {
+        self.reward_token_mint != Pubkey::default()
+    }
 }
 
 #[account]
+#[derive(InitSpace)]
 pub struct User {
     //这个字段必须第一位
     pub total_deposited_amount: u64, // 用户总存入的质押金额
@@ -32,12 +176,17 @@ pub struct User {
     pub staked_info: [Staked; 10],   // 固定10个质押池
     pub isinit: bool,
     pub user_address: Pubkey,
+    // 以下字段必须位于末尾，含义同 StakingInstance
+    pub version: u8,                         // 账户布局版本
+    pub bump: u8,                            // 用户 PDA 的 bump（version 2 起，取自预留字节）
+    pub stake_slots: [u64; 10], // 各槽位最近一次质押时的 slot，与 staked_info 一一对应，0 表示在 version 3 之前质押（version 3 起）
+    pub reserved: [u8; USER_RESERVED_BYTES], // 预留给后续新增字段
 }
 
-#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, InitSpace)]
 pub struct Staked {
     pub deposited_amount: u64,   // 用户总存入的质押金额
-    pub reward_debt: u64,        // 用户奖励债务
+    pub reward_debt: u128,       // 用户奖励债务
     pub accumulated_reward: u64, // 用户累计获得的奖励
     pub is_staked: bool,         // 用户是否已质押
     pub stake_type: u64,         // 质押类型
@@ -45,4 +194,74 @@ pub struct Staked {
     pub stake_end_time: u64,     // 质押结束时间（Unix 时间戳）
     pub receivedReward: u64,     //已领取收益
     pub can_cancel_stake: bool,  //是否可以解除质押
+    pub stream_reward_debt: [u128; MAX_REWARD_STREAMS], // 各奖励流的奖励债务
+    pub stream_accumulated_reward: [u64; MAX_REWARD_STREAMS], // 各奖励流待领取的奖励
+}
+
+// 白名单条目，每个实例每个钱包一个，由管理员创建和删除，存在即表示允许质押
+#[account]
+#[derive(InitSpace)]
+pub struct AllowEntry {
+    pub staking_instance: Pubkey, // 所属质押实例
+    pub wallet: Pubkey,           // 允许质押的钱包地址
+    pub version: u8,              // 账户布局版本
+    pub bump: u8,                 // 条目 PDA 的 bump
+    pub reserved: [u8; ALLOW_ENTRY_RESERVED_BYTES], // 预留给后续新增字段
+}
+
+// 受益人对出资人的授权，每个实例每对受益人/出资人一个，由受益人创建和删除，存在即表示允许该出资人 deposit_for
+#[account]
+#[derive(InitSpace)]
+pub struct DepositApproval {
+    pub staking_instance: Pubkey, // 所属质押实例
+    pub beneficiary: Pubkey,      // 受益人钱包地址
+    pub depositor: Pubkey,        // 获准代为质押的出资人
+    pub version: u8,              // 账户布局版本
+    pub bump: u8,                 // 授权 PDA 的 bump
+    pub reserved: [u8; DEPOSIT_APPROVAL_RESERVED_BYTES], // 预留给后续新增字段
+}
+
+// 多签管理员集合：把它的 admin_authority PDA 设为质押实例或抽奖合约的 authority 后，
+// 管理指令只能以提案形式经门限数量的成员批准后执行
+#[account]
+#[derive(InitSpace)]
+pub struct AdminSet {
+    pub create_key: Pubkey,                   // 创建时签名的任意公钥，用于派生集合地址
+    pub members: [Pubkey; MAX_ADMIN_MEMBERS], // 成员列表，前 member_count 个有效
+    pub member_count: u8,                     // 成员数量
+    pub threshold: u8,                        // 执行提案所需的批准数
+    pub generation: u32,                      // 成员或门限每变更一次加 1，之前的提案随之失效
+    pub proposal_count: u64,                  // 已创建的提案数，即下一个提案的编号
+    pub version: u8,                          // 账户布局版本
+    pub bump: u8,                             // 集合 PDA 的 bump
+    pub authority_bump: u8,                   // admin_authority PDA 的 bump
+    pub delay_secs: u64, // 提案排队后到可执行的延迟，低于 MIN_TIMELOCK_DELAY_SECS 时按最小值处理（version 2 起）
+    pub reserved: [u8; ADMIN_SET_RESERVED_BYTES], // 预留给后续新增字段
+}
+
+// 提案：待 admin_authority PDA 签名执行的一条指令
+#[account]
+#[derive(InitSpace)]
+pub struct Proposal {
+    pub admin_set: Pubkey,  // 所属管理员集合
+    pub id: u64,            // 提案编号
+    pub proposer: Pubkey,   // 提案人，执行后租金退回
+    pub generation: u32,    // 创建时管理员集合的 generation
+    pub program_id: Pubkey, // 被调用的程序
+    pub accounts: [ProposalAccount; MAX_PROPOSAL_ACCOUNTS], // 指令账户，前 account_count 个有效
+    pub account_count: u8,
+    pub data: [u8; MAX_PROPOSAL_DATA_LEN], // 指令数据，前 data_len 字节有效
+    pub data_len: u16,
+    pub approvals: u16, // 批准位图，第 i 位对应 members[i]
+    pub version: u8,    // 账户布局版本
+    pub bump: u8,       // 提案 PDA 的 bump
+    pub eta: u64,       // 排队后的最早执行时间，0 表示尚未排队（version 2 起）
+    pub reserved: [u8; PROPOSAL_RESERVED_BYTES], // 预留给后续新增字段
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct ProposalAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
 }
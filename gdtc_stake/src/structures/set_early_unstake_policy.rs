@@ -0,0 +1,30 @@
+use super::{AdminSet, StakingInstance};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct SetEarlyUnstakePolicy<'info> {
+    #[account(
+        mut,
+        seeds = [crate::ADMIN_AUTHORITY_SEED, admin_set.key().as_ref()],
+        bump = admin_set.authority_bump,
+    )]
+    pub authority: Signer<'info>, // 管理员集合的 admin_authority PDA，只能通过时间锁提案签名
+    #[account(
+        seeds = [crate::ADMIN_SET_SEED, admin_set.create_key.as_ref()],
+        bump = admin_set.bump,
+    )]
+    pub admin_set: Account<'info, AdminSet>, // 管理员集合
+    #[account(
+        mut,
+        seeds = [
+            crate::STAKING_SEED,
+            staking_instance.staking_token_mint_seed(),
+            staking_instance.reward_token_mint_seed(),
+            &staking_instance.instance_id_seed()
+        ],
+        bump = staking_instance.bump,
+    )]
+    pub staking_instance: Account<'info, StakingInstance>,
+    pub treasury_lp_token_account: InterfaceAccount<'info, TokenAccount>, // 接收 LP 罚金的国库账户
+}
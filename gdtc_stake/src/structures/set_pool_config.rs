@@ -0,0 +1,58 @@
+use super::{AdminSet, StakingInstance};
+use anchor_lang::prelude::*;
+
+// 管理员修改质押池参数的通用账户结构
+#[derive(Accounts)]
+pub struct SetPoolConfig<'info> {
+    #[account(
+        mut,
+        seeds = [crate::ADMIN_AUTHORITY_SEED, admin_set.key().as_ref()],
+        bump = admin_set.authority_bump,
+    )]
+    pub authority: Signer<'info>, // 管理员集合的 admin_authority PDA，只能通过时间锁提案签名
+    #[account(
+        seeds = [crate::ADMIN_SET_SEED, admin_set.create_key.as_ref()],
+        bump = admin_set.bump,
+    )]
+    pub admin_set: Account<'info, AdminSet>, // 管理员集合
+    #[account(
+        mut,
+        seeds = [
+            crate::STAKING_SEED,
+            staking_instance.staking_token_mint_seed(),
+            staking_instance.reward_token_mint_seed(),
+            &staking_instance.instance_id_seed()
+        ],
+        bump = staking_instance.bump,
+    )]
+    pub staking_instance: Account<'info, StakingInstance>,
+}
+
+// 把质押实例的管理权限交给管理员集合的 admin_authority PDA。
+// 当前管理员若仍是单签账户，只能借此一次性移交；移交后再次调用也只能通过时间锁提案
+#[derive(Accounts)]
+pub struct SetAuthority<'info> {
+    pub authority: Signer<'info>, // 当前管理员账户
+    #[account(
+        mut,
+        seeds = [
+            crate::STAKING_SEED,
+            staking_instance.staking_token_mint_seed(),
+            staking_instance.reward_token_mint_seed(),
+            &staking_instance.instance_id_seed()
+        ],
+        bump = staking_instance.bump,
+    )]
+    pub staking_instance: Account<'info, StakingInstance>,
+    #[account(
+        seeds = [crate::ADMIN_SET_SEED, new_admin_set.create_key.as_ref()],
+        bump = new_admin_set.bump,
+    )]
+    pub new_admin_set: Account<'info, AdminSet>, // 接管的管理员集合
+    /// CHECK: only the address is used; it becomes the new authority
+    #[account(
+        seeds = [crate::ADMIN_AUTHORITY_SEED, new_admin_set.key().as_ref()],
+        bump = new_admin_set.authority_bump,
+    )]
+    pub new_admin_authority: UncheckedAccount<'info>, // 新管理员集合的 admin_authority PDA
+}
@@ -0,0 +1,65 @@
+use super::{AdminSet, StakingInstance};
+use crate::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+
+// 管理员取回误转入 LP 权限 PDA 名下账户的代币，以及奖励结束后多余的奖励代币
+#[derive(Accounts)]
+pub struct Sweep<'info> {
+    #[account(
+        seeds = [crate::ADMIN_AUTHORITY_SEED, admin_set.key().as_ref()],
+        bump = admin_set.authority_bump,
+    )]
+    pub authority: Signer<'info>, // 管理员集合的 admin_authority PDA，只能通过时间锁提案签名
+    #[account(
+        seeds = [crate::ADMIN_SET_SEED, admin_set.create_key.as_ref()],
+        bump = admin_set.bump,
+    )]
+    pub admin_set: Account<'info, AdminSet>, // 管理员集合
+    #[account(
+        seeds = [
+            crate::STAKING_SEED,
+            staking_instance.staking_token_mint_seed(),
+            staking_instance.reward_token_mint_seed(),
+            &staking_instance.instance_id_seed()
+        ],
+        bump = staking_instance.bump,
+    )]
+    pub staking_instance: Account<'info, StakingInstance>,
+    /// CHECK: `pda_account` is a derived account from the program, and we ensure it's valid at runtime
+    #[account(
+        seeds = [
+            crate::LPTOKEN_SEED,
+            staking_instance.lp_authority_seed(&staking_instance.key())
+        ],
+        bump = staking_instance.lp_authority_bump,
+    )]
+    pub pda_account: AccountInfo<'info>, // 持有各金库的 LP 权限 PDA
+    #[account(
+        mut,
+        constraint = source.owner == pda_account.key() @ ErrorCode::PdaAccountIsNotMatch,
+        constraint = source.mint == mint.key() @ ErrorCode::MintAccountIsNotMatch,
+    )]
+    pub source: InterfaceAccount<'info, TokenAccount>, // 被取回代币的 PDA 名下账户
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        constraint = destination.mint == mint.key() @ ErrorCode::MintAccountIsNotMatch,
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>, // 接收账户
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> Sweep<'info> {
+    pub fn into_transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: self.source.to_account_info(),
+                mint: self.mint.to_account_info(),
+                to: self.destination.to_account_info(),
+                authority: self.pda_account.to_account_info(),
+            },
+        )
+    }
+}
@@ -0,0 +1,26 @@
+use super::{StakingInstance, User};
+use anchor_lang::prelude::*;
+
+// 只读查询用户的治理投票权，不需要签名，其他程序可以通过 CPI 调用并读取返回值
+#[derive(Accounts)]
+pub struct VotingPower<'info> {
+    #[account(
+        seeds = [
+            crate::STAKING_SEED,
+            staking_instance.staking_token_mint_seed(),
+            staking_instance.reward_token_mint_seed(),
+            &staking_instance.instance_id_seed()
+        ],
+        bump = staking_instance.bump,
+    )]
+    pub staking_instance: Account<'info, StakingInstance>,
+    #[account(
+        seeds = [
+            crate::USER_SEED,
+            staking_instance.key().as_ref(),
+            user_instance.user_address.as_ref()
+        ],
+        bump = user_instance.bump,
+    )]
+    pub user_instance: Account<'info, User>, // 被查询的用户账户
+}
@@ -1,3 +1,6 @@
+use crate::constants::FAST_TIME_LOCK_TERMS;
+use crate::structures::LockTerm;
+
 pub const SECONDS_IN_MINUTE: u64 = 60;
 pub const SECONDS_IN_HOUR: u64 = 60 * SECONDS_IN_MINUTE;
 pub const SECONDS_IN_DAY: u64 = 24 * SECONDS_IN_HOUR;
@@ -6,95 +9,89 @@ pub const SECONDS_IN_LEAP_YEAR: u64 = 366 * SECONDS_IN_DAY;
 
 pub const DAYS_IN_MONTH: [u64; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
 pub const DAYS_IN_MONTH_LEAP_YEAR: [u64; 12] = [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
-const UTC_OFFSET_SECONDS: u64 = 8 * 60 * 60; // UTC+8 (东八区)
 
-fn is_leap_year(year: u64) -> bool {
+pub fn is_leap_year(year: u64) -> bool {
     (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
 }
 
-//参数为utc+0时间，得出结果为utc+8 日期
-pub fn timestamp_to_ymd(mut unix_timestamp: u64) -> (u64, u64, u64) {
-    let mut timestamp = unix_timestamp;
-
-    let mut year = 1970;
-
-    timestamp += UTC_OFFSET_SECONDS;
-    // 减去年份的秒数，直到找到具体年份
-    while timestamp >= 0 {
-        let year_seconds = if is_leap_year(year) {
-            SECONDS_IN_LEAP_YEAR
-        } else {
-            SECONDS_IN_YEAR
-        };
-
-        if timestamp >= year_seconds {
-            timestamp -= year_seconds;
-            year += 1;
-        } else {
-            break;
-        }
-    }
-
-    // 找到月份
-    let mut month = 0;
-    let days_in_month = if is_leap_year(year) {
-        &DAYS_IN_MONTH_LEAP_YEAR
+/// 指定年月的天数，month 取值 1..=12
+pub fn days_in_month(year: u64, month: u64) -> u64 {
+    if is_leap_year(year) {
+        DAYS_IN_MONTH_LEAP_YEAR[(month - 1) as usize]
     } else {
-        &DAYS_IN_MONTH
-    };
-
-    while timestamp >= 0 {
-        let month_days = days_in_month[month] * SECONDS_IN_DAY;
-        if timestamp >= month_days {
-            timestamp -= month_days;
-            month += 1;
-        } else {
-            break;
-        }
+        DAYS_IN_MONTH[(month - 1) as usize]
     }
+}
 
-    let day = (timestamp / SECONDS_IN_DAY) + 1;
+// 把 utc+0 时间戳换算成当地时间的秒数，早于 1970-01-01 当地时间的结果截断为 0
+fn to_local_seconds(unix_timestamp: u64, utc_offset_seconds: i32) -> u64 {
+    (unix_timestamp as i64)
+        .saturating_add(utc_offset_seconds as i64)
+        .max(0) as u64
+}
 
-    (year, (month + 1) as u64, day) // 返回年月日
+// 自 1970-01-01 起的天数转换为年月日（Howard Hinnant 的 civil_from_days 算法）
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
 }
 
-// 日期转换为 Unix 时间戳， 参数为utc+8 时间，得出utc+0 时间戳
-fn date_to_timestamp(year: u64, month: u64, day: u64) -> u64 {
-    // const SECONDS_IN_DAY: u64 = 24 * 60 * 60;
-    const DAYS_IN_MONTH: [u64; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+// 年月日转换为自 1970-01-01 起的天数（days_from_civil 算法），year 不早于 1970
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let yoe = year - era * 400;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
 
-    let mut timestamp = 0;
+/// 参数为utc+0时间，得出结果为 utc+utc_offset_seconds 当地日期
+pub fn timestamp_to_ymd(unix_timestamp: u64, utc_offset_seconds: i32) -> (u64, u64, u64) {
+    civil_from_days(to_local_seconds(unix_timestamp, utc_offset_seconds) / SECONDS_IN_DAY)
+}
 
-    // 加上前面的年数
-    for y in 1970..year {
-        timestamp += if is_leap_year(y) {
-            SECONDS_IN_DAY * 366
-        } else {
-            SECONDS_IN_DAY * 365
-        };
-    }
+/// 日期转换为 Unix 时间戳，参数为当地日期（当天 00:00），得出utc+0 时间戳
+pub fn date_to_timestamp(year: u64, month: u64, day: u64, utc_offset_seconds: i32) -> u64 {
+    let local = days_from_civil(year, month, day) * SECONDS_IN_DAY;
+    (local as i64).saturating_sub(utc_offset_seconds as i64).max(0) as u64
+}
 
-    // 加上前面的月数
-    for m in 1..month {
-        timestamp += SECONDS_IN_DAY * if m == 2 && is_leap_year(year) {
-            29
-        } else {
-            DAYS_IN_MONTH[(m - 1) as usize]
-        };
-    }
+/// 在当地日期上增加若干个月，目标月份没有该日时取当月最后一天（如 1 月 31 日加 3 个月为 4 月 30 日）
+pub fn add_months(year: u64, month: u64, day: u64, months_to_add: u64) -> (u64, u64, u64) {
+    let month_index = (month - 1) + months_to_add;
+    let year = year + month_index / 12;
+    let month = month_index % 12 + 1;
+    (year, month, day.min(days_in_month(year, month)))
+}
 
-    // 加上天数
-    timestamp += SECONDS_IN_DAY * (day - 1);
-    timestamp -= UTC_OFFSET_SECONDS;
+/// 在购买时间上增加若干个日历月，按 utc_offset_seconds 所在时区的日历计算，保留购买时刻的当地时分秒
+pub fn add_calendar_months(purchase_timestamp: u64, months: u64, utc_offset_seconds: i32) -> u64 {
+    let (year, month, day) = timestamp_to_ymd(purchase_timestamp, utc_offset_seconds);
+    let time_of_day = to_local_seconds(purchase_timestamp, utc_offset_seconds) % SECONDS_IN_DAY;
 
-    timestamp
+    // 计算新日期，月底自动对齐
+    let (year, month, day) = add_months(year, month, day, months);
+
+    // 返回计算后的时间戳
+    date_to_timestamp(year, month, day, utc_offset_seconds) + time_of_day
 }
 
 /// 根据质押类型，计算出时间戳，stake_type 为 0 代表三个月，1 代表六个月，2 代表十二个月，
-pub fn generate_release_timestamps(purchase_timestamp: u64, stake_type: u64) -> u64 {
-    let (mut year, mut month, mut day) = timestamp_to_ymd(purchase_timestamp);
-    let addtime = date_to_timestamp(year, month, day);
-    let add = purchase_timestamp - addtime;
+pub fn generate_release_timestamps(
+    purchase_timestamp: u64,
+    stake_type: u64,
+    utc_offset_seconds: i32,
+) -> u64 {
     // 根据质押类型设置需要加的月份数
     let months_to_add = match stake_type {
         0 => 3,        // 三个月
@@ -102,37 +99,29 @@ pub fn generate_release_timestamps(purchase_timestamp: u64, stake_type: u64) ->
         2 => 12,       // 十二个月
         _ => return 0, // 无效的 stake_type，返回 0
     };
+    add_calendar_months(purchase_timestamp, months_to_add, utc_offset_seconds)
+}
 
-    // 计算新日期
-    for _ in 0..months_to_add {
-        // 更新到下一个月
-        if month == 12 {
-            month = 1;
-            year += 1;
-        } else {
-            month += 1;
-        }
+/// 质押时实际使用的锁仓期限，启用 `fast-time` 特性时替换为分钟级期限，
+/// QA 可在 devnet 上一小时内走完 质押 → 到期 → 领取 → 解押 的完整流程
+pub fn effective_lock_term(stake_type: u64, configured: LockTerm) -> LockTerm {
+    if cfg!(feature = "fast-time") {
+        FAST_TIME_LOCK_TERMS[stake_type as usize]
+    } else {
+        configured
     }
-
-    // 返回计算后的时间戳
-    date_to_timestamp(year, month, day) + add
 }
 
-/// 根据质押类型，计算出时间戳，stake_type 为 0 代表30分钟，1代表60分钟，2代表120分钟
-pub fn test_generate_release_timestamp(purchase_timestamp: u64, stake_type: u64) -> u64 {
-    let (mut year, mut month, mut day) = timestamp_to_ymd(purchase_timestamp);
-
-    // 根据质押类型设置需要加的分钟数
-    let minutes_to_add = match stake_type {
-        0 => 30,       // 30分钟
-        1 => 60,       // 60分钟
-        2 => 120,      // 120分钟
-        _ => return 0, // 无效的 stake_type，返回 0
-    };
-
-    // 将分钟转换为秒数
-    let seconds_to_add = minutes_to_add * 60;
-
-    // 增加秒数（转换为时间戳）
-    purchase_timestamp + seconds_to_add
+/// 根据池子的锁仓期限计算到期时间戳
+pub fn lock_end_timestamp(
+    purchase_timestamp: u64,
+    lock_term: LockTerm,
+    utc_offset_seconds: i32,
+) -> u64 {
+    match lock_term {
+        LockTerm::CalendarMonths(months) => {
+            add_calendar_months(purchase_timestamp, months, utc_offset_seconds)
+        }
+        LockTerm::FixedSeconds(seconds) => purchase_timestamp.saturating_add(seconds),
+    }
 }
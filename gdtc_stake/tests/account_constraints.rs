@@ -0,0 +1,1050 @@
+mod common;
+
+use std::collections::BTreeSet;
+
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::Bumps;
+use anchor_spl::token::spl_token::state::{Account as SplTokenAccount, AccountState, Mint};
+use gdtc_stake::constants::{
+    ADMIN_SET_RESERVED_BYTES, ADMIN_SET_SEED, ADMIN_SET_VERSION, ALLOW_ENTRY_RESERVED_BYTES,
+    ALLOW_ENTRY_SEED, ALLOW_ENTRY_VERSION, DEPOSIT_APPROVAL_RESERVED_BYTES, DEPOSIT_APPROVAL_SEED,
+    DEPOSIT_APPROVAL_VERSION, LEGACY_INSTANCE_ID, LPTOKEN_SEED, MAX_ADMIN_MEMBERS,
+    MAX_PROPOSAL_ACCOUNTS, MAX_PROPOSAL_DATA_LEN, MIN_TIMELOCK_DELAY_SECS, PROPOSAL_RESERVED_BYTES,
+    PROPOSAL_SEED, PROPOSAL_VERSION, STAKING_SEED, USER_SEED,
+};
+use gdtc_stake::structures::admin_set::{CancelProposal, ExecuteProposal, SetAdminConfig};
+use gdtc_stake::structures::cancel_staking::CancelStaking;
+use gdtc_stake::structures::claim_rewards::ClaimRewards;
+use gdtc_stake::structures::crank_pools::CrankPools;
+use gdtc_stake::structures::deposit_for::DepositFor;
+use gdtc_stake::structures::early_unstake::EarlyUnstake;
+use gdtc_stake::structures::enter_staking::EnterStaking;
+use gdtc_stake::structures::migrate_accounts::MigrateUser;
+use gdtc_stake::structures::set_pool_config::{SetAuthority, SetPoolConfig};
+use gdtc_stake::structures::sweep::Sweep;
+use gdtc_stake::structures::voting_power::VotingPower;
+use gdtc_stake::structures::{
+    AdminSet, AllowEntry, DepositApproval, Proposal, ProposalAccount, StakingInstance, User,
+};
+use gdtc_stake::{
+    find_admin_authority_address, find_lp_authority_address, find_staking_instance_address,
+    ErrorCode,
+};
+
+// 直接调用 Anchor 生成的 try_accounts，只检查账户约束，不执行指令本身
+
+#[derive(Clone)]
+struct Fixture {
+    key: Pubkey,
+    owner: Pubkey,
+    data: Vec<u8>,
+    is_signer: bool,
+    executable: bool,
+}
+
+fn program_account<T: AccountSerialize>(key: Pubkey, value: &T) -> Fixture {
+    let mut data = Vec::new();
+    value.try_serialize(&mut data).unwrap();
+    Fixture {
+        key,
+        owner: gdtc_stake::ID,
+        data,
+        is_signer: false,
+        executable: false,
+    }
+}
+
+fn token_account(mint: Pubkey, owner: Pubkey) -> Fixture {
+    let mut data = vec![0; SplTokenAccount::LEN];
+    SplTokenAccount {
+        mint,
+        owner,
+        amount: 1_000_000_000,
+        delegate: COption::None,
+        state: AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    }
+    .pack_into_slice(&mut data);
+    Fixture {
+        key: Pubkey::new_unique(),
+        owner: anchor_spl::token::ID,
+        data,
+        is_signer: false,
+        executable: false,
+    }
+}
+
+fn mint() -> Fixture {
+    let mut data = vec![0; Mint::LEN];
+    Mint {
+        mint_authority: COption::None,
+        supply: 1_000_000_000_000,
+        decimals: 9,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+    .pack_into_slice(&mut data);
+    Fixture {
+        key: Pubkey::new_unique(),
+        owner: anchor_spl::token::ID,
+        data,
+        is_signer: false,
+        executable: false,
+    }
+}
+
+fn plain(key: Pubkey, is_signer: bool) -> Fixture {
+    Fixture {
+        key,
+        owner: anchor_lang::system_program::ID,
+        data: Vec::new(),
+        is_signer,
+        executable: false,
+    }
+}
+
+fn program(key: Pubkey) -> Fixture {
+    Fixture {
+        key,
+        owner: Pubkey::default(),
+        data: Vec::new(),
+        is_signer: false,
+        executable: true,
+    }
+}
+
+fn try_accounts<T>(fixtures: Vec<Fixture>) -> Result<()>
+where
+    T: Accounts<'static, <T as Bumps>::Bumps> + Bumps,
+    <T as Bumps>::Bumps: Default,
+{
+    try_accounts_with_args::<T>(fixtures, &[])
+}
+
+// 带 #[instruction(...)] 的账户结构需要指令参数（不含鉴别符）参与种子计算
+fn try_accounts_with_args<T>(fixtures: Vec<Fixture>, ix_data: &[u8]) -> Result<()>
+where
+    T: Accounts<'static, <T as Bumps>::Bumps> + Bumps,
+    <T as Bumps>::Bumps: Default,
+{
+    let infos: Vec<AccountInfo<'static>> = fixtures
+        .into_iter()
+        .map(|fixture| {
+            AccountInfo::new(
+                Box::leak(Box::new(fixture.key)),
+                fixture.is_signer,
+                true,
+                Box::leak(Box::new(1_000_000_000)),
+                Box::leak(fixture.data.into_boxed_slice()),
+                Box::leak(Box::new(fixture.owner)),
+                fixture.executable,
+                0,
+            )
+        })
+        .collect();
+    let mut infos: &'static [AccountInfo<'static>] = Box::leak(infos.into_boxed_slice());
+    T::try_accounts(
+        &gdtc_stake::ID,
+        &mut infos,
+        ix_data,
+        &mut Default::default(),
+        &mut BTreeSet::new(),
+    )
+    .map(|_| ())
+}
+
+fn new_instance(staking_token_mint: Pubkey, reward_token_mint: Pubkey) -> StakingInstance {
+    StakingInstance {
+        reward_token_mint,
+        staking_token_mint,
+        ..common::new_instance(1)
+    }
+}
+
+fn new_user(
+    staking_instance: &Pubkey,
+    user_address: Pubkey,
+    user_superior_token_account: Pubkey,
+) -> Fixture {
+    let (address, bump) = Pubkey::find_program_address(
+        &[USER_SEED, staking_instance.as_ref(), user_address.as_ref()],
+        &gdtc_stake::ID,
+    );
+    let user = User {
+        user_superior_token_account,
+        user_address,
+        bump,
+        ..common::new_user()
+    };
+    program_account(address, &user)
+}
+
+fn allow_entry(staking_instance: &Pubkey, wallet: Pubkey) -> Fixture {
+    let (address, bump) = Pubkey::find_program_address(
+        &[ALLOW_ENTRY_SEED, staking_instance.as_ref(), wallet.as_ref()],
+        &gdtc_stake::ID,
+    );
+    let allow_entry = AllowEntry {
+        staking_instance: *staking_instance,
+        wallet,
+        version: ALLOW_ENTRY_VERSION,
+        bump,
+        reserved: [0; ALLOW_ENTRY_RESERVED_BYTES],
+    };
+    program_account(address, &allow_entry)
+}
+
+fn deposit_approval(staking_instance: &Pubkey, beneficiary: Pubkey, depositor: Pubkey) -> Fixture {
+    let (address, bump) = Pubkey::find_program_address(
+        &[
+            DEPOSIT_APPROVAL_SEED,
+            staking_instance.as_ref(),
+            beneficiary.as_ref(),
+            depositor.as_ref(),
+        ],
+        &gdtc_stake::ID,
+    );
+    let deposit_approval = DepositApproval {
+        staking_instance: *staking_instance,
+        beneficiary,
+        depositor,
+        version: DEPOSIT_APPROVAL_VERSION,
+        bump,
+        reserved: [0; DEPOSIT_APPROVAL_RESERVED_BYTES],
+    };
+    program_account(address, &deposit_approval)
+}
+
+// 一组相互匹配的账户：质押实例、用户及其上级、金库和 Mint
+#[derive(Clone)]
+struct World {
+    authority: Fixture,
+    superior: Pubkey,
+    staking_instance: Fixture,
+    user_instance: Fixture,
+    super_instance: Fixture,
+    user_lp_token_account: Fixture,
+    user_gdtc_token_account: Fixture,
+    user_super_gdtc_token_account: Fixture,
+    lp_vault: Fixture,
+    treasury_lp_token_account: Fixture,
+    reward_vault: Fixture,
+    staking_token_mint: Fixture,
+    reward_token_mint: Fixture,
+    pda_account: Fixture,
+    allow_entry: Fixture,
+    payer: Fixture,
+    payer_lp_token_account: Fixture,
+    deposit_approval: Fixture,
+    admin_set: Fixture,
+    admin_authority: Fixture,
+}
+
+impl World {
+    fn new() -> Self {
+        Self::with_instance_id(0)
+    }
+
+    fn with_instance_id(instance_id: u64) -> Self {
+        let authority = Pubkey::new_unique();
+        let superior = Pubkey::new_unique();
+        let payer = Pubkey::new_unique();
+        let staking_token_mint = mint();
+        let staking_token_mint_key = staking_token_mint.key;
+        let reward_token_mint = mint();
+
+        let mut staking_instance = new_instance(staking_token_mint.key, reward_token_mint.key);
+        staking_instance.instance_id = instance_id;
+        let (instance_address, bump) =
+            find_staking_instance_address(&staking_instance, &gdtc_stake::ID);
+        let (lp_authority, lp_authority_bump) =
+            find_lp_authority_address(&staking_instance, &instance_address, &gdtc_stake::ID);
+        let lp_vault = token_account(staking_token_mint.key, lp_authority);
+        staking_instance.bump = bump;
+        staking_instance.lp_authority_bump = lp_authority_bump;
+        staking_instance.lp_token_account = lp_vault.key;
+        let treasury_lp_token_account = token_account(staking_token_mint.key, Pubkey::new_unique());
+        staking_instance.treasury_lp_token_account = treasury_lp_token_account.key;
+        // 管理权限已移交给管理员集合
+        let (admin_set, admin_authority) = admin_set_fixture(authority);
+        staking_instance.authority = admin_authority;
+
+        let user_super_gdtc_token_account = token_account(reward_token_mint.key, superior);
+        World {
+            authority: plain(authority, true),
+            superior,
+            user_instance: new_user(
+                &instance_address,
+                authority,
+                user_super_gdtc_token_account.key,
+            ),
+            super_instance: new_user(&instance_address, superior, Pubkey::new_unique()),
+            staking_instance: program_account(instance_address, &staking_instance),
+            user_lp_token_account: token_account(staking_token_mint.key, authority),
+            user_gdtc_token_account: token_account(reward_token_mint.key, authority),
+            user_super_gdtc_token_account,
+            lp_vault,
+            treasury_lp_token_account,
+            reward_vault: token_account(reward_token_mint.key, lp_authority),
+            staking_token_mint,
+            reward_token_mint,
+            pda_account: plain(lp_authority, false),
+            // 可选账户不传时以程序 ID 占位
+            allow_entry: program(gdtc_stake::ID),
+            payer: plain(payer, true),
+            payer_lp_token_account: token_account(staking_token_mint_key, payer),
+            // 用户（authority）已授权 payer 代为质押
+            deposit_approval: deposit_approval(&instance_address, authority, payer),
+            admin_set,
+            admin_authority: plain(admin_authority, true),
+        }
+    }
+
+    fn instance_key(&self) -> Pubkey {
+        self.staking_instance.key
+    }
+
+    fn lp_authority(&self) -> Pubkey {
+        self.pda_account.key
+    }
+
+    fn enter_staking(&self) -> Result<()> {
+        try_accounts::<EnterStaking>(vec![
+            self.authority.clone(),
+            self.staking_instance.clone(),
+            self.user_instance.clone(),
+            self.user_lp_token_account.clone(),
+            self.lp_vault.clone(),
+            self.staking_token_mint.clone(),
+            program(anchor_lang::system_program::ID),
+            program(anchor_spl::token::ID),
+            self.allow_entry.clone(),
+        ])
+    }
+
+    // 第三方 payer 为 authority 出资
+    fn deposit_for(&self, beneficiary: Pubkey) -> Result<()> {
+        try_accounts_with_args::<DepositFor>(
+            vec![
+                self.payer.clone(),
+                self.staking_instance.clone(),
+                self.user_instance.clone(),
+                self.payer_lp_token_account.clone(),
+                self.lp_vault.clone(),
+                self.staking_token_mint.clone(),
+                program(anchor_spl::token::ID),
+                self.allow_entry.clone(),
+                self.deposit_approval.clone(),
+            ],
+            beneficiary.as_ref(),
+        )
+    }
+
+    // 不需要任何签名者
+    fn crank_pools(&self) -> Result<()> {
+        try_accounts::<CrankPools>(vec![self.staking_instance.clone()])
+    }
+
+    // 只读查询，不需要任何签名者
+    fn voting_power(&self) -> Result<()> {
+        try_accounts::<VotingPower>(vec![
+            self.staking_instance.clone(),
+            self.user_instance.clone(),
+        ])
+    }
+
+    fn sweep(&self, source: &Fixture, mint: &Fixture, destination: &Fixture) -> Result<()> {
+        try_accounts::<Sweep>(vec![
+            self.admin_authority.clone(),
+            self.admin_set.clone(),
+            self.staking_instance.clone(),
+            self.pda_account.clone(),
+            source.clone(),
+            mint.clone(),
+            destination.clone(),
+            program(anchor_spl::token::ID),
+        ])
+    }
+
+    fn set_pool_config(&self, authority: &Fixture) -> Result<()> {
+        try_accounts::<SetPoolConfig>(vec![
+            authority.clone(),
+            self.admin_set.clone(),
+            self.staking_instance.clone(),
+        ])
+    }
+
+    fn set_authority(&self, new_admin_set: &Fixture, new_admin_authority: Pubkey) -> Result<()> {
+        try_accounts::<SetAuthority>(vec![
+            self.authority.clone(),
+            self.staking_instance.clone(),
+            new_admin_set.clone(),
+            plain(new_admin_authority, false),
+        ])
+    }
+
+    // 用户账户以 UncheckedAccount 接收，只校验实例
+    fn migrate_user(&self) -> Result<()> {
+        try_accounts::<MigrateUser>(vec![
+            self.authority.clone(),
+            self.staking_instance.clone(),
+            self.user_instance.clone(),
+            program(anchor_lang::system_program::ID),
+        ])
+    }
+
+    fn cancel_staking(&self) -> Result<()> {
+        try_accounts::<CancelStaking>(vec![
+            self.authority.clone(),
+            self.staking_instance.clone(),
+            self.user_instance.clone(),
+            self.user_lp_token_account.clone(),
+            self.lp_vault.clone(),
+            self.staking_token_mint.clone(),
+            self.pda_account.clone(),
+            program(anchor_lang::system_program::ID),
+            program(anchor_spl::token::ID),
+        ])
+    }
+
+    fn claim_rewards(&self) -> Result<()> {
+        try_accounts::<ClaimRewards>(vec![
+            self.authority.clone(),
+            self.staking_instance.clone(),
+            self.user_instance.clone(),
+            self.super_instance.clone(),
+            self.user_super_gdtc_token_account.clone(),
+            self.user_gdtc_token_account.clone(),
+            self.reward_vault.clone(),
+            self.reward_token_mint.clone(),
+            self.pda_account.clone(),
+            program(anchor_lang::system_program::ID),
+            program(anchor_spl::token::ID),
+        ])
+    }
+
+    fn early_unstake(&self) -> Result<()> {
+        try_accounts::<EarlyUnstake>(vec![
+            self.authority.clone(),
+            self.staking_instance.clone(),
+            self.user_instance.clone(),
+            self.super_instance.clone(),
+            self.user_super_gdtc_token_account.clone(),
+            self.user_lp_token_account.clone(),
+            self.lp_vault.clone(),
+            self.treasury_lp_token_account.clone(),
+            self.staking_token_mint.clone(),
+            self.user_gdtc_token_account.clone(),
+            self.reward_vault.clone(),
+            self.reward_token_mint.clone(),
+            self.pda_account.clone(),
+            program(anchor_spl::token::ID),
+        ])
+    }
+}
+
+fn seeds_error() -> Error {
+    AnchorErrorCode::ConstraintSeeds.into()
+}
+
+#[test]
+fn matching_accounts_pass_every_constraint() {
+    let world = World::new();
+    assert!(world.enter_staking().is_ok());
+    assert!(world.deposit_for(world.authority.key).is_ok());
+    assert!(world.cancel_staking().is_ok());
+    assert!(world.claim_rewards().is_ok());
+    assert!(world.early_unstake().is_ok());
+    assert!(world.crank_pools().is_ok());
+}
+
+#[test]
+fn staking_instance_must_be_the_canonical_pda() {
+    // 内容相同但地址不同的实例账户
+    let mut world = World::new();
+    world.staking_instance.key = Pubkey::new_unique();
+    assert_eq!(world.enter_staking(), Err(seeds_error()));
+    assert_eq!(world.cancel_staking(), Err(seeds_error()));
+    assert_eq!(world.claim_rewards(), Err(seeds_error()));
+    assert_eq!(world.crank_pools(), Err(seeds_error()));
+}
+
+#[test]
+fn legacy_singleton_instance_keeps_its_original_addresses() {
+    // 最初部署的实例只由 STAKING_SEED 派生，金库权限只由 LPTOKEN_SEED 派生，迁移后地址不变
+    let world = World::with_instance_id(LEGACY_INSTANCE_ID);
+    assert_eq!(
+        world.instance_key(),
+        Pubkey::find_program_address(&[STAKING_SEED], &gdtc_stake::ID).0
+    );
+    assert_eq!(
+        world.lp_authority(),
+        Pubkey::find_program_address(&[LPTOKEN_SEED], &gdtc_stake::ID).0
+    );
+    assert!(world.enter_staking().is_ok());
+    assert!(world.cancel_staking().is_ok());
+    assert!(world.claim_rewards().is_ok());
+    assert!(world.early_unstake().is_ok());
+    assert!(world.crank_pools().is_ok());
+    assert!(world.voting_power().is_ok());
+    assert!(world.migrate_user().is_ok());
+
+    // 其他实例不能冒用单例地址
+    let legacy_instance = world.staking_instance.clone();
+    let mut world = World::new();
+    world.staking_instance.key = legacy_instance.key;
+    assert_eq!(world.enter_staking(), Err(seeds_error()));
+    assert_eq!(world.migrate_user(), Err(seeds_error()));
+}
+
+#[test]
+fn user_instance_must_belong_to_the_signer() {
+    let mut world = World::new();
+    world.user_instance = new_user(
+        &world.instance_key(),
+        Pubkey::new_unique(),
+        world.user_super_gdtc_token_account.key,
+    );
+    assert_eq!(world.enter_staking(), Err(seeds_error()));
+    assert_eq!(world.cancel_staking(), Err(seeds_error()));
+
+    // 用户账户属于另一个质押实例
+    let mut world = World::new();
+    world.user_instance = new_user(
+        &Pubkey::new_unique(),
+        world.authority.key,
+        world.user_super_gdtc_token_account.key,
+    );
+    assert_eq!(world.enter_staking(), Err(seeds_error()));
+    assert_eq!(world.cancel_staking(), Err(seeds_error()));
+    assert_eq!(world.claim_rewards(), Err(seeds_error()));
+}
+
+#[test]
+fn staking_token_mint_must_match_the_instance() {
+    let mut world = World::new();
+    world.staking_token_mint = mint();
+    assert_eq!(
+        world.enter_staking(),
+        Err(ErrorCode::MintAccountIsNotMatch.into())
+    );
+    assert_eq!(
+        world.cancel_staking(),
+        Err(ErrorCode::MintAccountIsNotMatch.into())
+    );
+    assert_eq!(
+        world.early_unstake(),
+        Err(ErrorCode::MintAccountIsNotMatch.into())
+    );
+}
+
+#[test]
+fn user_lp_token_account_must_match_mint_and_owner() {
+    let mut world = World::new();
+    world.user_lp_token_account = token_account(world.reward_token_mint.key, world.authority.key);
+    assert_eq!(
+        world.enter_staking(),
+        Err(ErrorCode::MintAccountIsNotMatch.into())
+    );
+    assert_eq!(
+        world.cancel_staking(),
+        Err(ErrorCode::MintAccountIsNotMatch.into())
+    );
+    assert_eq!(
+        world.early_unstake(),
+        Err(ErrorCode::MintAccountIsNotMatch.into())
+    );
+
+    // 解押时 LP 不能转到别人的账户
+    let mut world = World::new();
+    world.user_lp_token_account = token_account(world.staking_token_mint.key, Pubkey::new_unique());
+    assert_eq!(
+        world.enter_staking(),
+        Err(ErrorCode::UserAccountIsNotMatch.into())
+    );
+    assert_eq!(
+        world.cancel_staking(),
+        Err(ErrorCode::UserAccountIsNotMatch.into())
+    );
+}
+
+#[test]
+fn lp_vault_must_be_the_instance_vault() {
+    // 同一 Mint、同一 PDA 持有，但不是实例登记的金库
+    let mut world = World::new();
+    world.lp_vault = token_account(world.staking_token_mint.key, world.lp_authority());
+    assert_eq!(
+        world.enter_staking(),
+        Err(ErrorCode::MintAccountIsNotMatch.into())
+    );
+    assert_eq!(
+        world.cancel_staking(),
+        Err(ErrorCode::MintAccountIsNotMatch.into())
+    );
+    assert_eq!(
+        world.early_unstake(),
+        Err(ErrorCode::MintAccountIsNotMatch.into())
+    );
+}
+
+#[test]
+fn treasury_must_be_the_instance_treasury() {
+    // 同一 Mint 的其他 LP 账户，不是实例登记的罚金接收账户
+    let mut world = World::new();
+    world.treasury_lp_token_account =
+        token_account(world.staking_token_mint.key, Pubkey::new_unique());
+    assert_eq!(
+        world.early_unstake(),
+        Err(ErrorCode::InvalidTreasuryAccount.into())
+    );
+}
+
+#[test]
+fn pda_account_must_be_the_instance_lp_authority() {
+    let mut world = World::new();
+    world.pda_account = plain(Pubkey::new_unique(), false);
+    assert_eq!(world.cancel_staking(), Err(seeds_error()));
+    // 奖励金库在 pda_account 之前校验，金库持有人与替换的账户不一致
+    assert_eq!(
+        world.claim_rewards(),
+        Err(ErrorCode::PdaAccountIsNotMatch.into())
+    );
+
+    // 金库也一并换成假 PDA 持有的账户，仍然被 seeds 约束拒绝
+    world.reward_vault = token_account(world.reward_token_mint.key, world.pda_account.key);
+    assert_eq!(world.claim_rewards(), Err(seeds_error()));
+}
+
+#[test]
+fn reward_token_mint_must_match_the_instance() {
+    let mut world = World::new();
+    world.reward_token_mint = mint();
+    assert_eq!(
+        world.claim_rewards(),
+        Err(ErrorCode::MintAccountIsNotMatch.into())
+    );
+    assert_eq!(
+        world.early_unstake(),
+        Err(ErrorCode::MintAccountIsNotMatch.into())
+    );
+}
+
+#[test]
+fn user_gdtc_token_account_must_match_mint_and_owner() {
+    let mut world = World::new();
+    world.user_gdtc_token_account =
+        token_account(world.staking_token_mint.key, world.authority.key);
+    assert_eq!(
+        world.claim_rewards(),
+        Err(ErrorCode::MintAccountIsNotMatch.into())
+    );
+    assert_eq!(
+        world.early_unstake(),
+        Err(ErrorCode::MintAccountIsNotMatch.into())
+    );
+
+    let mut world = World::new();
+    world.user_gdtc_token_account =
+        token_account(world.reward_token_mint.key, Pubkey::new_unique());
+    assert_eq!(
+        world.claim_rewards(),
+        Err(ErrorCode::UserAccountIsNotMatch.into())
+    );
+}
+
+#[test]
+fn superior_accounts_must_match_the_user_record() {
+    // 上级名下的另一个 GDTC 账户，不是用户登记的上级账户
+    let mut world = World::new();
+    world.user_super_gdtc_token_account =
+        token_account(world.reward_token_mint.key, world.superior);
+    assert_eq!(
+        world.claim_rewards(),
+        Err(ErrorCode::MintAccountIsNotMatch.into())
+    );
+
+    // 换成别人的 token 账户时，上级用户账户的派生地址随之不匹配
+    let mut world = World::new();
+    world.user_super_gdtc_token_account =
+        token_account(world.reward_token_mint.key, Pubkey::new_unique());
+    assert_eq!(world.claim_rewards(), Err(seeds_error()));
+
+    // 上级用户账户换成其他用户的账户
+    let mut world = World::new();
+    world.super_instance = new_user(
+        &world.instance_key(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    assert_eq!(world.claim_rewards(), Err(seeds_error()));
+}
+
+#[test]
+fn reward_vault_must_be_held_by_the_lp_authority() {
+    let mut world = World::new();
+    world.reward_vault = token_account(world.reward_token_mint.key, Pubkey::new_unique());
+    assert_eq!(
+        world.claim_rewards(),
+        Err(ErrorCode::PdaAccountIsNotMatch.into())
+    );
+    assert_eq!(
+        world.early_unstake(),
+        Err(ErrorCode::PdaAccountIsNotMatch.into())
+    );
+
+    let mut world = World::new();
+    world.reward_vault = token_account(world.staking_token_mint.key, world.lp_authority());
+    assert_eq!(
+        world.claim_rewards(),
+        Err(ErrorCode::MintAccountIsNotMatch.into())
+    );
+    assert_eq!(
+        world.early_unstake(),
+        Err(ErrorCode::MintAccountIsNotMatch.into())
+    );
+}
+
+#[test]
+fn allow_entry_must_belong_to_the_signer() {
+    let mut world = World::new();
+    world.allow_entry = allow_entry(&world.instance_key(), world.authority.key);
+    assert!(world.enter_staking().is_ok());
+
+    // 别人的白名单条目
+    world.allow_entry = allow_entry(&world.instance_key(), Pubkey::new_unique());
+    assert_eq!(world.enter_staking(), Err(seeds_error()));
+
+    // 其他质押实例的白名单条目
+    world.allow_entry = allow_entry(&Pubkey::new_unique(), world.authority.key);
+    assert_eq!(world.enter_staking(), Err(seeds_error()));
+}
+
+#[test]
+fn deposit_for_credits_only_the_beneficiary_user_account() {
+    let mut world = World::new();
+    // 受益人参数与用户账户不一致
+    assert_eq!(world.deposit_for(world.superior), Err(seeds_error()));
+
+    // 受益人自己的用户账户，但传入的是其他钱包的账户
+    world.user_instance = world.super_instance.clone();
+    assert_eq!(world.deposit_for(world.authority.key), Err(seeds_error()));
+    world.deposit_approval =
+        deposit_approval(&world.instance_key(), world.superior, world.payer.key);
+    assert!(world.deposit_for(world.superior).is_ok());
+}
+
+#[test]
+fn deposit_for_needs_the_beneficiary_approval_of_the_payer() {
+    let mut world = World::new();
+    assert!(world.deposit_for(world.authority.key).is_ok());
+
+    // 受益人授权的是其他出资人
+    world.deposit_approval = deposit_approval(
+        &world.instance_key(),
+        world.authority.key,
+        Pubkey::new_unique(),
+    );
+    assert_eq!(world.deposit_for(world.authority.key), Err(seeds_error()));
+
+    // 其他受益人对该出资人的授权
+    world.deposit_approval =
+        deposit_approval(&world.instance_key(), world.superior, world.payer.key);
+    assert_eq!(world.deposit_for(world.authority.key), Err(seeds_error()));
+
+    // 其他质押实例中的授权
+    world.deposit_approval =
+        deposit_approval(&Pubkey::new_unique(), world.authority.key, world.payer.key);
+    assert_eq!(world.deposit_for(world.authority.key), Err(seeds_error()));
+
+    // 没有授权记录
+    world.deposit_approval = plain(world.deposit_approval.key, false);
+    assert_eq!(
+        world.deposit_for(world.authority.key),
+        Err(AnchorErrorCode::AccountOwnedByWrongProgram.into())
+    );
+}
+
+#[test]
+fn deposit_for_pays_from_the_payer_token_account() {
+    let mut world = World::new();
+    // 不能从受益人的 LP 账户扣款
+    world.payer_lp_token_account = world.user_lp_token_account.clone();
+    assert_eq!(
+        world.deposit_for(world.authority.key),
+        Err(ErrorCode::UserAccountIsNotMatch.into())
+    );
+
+    world.payer_lp_token_account = token_account(world.reward_token_mint.key, world.payer.key);
+    assert_eq!(
+        world.deposit_for(world.authority.key),
+        Err(ErrorCode::MintAccountIsNotMatch.into())
+    );
+}
+
+#[test]
+fn deposit_for_checks_the_beneficiary_allow_entry() {
+    let mut world = World::new();
+    world.allow_entry = allow_entry(&world.instance_key(), world.authority.key);
+    assert!(world.deposit_for(world.authority.key).is_ok());
+
+    // 出资人的白名单条目不能代替受益人的
+    world.allow_entry = allow_entry(&world.instance_key(), world.payer.key);
+    assert_eq!(world.deposit_for(world.authority.key), Err(seeds_error()));
+}
+
+#[test]
+fn sweep_only_moves_tokens_held_by_the_lp_authority() {
+    let world = World::new();
+    let destination = token_account(world.reward_token_mint.key, Pubkey::new_unique());
+    assert!(world
+        .sweep(&world.reward_vault, &world.reward_token_mint, &destination)
+        .is_ok());
+
+    // 不是 LP 权限 PDA 持有的账户
+    assert_eq!(
+        world.sweep(
+            &world.user_gdtc_token_account,
+            &world.reward_token_mint,
+            &destination
+        ),
+        Err(ErrorCode::PdaAccountIsNotMatch.into())
+    );
+
+    // source、mint 和接收账户必须是同一种代币
+    assert_eq!(
+        world.sweep(&world.lp_vault, &world.reward_token_mint, &destination),
+        Err(ErrorCode::MintAccountIsNotMatch.into())
+    );
+    assert_eq!(
+        world.sweep(&world.lp_vault, &world.staking_token_mint, &destination),
+        Err(ErrorCode::MintAccountIsNotMatch.into())
+    );
+
+    let mut world = world;
+    world.pda_account = plain(Pubkey::new_unique(), false);
+    assert_eq!(
+        world.sweep(&world.reward_vault, &world.reward_token_mint, &destination),
+        Err(seeds_error())
+    );
+}
+
+#[test]
+fn admin_setters_require_the_admin_authority_signature() {
+    let world = World::new();
+    assert!(world.set_pool_config(&world.admin_authority).is_ok());
+
+    // 单签管理员不能绕过时间锁直接修改配置
+    assert_eq!(world.set_pool_config(&world.authority), Err(seeds_error()));
+
+    let mut admin_authority = world.admin_authority.clone();
+    admin_authority.is_signer = false;
+    assert_eq!(
+        world.set_pool_config(&admin_authority),
+        Err(AnchorErrorCode::AccountNotSigner.into())
+    );
+
+    // 其他管理员集合的 admin_authority
+    let (_, other_authority) = admin_set_fixture(world.authority.key);
+    assert_eq!(
+        world.set_pool_config(&plain(other_authority, true)),
+        Err(seeds_error())
+    );
+
+    let destination = token_account(world.reward_token_mint.key, Pubkey::new_unique());
+    let mut single_key = world.clone();
+    single_key.admin_authority = world.authority.clone();
+    assert_eq!(
+        single_key.sweep(&world.reward_vault, &world.reward_token_mint, &destination),
+        Err(seeds_error())
+    );
+}
+
+#[test]
+fn set_authority_hands_over_only_to_an_admin_authority() {
+    let world = World::new();
+    let (new_admin_set, new_admin_authority) = admin_set_fixture(world.authority.key);
+    assert!(world
+        .set_authority(&new_admin_set, new_admin_authority)
+        .is_ok());
+
+    // 不能移交给任意单签账户
+    assert_eq!(
+        world.set_authority(&new_admin_set, Pubkey::new_unique()),
+        Err(seeds_error())
+    );
+    assert_eq!(
+        world.set_authority(&new_admin_set, world.admin_authority.key),
+        Err(seeds_error())
+    );
+}
+
+struct AdminWorld {
+    admin_set: Fixture,
+    admin_authority: Fixture,
+    proposal: Fixture,
+    proposer: Fixture,
+}
+
+fn admin_set_fixture(member: Pubkey) -> (Fixture, Pubkey) {
+    let create_key = Pubkey::new_unique();
+    let (address, bump) =
+        Pubkey::find_program_address(&[ADMIN_SET_SEED, create_key.as_ref()], &gdtc_stake::ID);
+    let (admin_authority, authority_bump) = find_admin_authority_address(&address, &gdtc_stake::ID);
+    let mut members = [Pubkey::default(); MAX_ADMIN_MEMBERS];
+    members[0] = member;
+    let admin_set = AdminSet {
+        create_key,
+        members,
+        member_count: 1,
+        threshold: 1,
+        generation: 0,
+        proposal_count: 1,
+        version: ADMIN_SET_VERSION,
+        bump,
+        authority_bump,
+        delay_secs: MIN_TIMELOCK_DELAY_SECS,
+        reserved: [0; ADMIN_SET_RESERVED_BYTES],
+    };
+    (program_account(address, &admin_set), admin_authority)
+}
+
+fn proposal_fixture(admin_set: &Pubkey, proposer: Pubkey) -> Fixture {
+    let (address, bump) = Pubkey::find_program_address(
+        &[PROPOSAL_SEED, admin_set.as_ref(), &0u64.to_le_bytes()],
+        &gdtc_stake::ID,
+    );
+    let proposal = Proposal {
+        admin_set: *admin_set,
+        id: 0,
+        proposer,
+        generation: 0,
+        program_id: gdtc_stake::ID,
+        accounts: [ProposalAccount::default(); MAX_PROPOSAL_ACCOUNTS],
+        account_count: 0,
+        data: [0; MAX_PROPOSAL_DATA_LEN],
+        data_len: 0,
+        approvals: 1,
+        version: PROPOSAL_VERSION,
+        bump,
+        eta: 0,
+        reserved: [0; PROPOSAL_RESERVED_BYTES],
+    };
+    program_account(address, &proposal)
+}
+
+impl AdminWorld {
+    fn new() -> Self {
+        let proposer = Pubkey::new_unique();
+        let (admin_set, admin_authority) = admin_set_fixture(proposer);
+        AdminWorld {
+            proposal: proposal_fixture(&admin_set.key, proposer),
+            admin_set,
+            admin_authority: plain(admin_authority, false),
+            proposer: plain(proposer, false),
+        }
+    }
+
+    fn execute_proposal(&self) -> Result<()> {
+        try_accounts::<ExecuteProposal>(vec![
+            self.admin_set.clone(),
+            self.admin_authority.clone(),
+            self.proposal.clone(),
+            self.proposer.clone(),
+        ])
+    }
+
+    fn cancel_proposal(&self, member: Pubkey) -> Result<()> {
+        try_accounts::<CancelProposal>(vec![
+            plain(member, true),
+            self.admin_set.clone(),
+            self.proposal.clone(),
+            self.proposer.clone(),
+        ])
+    }
+
+    fn set_admin_members(&self, admin_authority: Fixture) -> Result<()> {
+        try_accounts::<SetAdminConfig>(vec![self.admin_set.clone(), admin_authority])
+    }
+}
+
+#[test]
+fn execute_proposal_binds_the_proposal_to_its_admin_set() {
+    let world = AdminWorld::new();
+    assert!(world.execute_proposal().is_ok());
+
+    // 其他管理员集合的提案和执行权限
+    let mut other = AdminWorld::new();
+    other.proposal = world.proposal.clone();
+    assert_eq!(other.execute_proposal(), Err(seeds_error()));
+
+    let mut other = AdminWorld::new();
+    other.admin_authority = world.admin_authority.clone();
+    assert_eq!(other.execute_proposal(), Err(seeds_error()));
+
+    // 租金只能退回提案人
+    let mut world = world;
+    world.proposer = plain(Pubkey::new_unique(), false);
+    assert_eq!(
+        world.execute_proposal(),
+        Err(AnchorErrorCode::ConstraintHasOne.into())
+    );
+}
+
+#[test]
+fn set_admin_members_requires_the_admin_authority_signature() {
+    let world = AdminWorld::new();
+    let mut admin_authority = world.admin_authority.clone();
+    admin_authority.is_signer = true;
+    assert!(world.set_admin_members(admin_authority).is_ok());
+
+    // 成员本人签名也不行，必须经提案由 PDA 签名
+    assert_eq!(
+        world.set_admin_members(world.admin_authority.clone()),
+        Err(AnchorErrorCode::AccountNotSigner.into())
+    );
+    assert_eq!(
+        world.set_admin_members(plain(world.proposer.key, true)),
+        Err(seeds_error())
+    );
+}
+
+#[test]
+fn cancel_proposal_refunds_only_the_proposer() {
+    let world = AdminWorld::new();
+    assert!(world.cancel_proposal(world.proposer.key).is_ok());
+
+    let mut other = AdminWorld::new();
+    other.proposal = world.proposal.clone();
+    assert_eq!(
+        other.cancel_proposal(other.proposer.key),
+        Err(seeds_error())
+    );
+
+    let mut world = world;
+    world.proposer = plain(Pubkey::new_unique(), false);
+    assert_eq!(
+        world.cancel_proposal(world.proposer.key),
+        Err(AnchorErrorCode::ConstraintHasOne.into())
+    );
+}
+
+#[test]
+fn voting_power_reads_only_users_of_the_instance() {
+    let mut world = World::new();
+    assert!(world.voting_power().is_ok());
+    // 任何钱包的用户账户都可以查询
+    world.user_instance = world.super_instance.clone();
+    assert!(world.voting_power().is_ok());
+
+    world.user_instance = new_user(
+        &Pubkey::new_unique(),
+        world.authority.key,
+        world.user_super_gdtc_token_account.key,
+    );
+    assert_eq!(world.voting_power(), Err(seeds_error()));
+}
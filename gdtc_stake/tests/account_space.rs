@@ -0,0 +1,175 @@
+use anchor_lang::prelude::*;
+use gdtc_stake::constants::{
+    ADMIN_SET_RESERVED_BYTES, ADMIN_SET_VERSION, ALLOW_ENTRY_RESERVED_BYTES, ALLOW_ENTRY_VERSION,
+    DEPOSIT_APPROVAL_RESERVED_BYTES, DEPOSIT_APPROVAL_VERSION, MAX_ADMIN_MEMBERS,
+    MAX_PROPOSAL_ACCOUNTS, MAX_PROPOSAL_DATA_LEN, MAX_REWARD_STREAMS, PROPOSAL_RESERVED_BYTES,
+    PROPOSAL_VERSION, STAKING_INSTANCE_RESERVED_BYTES, STAKING_INSTANCE_VERSION,
+    USER_RESERVED_BYTES, USER_VERSION,
+};
+use gdtc_stake::structures::{
+    AdminSet, AllowEntry, DepositApproval, EmissionSchedule, LockTerm, Proposal, ProposalAccount,
+    RateCurve, StakeLimits, Staked, StakingInstance, StakingPool, User,
+};
+
+fn pool(stake_type: u64) -> StakingPool {
+    StakingPool {
+        stake_type,
+        reward_token_per_sec: u64::MAX,
+        accumulated_reward_per_share: u128::MAX,
+        last_reward_timestamp: u64::MAX,
+        total_shares: u64::MAX,
+        reward_streams: Default::default(),
+        early_unstake_enabled: true,
+        penalty_bps: u16::MAX,
+        reward_forfeit_bps: u16::MAX,
+        penalty_decays: true,
+        redistribute_forfeits: true,
+        lock_term: LockTerm::FixedSeconds(u64::MAX),
+    }
+}
+
+fn staked() -> Staked {
+    Staked {
+        deposited_amount: u64::MAX,
+        reward_debt: u128::MAX,
+        accumulated_reward: u64::MAX,
+        is_staked: true,
+        stake_type: 2,
+        stake_start_time: u64::MAX,
+        stake_end_time: u64::MAX,
+        receivedReward: u64::MAX,
+        can_cancel_stake: true,
+        stream_reward_debt: [u128::MAX; MAX_REWARD_STREAMS],
+        stream_accumulated_reward: [u64::MAX; MAX_REWARD_STREAMS],
+    }
+}
+
+// Borsh 编码长度与字段取值无关（无 Option / Vec），序列化结果必须正好填满账户
+fn serialized_len<T: AccountSerialize>(account: &T) -> usize {
+    let mut bytes = Vec::new();
+    account.try_serialize(&mut bytes).unwrap();
+    bytes.len()
+}
+
+#[test]
+fn staking_instance_fills_its_space_exactly() {
+    let instance = StakingInstance {
+        authority: Pubkey::new_unique(),
+        reward_token_mint: Pubkey::new_unique(),
+        staking_token_mint: Pubkey::new_unique(),
+        pools: [pool(0), pool(1), pool(2)],
+        lp_token_account: Pubkey::new_unique(),
+        instance_id: u64::MAX,
+        treasury_lp_token_account: Pubkey::new_unique(),
+        utc_offset_seconds: i32::MIN,
+        version: STAKING_INSTANCE_VERSION,
+        bump: 255,
+        lp_authority_bump: 254,
+        allowlist_enabled: true,
+        stake_limits: [StakeLimits {
+            min_stake: u64::MAX,
+            max_stake_per_user: u64::MAX,
+            pool_capacity: u64::MAX,
+        }; 3],
+        emission_schedules: [EmissionSchedule {
+            start_time: u64::MAX,
+            period_secs: u32::MAX,
+            decay_bps: u16::MAX,
+        }; 3],
+        rate_curves: [RateCurve {
+            target_apr_bps: u32::MAX,
+            min_reward_per_sec: u64::MAX,
+            max_reward_per_sec: u64::MAX,
+        }; 3],
+        reward_liability: u64::MAX,
+        reserved: [0; STAKING_INSTANCE_RESERVED_BYTES],
+    };
+    assert_eq!(serialized_len(&instance), 8 + StakingInstance::INIT_SPACE);
+}
+
+#[test]
+fn user_fills_its_space_exactly() {
+    let user = User {
+        total_deposited_amount: u64::MAX,
+        user_superior_token_account: Pubkey::new_unique(),
+        staked_info: core::array::from_fn(|_| staked()),
+        isinit: true,
+        user_address: Pubkey::new_unique(),
+        version: USER_VERSION,
+        bump: 255,
+        stake_slots: [u64::MAX; 10],
+        reserved: [0; USER_RESERVED_BYTES],
+    };
+    assert_eq!(serialized_len(&user), 8 + User::INIT_SPACE);
+}
+
+#[test]
+fn allow_entry_fills_its_space_exactly() {
+    let allow_entry = AllowEntry {
+        staking_instance: Pubkey::new_unique(),
+        wallet: Pubkey::new_unique(),
+        version: ALLOW_ENTRY_VERSION,
+        bump: 255,
+        reserved: [0; ALLOW_ENTRY_RESERVED_BYTES],
+    };
+    assert_eq!(serialized_len(&allow_entry), 8 + AllowEntry::INIT_SPACE);
+}
+
+#[test]
+fn deposit_approval_fills_its_space_exactly() {
+    let deposit_approval = DepositApproval {
+        staking_instance: Pubkey::new_unique(),
+        beneficiary: Pubkey::new_unique(),
+        depositor: Pubkey::new_unique(),
+        version: DEPOSIT_APPROVAL_VERSION,
+        bump: 255,
+        reserved: [0; DEPOSIT_APPROVAL_RESERVED_BYTES],
+    };
+    assert_eq!(
+        serialized_len(&deposit_approval),
+        8 + DepositApproval::INIT_SPACE
+    );
+}
+
+#[test]
+fn admin_set_fills_its_space_exactly() {
+    let admin_set = AdminSet {
+        create_key: Pubkey::new_unique(),
+        members: [Pubkey::new_unique(); MAX_ADMIN_MEMBERS],
+        member_count: u8::MAX,
+        threshold: u8::MAX,
+        generation: u32::MAX,
+        proposal_count: u64::MAX,
+        version: ADMIN_SET_VERSION,
+        bump: 255,
+        authority_bump: 254,
+        delay_secs: u64::MAX,
+        reserved: [0; ADMIN_SET_RESERVED_BYTES],
+    };
+    assert_eq!(serialized_len(&admin_set), 8 + AdminSet::INIT_SPACE);
+}
+
+#[test]
+fn proposal_fills_its_space_exactly() {
+    let proposal = Proposal {
+        admin_set: Pubkey::new_unique(),
+        id: u64::MAX,
+        proposer: Pubkey::new_unique(),
+        generation: u32::MAX,
+        program_id: Pubkey::new_unique(),
+        accounts: [ProposalAccount {
+            pubkey: Pubkey::new_unique(),
+            is_signer: true,
+            is_writable: true,
+        }; MAX_PROPOSAL_ACCOUNTS],
+        account_count: u8::MAX,
+        data: [u8::MAX; MAX_PROPOSAL_DATA_LEN],
+        data_len: u16::MAX,
+        approvals: u16::MAX,
+        version: PROPOSAL_VERSION,
+        bump: 255,
+        eta: u64::MAX,
+        reserved: [0; PROPOSAL_RESERVED_BYTES],
+    };
+    assert_eq!(serialized_len(&proposal), 8 + Proposal::INIT_SPACE);
+}
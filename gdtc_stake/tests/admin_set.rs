@@ -0,0 +1,313 @@
+use anchor_lang::prelude::*;
+use anchor_lang::InstructionData;
+use gdtc_stake::constants::{
+    ADMIN_SET_RESERVED_BYTES, ADMIN_SET_VERSION, MAX_ADMIN_MEMBERS, MAX_PROPOSAL_ACCOUNTS,
+    MAX_PROPOSAL_DATA_LEN, MAX_TIMELOCK_DELAY_SECS, MIN_TIMELOCK_DELAY_SECS,
+    PROPOSAL_RESERVED_BYTES, PROPOSAL_VERSION,
+};
+use gdtc_stake::structures::{AdminSet, Proposal, ProposalAccount, StakeLimits};
+use gdtc_stake::{
+    admin_member_index, approval_count, approve, check_admin_members, check_proposal_cancellable,
+    check_proposal_executable, check_timelock_delay, check_timelock_elapsed, enqueue_proposal,
+    find_admin_authority_address, proposal_instruction, store_admin_members,
+    store_proposal_instruction, timelock_delay, ErrorCode,
+};
+
+fn members(count: usize) -> Vec<Pubkey> {
+    (0..count).map(|_| Pubkey::new_unique()).collect()
+}
+
+fn new_admin_set(members: &[Pubkey], threshold: u8) -> AdminSet {
+    let mut admin_set = AdminSet {
+        create_key: Pubkey::new_unique(),
+        members: [Pubkey::default(); MAX_ADMIN_MEMBERS],
+        member_count: 0,
+        threshold: 0,
+        generation: 0,
+        proposal_count: 0,
+        version: ADMIN_SET_VERSION,
+        bump: 0,
+        authority_bump: 0,
+        delay_secs: MIN_TIMELOCK_DELAY_SECS,
+        reserved: [0; ADMIN_SET_RESERVED_BYTES],
+    };
+    store_admin_members(&mut admin_set, members, threshold).unwrap();
+    admin_set
+}
+
+fn new_proposal(admin_set: &AdminSet) -> Proposal {
+    Proposal {
+        admin_set: Pubkey::new_unique(),
+        id: 0,
+        proposer: admin_set.members[0],
+        generation: admin_set.generation,
+        program_id: Pubkey::default(),
+        accounts: [ProposalAccount::default(); MAX_PROPOSAL_ACCOUNTS],
+        account_count: 0,
+        data: [0; MAX_PROPOSAL_DATA_LEN],
+        data_len: 0,
+        approvals: 0,
+        version: PROPOSAL_VERSION,
+        bump: 0,
+        eta: 0,
+        reserved: [0; PROPOSAL_RESERVED_BYTES],
+    }
+}
+
+#[test]
+fn member_list_and_threshold_are_validated() {
+    let valid = members(3);
+    assert!(check_admin_members(&valid, 1).is_ok());
+    assert!(check_admin_members(&valid, 3).is_ok());
+    assert!(check_admin_members(&members(MAX_ADMIN_MEMBERS), 1).is_ok());
+
+    for threshold in [0, 4] {
+        assert_eq!(
+            check_admin_members(&valid, threshold).unwrap_err(),
+            ErrorCode::InvalidAdminThreshold.into()
+        );
+    }
+
+    let mut duplicated = valid.clone();
+    duplicated.push(valid[1]);
+    let mut with_default = valid.clone();
+    with_default.push(Pubkey::default());
+    for invalid in [
+        vec![],
+        members(MAX_ADMIN_MEMBERS + 1),
+        duplicated,
+        with_default,
+    ] {
+        assert_eq!(
+            check_admin_members(&invalid, 1).unwrap_err(),
+            ErrorCode::InvalidAdminMembers.into()
+        );
+    }
+}
+
+#[test]
+fn shrinking_the_member_list_clears_removed_members() {
+    let old_members = members(5);
+    let mut admin_set = new_admin_set(&old_members, 3);
+    assert_eq!(admin_member_index(&admin_set, &old_members[4]), Some(4));
+
+    let new_members = members(2);
+    store_admin_members(&mut admin_set, &new_members, 2).unwrap();
+    assert_eq!(admin_set.member_count, 2);
+    assert_eq!(admin_set.threshold, 2);
+    assert_eq!(admin_member_index(&admin_set, &new_members[1]), Some(1));
+    for old in old_members.iter() {
+        assert_eq!(admin_member_index(&admin_set, old), None);
+    }
+    assert!(admin_set.members[2..]
+        .iter()
+        .all(|member| *member == Pubkey::default()));
+
+    // 默认公钥不能借空位冒充成员
+    assert_eq!(admin_member_index(&admin_set, &Pubkey::default()), None);
+}
+
+#[test]
+fn proposal_needs_threshold_distinct_approvals() {
+    let admin_set = new_admin_set(&members(3), 2);
+    let mut proposal = new_proposal(&admin_set);
+
+    approve(&mut proposal, 0).unwrap();
+    assert_eq!(
+        approve(&mut proposal, 0).unwrap_err(),
+        ErrorCode::ProposalAlreadyApproved.into()
+    );
+    assert_eq!(approval_count(proposal.approvals), 1);
+    assert_eq!(
+        check_proposal_executable(&admin_set, &proposal).unwrap_err(),
+        ErrorCode::NotEnoughApprovals.into()
+    );
+
+    approve(&mut proposal, 2).unwrap();
+    assert_eq!(approval_count(proposal.approvals), 2);
+    assert!(check_proposal_executable(&admin_set, &proposal).is_ok());
+}
+
+#[test]
+fn changing_members_invalidates_open_proposals() {
+    let mut admin_set = new_admin_set(&members(2), 1);
+    let mut proposal = new_proposal(&admin_set);
+    approve(&mut proposal, 0).unwrap();
+    assert!(check_proposal_executable(&admin_set, &proposal).is_ok());
+
+    // set_admin_members 每次都会递增 generation
+    admin_set.generation += 1;
+    assert_eq!(
+        check_proposal_executable(&admin_set, &proposal).unwrap_err(),
+        ErrorCode::StaleProposal.into()
+    );
+}
+
+#[test]
+fn only_the_proposer_cancels_a_current_proposal() {
+    let admin_set = new_admin_set(&members(3), 2);
+    let mut proposal = new_proposal(&admin_set);
+    approve(&mut proposal, 0).unwrap();
+    approve(&mut proposal, 1).unwrap();
+    enqueue_proposal(&admin_set, &mut proposal, 1_000).unwrap();
+
+    // 单个非提案人成员不能否决已获批准并排队的提案
+    assert_eq!(
+        check_proposal_cancellable(&admin_set, &proposal, &admin_set.members[1]).unwrap_err(),
+        ErrorCode::NotProposer.into()
+    );
+    assert_eq!(
+        check_proposal_cancellable(&admin_set, &proposal, &Pubkey::new_unique()).unwrap_err(),
+        ErrorCode::NotProposer.into()
+    );
+    assert!(check_proposal_cancellable(&admin_set, &proposal, &admin_set.members[0]).is_ok());
+}
+
+#[test]
+fn any_member_closes_a_stale_proposal() {
+    let mut admin_set = new_admin_set(&members(3), 2);
+    let proposal = new_proposal(&admin_set);
+    admin_set.generation += 1;
+    assert!(check_proposal_cancellable(&admin_set, &proposal, &admin_set.members[2]).is_ok());
+    assert_eq!(
+        check_proposal_cancellable(&admin_set, &proposal, &Pubkey::new_unique()).unwrap_err(),
+        ErrorCode::NotProposer.into()
+    );
+}
+
+#[test]
+fn stored_instruction_round_trips_with_the_admin_authority_as_signer() {
+    let admin_set = new_admin_set(&members(1), 1);
+    let (admin_authority, _) = find_admin_authority_address(&Pubkey::new_unique(), &gdtc_stake::ID);
+    let staking_instance = Pubkey::new_unique();
+
+    // 一条典型的管理指令：由多签修改池子的质押数量限制
+    let limits = StakeLimits {
+        min_stake: 1_000,
+        max_stake_per_user: 10_000,
+        pool_capacity: 0,
+    };
+    let data = gdtc_stake::instruction::SetStakeLimits {
+        stake_type: 1,
+        limits,
+    }
+    .data();
+    let accounts = [
+        ProposalAccount {
+            pubkey: admin_authority,
+            is_signer: false,
+            is_writable: true,
+        },
+        ProposalAccount {
+            pubkey: staking_instance,
+            is_signer: false,
+            is_writable: true,
+        },
+    ];
+
+    let mut proposal = new_proposal(&admin_set);
+    store_proposal_instruction(&mut proposal, gdtc_stake::ID, &accounts, &data).unwrap();
+    let instruction = proposal_instruction(&proposal, &admin_authority);
+
+    assert_eq!(instruction.program_id, gdtc_stake::ID);
+    assert_eq!(instruction.data, data);
+    assert_eq!(instruction.accounts.len(), 2);
+    assert_eq!(instruction.accounts[0].pubkey, admin_authority);
+    assert!(instruction.accounts[0].is_signer);
+    assert_eq!(instruction.accounts[1].pubkey, staking_instance);
+    assert!(!instruction.accounts[1].is_signer);
+    assert!(instruction.accounts[1].is_writable);
+}
+
+#[test]
+fn oversized_instructions_are_rejected() {
+    let admin_set = new_admin_set(&members(1), 1);
+    let mut proposal = new_proposal(&admin_set);
+    let accounts = [ProposalAccount::default(); MAX_PROPOSAL_ACCOUNTS + 1];
+    let data = [0u8; MAX_PROPOSAL_DATA_LEN + 1];
+
+    assert!(store_proposal_instruction(
+        &mut proposal,
+        gdtc_stake::ID,
+        &accounts[..MAX_PROPOSAL_ACCOUNTS],
+        &data[..MAX_PROPOSAL_DATA_LEN]
+    )
+    .is_ok());
+    assert_eq!(
+        store_proposal_instruction(&mut proposal, gdtc_stake::ID, &accounts, &[]).unwrap_err(),
+        ErrorCode::ProposalTooLarge.into()
+    );
+    assert_eq!(
+        store_proposal_instruction(&mut proposal, gdtc_stake::ID, &[], &data).unwrap_err(),
+        ErrorCode::ProposalTooLarge.into()
+    );
+}
+
+#[test]
+fn timelock_delay_has_a_minimum_and_a_maximum() {
+    assert!(check_timelock_delay(MIN_TIMELOCK_DELAY_SECS).is_ok());
+    assert!(check_timelock_delay(MAX_TIMELOCK_DELAY_SECS).is_ok());
+    for delay_secs in [0, MIN_TIMELOCK_DELAY_SECS - 1, MAX_TIMELOCK_DELAY_SECS + 1] {
+        assert_eq!(
+            check_timelock_delay(delay_secs).unwrap_err(),
+            ErrorCode::InvalidTimelockDelay.into()
+        );
+    }
+
+    // version 1 的集合读出 0，按最小延迟处理
+    let mut admin_set = new_admin_set(&members(1), 1);
+    admin_set.delay_secs = 0;
+    assert_eq!(timelock_delay(&admin_set), MIN_TIMELOCK_DELAY_SECS);
+    admin_set.delay_secs = 3 * MIN_TIMELOCK_DELAY_SECS;
+    assert_eq!(timelock_delay(&admin_set), 3 * MIN_TIMELOCK_DELAY_SECS);
+}
+
+#[test]
+fn approved_proposal_executes_only_after_the_delay() {
+    let mut admin_set = new_admin_set(&members(3), 2);
+    admin_set.delay_secs = 2 * MIN_TIMELOCK_DELAY_SECS;
+    let mut proposal = new_proposal(&admin_set);
+    let now = 1_700_000_000;
+
+    // 未达到门限不能排队，未排队不能执行
+    approve(&mut proposal, 0).unwrap();
+    assert_eq!(
+        enqueue_proposal(&admin_set, &mut proposal, now).unwrap_err(),
+        ErrorCode::NotEnoughApprovals.into()
+    );
+    assert_eq!(
+        check_timelock_elapsed(&proposal, now).unwrap_err(),
+        ErrorCode::ProposalNotQueued.into()
+    );
+
+    approve(&mut proposal, 1).unwrap();
+    let eta = enqueue_proposal(&admin_set, &mut proposal, now).unwrap();
+    assert_eq!(eta, now + 2 * MIN_TIMELOCK_DELAY_SECS);
+    assert_eq!(proposal.eta, eta);
+
+    // 重新排队不能推迟或提前执行时间
+    assert_eq!(
+        enqueue_proposal(&admin_set, &mut proposal, now + 1).unwrap_err(),
+        ErrorCode::ProposalAlreadyQueued.into()
+    );
+    assert_eq!(
+        check_timelock_elapsed(&proposal, eta - 1).unwrap_err(),
+        ErrorCode::TimelockNotElapsed.into()
+    );
+    assert!(check_timelock_elapsed(&proposal, eta).is_ok());
+}
+
+#[test]
+fn queued_proposal_goes_stale_when_members_change() {
+    let mut admin_set = new_admin_set(&members(2), 1);
+    let mut proposal = new_proposal(&admin_set);
+    approve(&mut proposal, 0).unwrap();
+    let eta = enqueue_proposal(&admin_set, &mut proposal, 0).unwrap();
+
+    admin_set.generation += 1;
+    assert!(check_timelock_elapsed(&proposal, eta).is_ok());
+    assert_eq!(
+        check_proposal_executable(&admin_set, &proposal).unwrap_err(),
+        ErrorCode::StaleProposal.into()
+    );
+}
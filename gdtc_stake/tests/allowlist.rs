@@ -0,0 +1,39 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use gdtc_stake::constants::{ALLOW_ENTRY_RESERVED_BYTES, ALLOW_ENTRY_VERSION};
+use gdtc_stake::structures::{AllowEntry, StakingInstance};
+use gdtc_stake::{check_allowlisted, ErrorCode};
+
+fn new_instance(allowlist_enabled: bool) -> StakingInstance {
+    let mut instance = common::new_instance(1);
+    instance.allowlist_enabled = allowlist_enabled;
+    instance
+}
+
+fn new_entry() -> AllowEntry {
+    AllowEntry {
+        staking_instance: Pubkey::new_unique(),
+        wallet: Pubkey::new_unique(),
+        version: ALLOW_ENTRY_VERSION,
+        bump: 0,
+        reserved: [0; ALLOW_ENTRY_RESERVED_BYTES],
+    }
+}
+
+#[test]
+fn open_instance_accepts_any_wallet() {
+    let instance = new_instance(false);
+    assert!(check_allowlisted(&instance, None).is_ok());
+    assert!(check_allowlisted(&instance, Some(&new_entry())).is_ok());
+}
+
+#[test]
+fn allowlist_mode_requires_an_entry() {
+    let instance = new_instance(true);
+    assert_eq!(
+        check_allowlisted(&instance, None).unwrap_err(),
+        ErrorCode::WalletNotAllowlisted.into()
+    );
+    assert!(check_allowlisted(&instance, Some(&new_entry())).is_ok());
+}
@@ -0,0 +1,219 @@
+use chrono::{DateTime, Datelike, Months, NaiveDate};
+use gdtc_stake::constants::MAX_UTC_OFFSET_SECONDS;
+use gdtc_stake::structures::LockTerm;
+use gdtc_stake::tools::{
+    add_months, date_to_timestamp, days_in_month, effective_lock_term, generate_release_timestamps,
+    lock_end_timestamp, timestamp_to_ymd,
+};
+use gdtc_stake::{check_utc_offset, ErrorCode};
+use proptest::prelude::*;
+
+const FIRST_DAY: (i32, u32, u32) = (1970, 1, 1);
+const LAST_DAY: (i32, u32, u32) = (2100, 12, 31);
+// 2100-12-31 23:59:59 UTC
+const MAX_TIMESTAMP: u64 = 4_133_980_799;
+
+fn every_day() -> impl Iterator<Item = NaiveDate> {
+    let first = NaiveDate::from_ymd_opt(FIRST_DAY.0, FIRST_DAY.1, FIRST_DAY.2).unwrap();
+    let last = NaiveDate::from_ymd_opt(LAST_DAY.0, LAST_DAY.1, LAST_DAY.2).unwrap();
+    first.iter_days().take_while(move |date| *date <= last)
+}
+
+fn ymd(date: NaiveDate) -> (u64, u64, u64) {
+    (date.year() as u64, date.month() as u64, date.day() as u64)
+}
+
+fn months_for(stake_type: u64) -> u32 {
+    match stake_type {
+        0 => 3,
+        1 => 6,
+        _ => 12,
+    }
+}
+
+// 参考实现：在当地时间上用 chrono 加月份（chrono 在月底同样向前对齐），再换算回 utc+0
+fn reference_release(purchase_timestamp: u64, stake_type: u64, utc_offset_seconds: i32) -> u64 {
+    let local = DateTime::from_timestamp(purchase_timestamp as i64 + utc_offset_seconds as i64, 0)
+        .unwrap()
+        .naive_utc();
+    let release = local
+        .checked_add_months(Months::new(months_for(stake_type)))
+        .unwrap();
+    (release.and_utc().timestamp() - utc_offset_seconds as i64) as u64
+}
+
+#[test]
+fn date_conversion_matches_chrono_for_every_day() {
+    for date in every_day() {
+        let (year, month, day) = ymd(date);
+        let midnight = date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp() as u64;
+
+        assert_eq!(date_to_timestamp(year, month, day, 0), midnight, "{date}");
+        assert_eq!(timestamp_to_ymd(midnight, 0), (year, month, day), "{date}");
+        assert_eq!(
+            timestamp_to_ymd(midnight + 86_399, 0),
+            (year, month, day),
+            "{date}"
+        );
+        assert_eq!(
+            days_in_month(year, month),
+            NaiveDate::from_ymd_opt(date.year(), date.month(), 1)
+                .unwrap()
+                .checked_add_months(Months::new(1))
+                .unwrap()
+                .signed_duration_since(
+                    NaiveDate::from_ymd_opt(date.year(), date.month(), 1).unwrap()
+                )
+                .num_days() as u64,
+            "{date}"
+        );
+    }
+}
+
+#[test]
+fn utc_plus_eight_dates_match_chrono_for_every_day() {
+    let offset = 8 * 60 * 60;
+    // 1970-01-01 00:00 UTC+8 早于 Unix 纪元，从第二天开始比较
+    for date in every_day().skip(1) {
+        let (year, month, day) = ymd(date);
+        let local_midnight = date_to_timestamp(year, month, day, offset);
+        assert_eq!(
+            local_midnight as i64,
+            date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp() - offset as i64,
+            "{date}"
+        );
+        assert_eq!(timestamp_to_ymd(local_midnight, offset), (year, month, day));
+        assert_eq!(
+            timestamp_to_ymd(local_midnight - 1, offset),
+            ymd(date.pred_opt().unwrap())
+        );
+    }
+}
+
+#[test]
+fn month_addition_clamps_to_month_end_for_every_day() {
+    for date in every_day() {
+        let (year, month, day) = ymd(date);
+        for months in [1, 3, 6, 12] {
+            let expected = date.checked_add_months(Months::new(months)).unwrap();
+            assert_eq!(
+                add_months(year, month, day, months as u64),
+                ymd(expected),
+                "{date} + {months} months"
+            );
+        }
+    }
+}
+
+#[test]
+fn release_timestamps_match_chrono_for_every_day() {
+    let offset = 8 * 60 * 60;
+    for date in every_day() {
+        // 当地时间每天 10:30 质押
+        let purchase = date.and_hms_opt(10, 30, 0).unwrap().and_utc().timestamp() - offset as i64;
+        if purchase < 0 {
+            continue;
+        }
+        for stake_type in 0..3 {
+            assert_eq!(
+                generate_release_timestamps(purchase as u64, stake_type, offset),
+                reference_release(purchase as u64, stake_type, offset),
+                "{date} stake_type {stake_type}"
+            );
+        }
+    }
+}
+
+#[test]
+fn january_thirty_first_matures_at_end_of_april() {
+    // 2025-01-31 12:00 UTC+8
+    let purchase = 1_738_296_000;
+    let release = generate_release_timestamps(purchase, 0, 8 * 60 * 60);
+    assert_eq!(timestamp_to_ymd(release, 8 * 60 * 60), (2025, 4, 30));
+    assert_eq!(release % 86_400, purchase % 86_400);
+}
+
+#[test]
+fn calendar_lock_terms_match_stake_types() {
+    let purchase = 1_738_296_000;
+    for (stake_type, months) in [(0, 3), (1, 6), (2, 12)] {
+        assert_eq!(
+            lock_end_timestamp(purchase, LockTerm::CalendarMonths(months), 8 * 60 * 60),
+            generate_release_timestamps(purchase, stake_type, 8 * 60 * 60)
+        );
+    }
+}
+
+#[test]
+fn fixed_lock_terms_have_constant_length() {
+    // 30 分钟、90 天的固定期限，与起始日期和时区无关
+    for seconds in [30 * 60, 90 * 86_400] {
+        for purchase in [1_738_296_000, 1_740_787_200, 1_767_225_599] {
+            for offset in [0, 8 * 60 * 60, -5 * 60 * 60] {
+                assert_eq!(
+                    lock_end_timestamp(purchase, LockTerm::FixedSeconds(seconds), offset)
+                        - purchase,
+                    seconds
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn effective_lock_term_follows_fast_time_feature() {
+    let configured = LockTerm::CalendarMonths(6);
+    let expected = if cfg!(feature = "fast-time") {
+        LockTerm::FixedSeconds(60 * 60)
+    } else {
+        configured
+    };
+    assert_eq!(effective_lock_term(1, configured), expected);
+}
+
+#[test]
+fn invalid_stake_type_returns_zero() {
+    assert_eq!(generate_release_timestamps(1_738_296_000, 3, 0), 0);
+}
+
+#[test]
+fn utc_offset_is_limited_to_fourteen_hours() {
+    for offset in [0, 28_800, -MAX_UTC_OFFSET_SECONDS, MAX_UTC_OFFSET_SECONDS] {
+        assert!(check_utc_offset(offset).is_ok());
+    }
+    for offset in [
+        MAX_UTC_OFFSET_SECONDS + 1,
+        -MAX_UTC_OFFSET_SECONDS - 1,
+        i32::MAX,
+        i32::MIN + 1,
+    ] {
+        assert_eq!(
+            check_utc_offset(offset),
+            Err(ErrorCode::InvalidUtcOffset.into())
+        );
+    }
+}
+
+proptest! {
+    #[test]
+    fn release_matches_chrono_for_any_time_and_offset(
+        purchase in (MAX_UTC_OFFSET_SECONDS as u64)..(MAX_TIMESTAMP - 366 * 86_400),
+        utc_offset_seconds in -MAX_UTC_OFFSET_SECONDS..=MAX_UTC_OFFSET_SECONDS,
+        stake_type in 0u64..3,
+    ) {
+        let release = generate_release_timestamps(purchase, stake_type, utc_offset_seconds);
+        prop_assert_eq!(release, reference_release(purchase, stake_type, utc_offset_seconds));
+        prop_assert!(release > purchase);
+    }
+
+    #[test]
+    fn ymd_round_trips_for_any_time_and_offset(
+        timestamp in (MAX_UTC_OFFSET_SECONDS as u64)..MAX_TIMESTAMP,
+        utc_offset_seconds in -MAX_UTC_OFFSET_SECONDS..=MAX_UTC_OFFSET_SECONDS,
+    ) {
+        let (year, month, day) = timestamp_to_ymd(timestamp, utc_offset_seconds);
+        let midnight = date_to_timestamp(year, month, day, utc_offset_seconds);
+        prop_assert!(midnight <= timestamp);
+        prop_assert!(timestamp - midnight < 86_400);
+    }
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use gdtc_stake::constants::MAX_REWARD_STREAMS;
+use gdtc_stake::structures::User;
+use gdtc_stake::{check_user_closable, ErrorCode};
+
+// 所有质押都已取消后的用户账户
+fn empty_user() -> User {
+    let mut user = common::new_user();
+    user.total_deposited_amount = 3_000;
+    user
+}
+
+#[test]
+fn user_without_stakes_or_rewards_can_close() {
+    assert!(check_user_closable(&empty_user()).is_ok());
+}
+
+#[test]
+fn active_staking_blocks_closing() {
+    let mut user = empty_user();
+    user.staked_info[9].is_staked = true;
+    user.staked_info[9].deposited_amount = 1_000;
+    assert_eq!(
+        check_user_closable(&user).unwrap_err(),
+        ErrorCode::UserHasActiveStaking.into()
+    );
+}
+
+#[test]
+fn unclaimed_rewards_block_closing() {
+    let mut user = empty_user();
+    user.staked_info[3].accumulated_reward = 1;
+    assert_eq!(
+        check_user_closable(&user).unwrap_err(),
+        ErrorCode::UserHasUnclaimedRewards.into()
+    );
+
+    let mut user = empty_user();
+    user.staked_info[0].stream_accumulated_reward[MAX_REWARD_STREAMS - 1] = 1;
+    assert_eq!(
+        check_user_closable(&user).unwrap_err(),
+        ErrorCode::UserHasUnclaimedRewards.into()
+    );
+}
@@ -0,0 +1,88 @@
+// 各测试文件共用的账户构造函数。账户结构新增字段时只需修改这里
+#![allow(dead_code)]
+
+use anchor_lang::prelude::Pubkey;
+use gdtc_stake::constants::{
+    MAX_REWARD_STREAMS, STAKING_INSTANCE_RESERVED_BYTES, STAKING_INSTANCE_VERSION,
+    USER_RESERVED_BYTES, USER_VERSION,
+};
+use gdtc_stake::structures::{LockTerm, Staked, StakingInstance, StakingPool, User};
+
+pub const START: u64 = 1_700_000_000;
+
+// 未使用的质押槽位
+pub fn empty_staked() -> Staked {
+    Staked {
+        deposited_amount: 0,
+        reward_debt: 0,
+        accumulated_reward: 0,
+        is_staked: false,
+        stake_type: 0,
+        stake_start_time: 0,
+        stake_end_time: 0,
+        receivedReward: 0,
+        can_cancel_stake: false,
+        stream_reward_debt: [0; MAX_REWARD_STREAMS],
+        stream_accumulated_reward: [0; MAX_REWARD_STREAMS],
+    }
+}
+
+// 没有质押、没有提前解押和合作方奖励的池子，从 START 开始计息
+pub fn new_pool(stake_type: u64, reward_token_per_sec: u64) -> StakingPool {
+    StakingPool {
+        stake_type,
+        reward_token_per_sec,
+        accumulated_reward_per_share: 0,
+        last_reward_timestamp: START,
+        total_shares: 0,
+        reward_streams: Default::default(),
+        early_unstake_enabled: false,
+        penalty_bps: 0,
+        reward_forfeit_bps: 0,
+        penalty_decays: false,
+        redistribute_forfeits: false,
+        lock_term: LockTerm::CalendarMonths(3),
+    }
+}
+
+// 三个池子速率相同、没有任何限制的当前版本实例
+pub fn new_instance(reward_token_per_sec: u64) -> StakingInstance {
+    StakingInstance {
+        authority: Pubkey::new_unique(),
+        reward_token_mint: Pubkey::new_unique(),
+        staking_token_mint: Pubkey::new_unique(),
+        pools: [
+            new_pool(0, reward_token_per_sec),
+            new_pool(1, reward_token_per_sec),
+            new_pool(2, reward_token_per_sec),
+        ],
+        lp_token_account: Pubkey::new_unique(),
+        instance_id: 0,
+        treasury_lp_token_account: Pubkey::new_unique(),
+        utc_offset_seconds: 0,
+        version: STAKING_INSTANCE_VERSION,
+        bump: 0,
+        lp_authority_bump: 0,
+        allowlist_enabled: false,
+        stake_limits: Default::default(),
+        emission_schedules: Default::default(),
+        rate_curves: Default::default(),
+        reward_liability: 0,
+        reserved: [0; STAKING_INSTANCE_RESERVED_BYTES],
+    }
+}
+
+// 所有槽位都为空的当前版本用户
+pub fn new_user() -> User {
+    User {
+        total_deposited_amount: 0,
+        user_superior_token_account: Pubkey::new_unique(),
+        staked_info: core::array::from_fn(|_| empty_staked()),
+        isinit: true,
+        user_address: Pubkey::new_unique(),
+        version: USER_VERSION,
+        bump: 0,
+        stake_slots: [0; 10],
+        reserved: [0; USER_RESERVED_BYTES],
+    }
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator, Event};
+use common::{new_pool, START};
+use gdtc_stake::constants::COMPUTATION_DECIMALS;
+use gdtc_stake::events::PoolsCranked;
+use gdtc_stake::reward_math::accumulated_reward;
+use gdtc_stake::structures::{EmissionSchedule, RateCurve, StakingInstance, StakingPool};
+use gdtc_stake::{pool_snapshots, update_reward_pool};
+
+fn pool(stake_type: u64, reward_token_per_sec: u64, total_shares: u64) -> StakingPool {
+    StakingPool {
+        total_shares,
+        ..new_pool(stake_type, reward_token_per_sec)
+    }
+}
+
+fn new_instance() -> StakingInstance {
+    let mut instance = common::new_instance(0);
+    instance.pools = [pool(0, 1_000, 3_000), pool(1, 7, 9_999), pool(2, 500, 0)];
+    instance
+}
+
+#[test]
+fn crank_brings_every_pool_up_to_date() {
+    let mut staking_instance = new_instance();
+    let now = START + 86_400 * 3;
+    update_reward_pool(now, &mut staking_instance).unwrap();
+
+    let snapshots = pool_snapshots(&staking_instance, now);
+    assert_eq!(snapshots.len(), staking_instance.pools.len());
+    for (snapshot, pool) in snapshots.iter().zip(staking_instance.pools.iter()) {
+        // 没有曲线和排放计划时实际速率就是配置的速率
+        assert_eq!(snapshot.effective_reward_per_sec, pool.reward_token_per_sec);
+        // 空池也推进时间戳
+        assert_eq!(snapshot.last_reward_timestamp, now);
+    }
+    assert_eq!(
+        snapshots[0].accumulated_reward_per_share,
+        (1_000 * 86_400 * 3) as u128 * COMPUTATION_DECIMALS as u128 / 3_000
+    );
+    assert_eq!(snapshots[2].accumulated_reward_per_share, 0);
+
+    // 同一秒内再次调用不改变任何状态
+    update_reward_pool(now, &mut staking_instance).unwrap();
+    assert_eq!(pool_snapshots(&staking_instance, now), snapshots);
+}
+
+#[test]
+fn snapshot_reports_the_rate_after_curve_and_schedule() {
+    let mut staking_instance = new_instance();
+    // 0 号池第二个 90 天周期内减半
+    staking_instance.emission_schedules[0] = EmissionSchedule {
+        start_time: START,
+        period_secs: 90 * 86_400,
+        decay_bps: 5_000,
+    };
+    // 1 号池的曲线按份额给出的速率低于下限，取下限
+    staking_instance.rate_curves[1] = RateCurve {
+        target_apr_bps: 1_000,
+        min_reward_per_sec: 40,
+        max_reward_per_sec: 80,
+    };
+    let now = START + 100 * 86_400;
+    update_reward_pool(now, &mut staking_instance).unwrap();
+
+    let snapshots = pool_snapshots(&staking_instance, now);
+    assert_eq!(snapshots[0].reward_token_per_sec, 1_000);
+    assert_eq!(snapshots[0].effective_reward_per_sec, 500);
+    assert_eq!(snapshots[1].reward_token_per_sec, 7);
+    assert_eq!(snapshots[1].effective_reward_per_sec, 40);
+    assert_eq!(snapshots[2].effective_reward_per_sec, 500);
+}
+
+#[test]
+fn cranking_does_not_change_what_stakers_earn() {
+    let mut cranked = new_instance();
+    let mut untouched = new_instance();
+    let end = START + 86_400 * 30;
+    let cranks = 97;
+
+    for step in 1..=cranks {
+        update_reward_pool(START + (end - START) * step / cranks, &mut cranked).unwrap();
+    }
+    update_reward_pool(end, &mut untouched).unwrap();
+
+    for (a, b) in cranked.pools.iter().zip(untouched.pools.iter()) {
+        let shares = a.total_shares;
+        let earned_cranked = accumulated_reward(shares, a.accumulated_reward_per_share).unwrap();
+        let earned_untouched = accumulated_reward(shares, b.accumulated_reward_per_share).unwrap();
+        // 每次累加向下取整，误差不超过每次 1 个最小单位，且只会少发
+        assert!(earned_cranked <= earned_untouched);
+        assert!(earned_untouched - earned_cranked <= cranks as u128);
+    }
+}
+
+#[test]
+fn snapshot_event_round_trips() {
+    let staking_instance = new_instance();
+    let event = PoolsCranked {
+        staking_instance: Pubkey::new_unique(),
+        timestamp: START,
+        pools: pool_snapshots(&staking_instance, START),
+    };
+    let data = event.data();
+    assert_eq!(&data[..8], PoolsCranked::DISCRIMINATOR);
+
+    let decoded = PoolsCranked::deserialize(&mut &data[8..]).unwrap();
+    assert_eq!(decoded.staking_instance, event.staking_instance);
+    assert_eq!(decoded.timestamp, START);
+    assert_eq!(decoded.pools, event.pools);
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use gdtc_stake::constants::{
+    FAST_TIME_CYCLE_SECONDS, FAST_TIME_LOCK_TERMS, FAST_TIME_STAKING_DEADLINES, STAKING_DEADLINES,
+};
+use gdtc_stake::structures::{LockTerm, StakeLimits, StakingInstance, User};
+use gdtc_stake::{first_free_slot, record_stake, staking_ended, ErrorCode};
+
+const NOW: u64 = common::START;
+
+fn new_instance() -> StakingInstance {
+    let mut instance = common::new_instance(1_000);
+    for pool in instance.pools.iter_mut() {
+        pool.last_reward_timestamp = NOW - 100;
+    }
+    instance
+}
+
+fn new_user(user_address: Pubkey) -> User {
+    let mut user = common::new_user();
+    user.user_address = user_address;
+    user
+}
+
+#[test]
+fn first_free_slot_skips_active_stakes() {
+    let mut user = new_user(Pubkey::new_unique());
+    assert_eq!(first_free_slot(&user), Some(0));
+
+    user.staked_info[0].is_staked = true;
+    user.staked_info[1].is_staked = true;
+    assert_eq!(first_free_slot(&user), Some(2));
+
+    for staked in user.staked_info.iter_mut() {
+        staked.is_staked = true;
+    }
+    assert_eq!(first_free_slot(&user), None);
+
+    // 解押后的槽位可以复用
+    user.staked_info[7].is_staked = false;
+    assert_eq!(first_free_slot(&user), Some(7));
+}
+
+#[test]
+fn deposits_for_a_beneficiary_land_in_its_free_slots() {
+    let mut staking_instance = new_instance();
+    let beneficiary = Pubkey::new_unique();
+    let mut user = new_user(beneficiary);
+
+    for amount in [1_000, 2_500] {
+        let slot = first_free_slot(&user).unwrap();
+        record_stake(&mut staking_instance, &mut user, slot, 1, amount, NOW).unwrap();
+    }
+
+    assert_eq!(user.user_address, beneficiary);
+    assert_eq!(user.total_deposited_amount, 3_500);
+    assert_eq!(user.staked_info[0].deposited_amount, 1_000);
+    assert_eq!(user.staked_info[1].deposited_amount, 2_500);
+    for staked in user.staked_info[..2].iter() {
+        assert!(staked.is_staked);
+        assert_eq!(staked.stake_type, 1);
+        assert_eq!(staked.stake_start_time, NOW);
+        assert!(staked.stake_end_time > NOW);
+    }
+    assert_eq!(first_free_slot(&user), Some(2));
+    assert_eq!(staking_instance.pools[1].total_shares, 3_500);
+    assert_eq!(staking_instance.pools[1].last_reward_timestamp, NOW);
+    // 新仓位只参与之后的奖励
+    let pool = &staking_instance.pools[1];
+    assert_eq!(
+        user.staked_info[1].reward_debt,
+        2_500 * pool.accumulated_reward_per_share
+    );
+}
+
+#[test]
+fn deposits_for_a_beneficiary_respect_its_stake_limits() {
+    let mut staking_instance = new_instance();
+    staking_instance.stake_limits[0] = StakeLimits {
+        min_stake: 0,
+        max_stake_per_user: 1_000,
+        pool_capacity: 0,
+    };
+    let mut user = new_user(Pubkey::new_unique());
+    record_stake(&mut staking_instance, &mut user, 0, 0, 1_000, NOW).unwrap();
+
+    // 由他人出资同样计入受益人的个人上限
+    assert_eq!(
+        record_stake(&mut staking_instance, &mut user, 1, 0, 1, NOW).unwrap_err(),
+        ErrorCode::UserStakeCapExceeded.into()
+    );
+    assert!(!user.staked_info[1].is_staked);
+}
+
+#[test]
+fn staking_window_closes_per_pool() {
+    assert!(staking_ended(3, NOW));
+    if cfg!(feature = "fast-time") {
+        // 每 3 小时一个周期，12 个月池在周期开始 60 分钟后停止质押，下个周期重新开放
+        let cycle_start = NOW - NOW % FAST_TIME_CYCLE_SECONDS;
+        assert!(!staking_ended(2, cycle_start + 60 * 60));
+        assert!(staking_ended(2, cycle_start + 60 * 60 + 1));
+        assert!(!staking_ended(0, cycle_start + 60 * 60 + 1));
+        assert!(!staking_ended(2, cycle_start + FAST_TIME_CYCLE_SECONDS));
+    } else {
+        assert!(!staking_ended(0, NOW));
+        assert!(!staking_ended(2, 2335136861));
+        assert!(staking_ended(2, 2335136862));
+    }
+}
+
+#[test]
+fn every_pool_matures_before_the_common_end() {
+    // 最后一刻进入的质押也在同一终点之前到期（fast-time 下为周期结束）
+    for (deadline, term) in FAST_TIME_STAKING_DEADLINES.iter().zip(FAST_TIME_LOCK_TERMS) {
+        let LockTerm::FixedSeconds(seconds) = term else {
+            panic!("fast-time lock terms are fixed seconds");
+        };
+        assert_eq!(deadline + seconds, FAST_TIME_CYCLE_SECONDS);
+    }
+    let common_end = STAKING_DEADLINES[0] + 92 * 24 * 60 * 60;
+    assert_eq!(STAKING_DEADLINES[1] + 184 * 24 * 60 * 60, common_end);
+    assert_eq!(STAKING_DEADLINES[2] + 366 * 24 * 60 * 60, common_end);
+}
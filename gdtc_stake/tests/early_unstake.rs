@@ -0,0 +1,232 @@
+mod common;
+
+use common::{new_user, START};
+use gdtc_stake::constants::REFERRAL_MIN_SUPERIOR_DEPOSIT;
+use gdtc_stake::structures::{StakingInstance, User};
+use gdtc_stake::{
+    apply_bps, calculate_referral_reward, early_unstake_bps, record_early_unstake, record_stake,
+    store_pending_reward, update_reward_pool, with_referral_allowance, ErrorCode,
+};
+
+const RATE: u64 = 1_000;
+const AMOUNT: u64 = 1_000_000_000;
+
+// 0 号池允许提前解押：LP 罚金 10%，放弃一半未领取奖励，罚金和放弃的奖励都不递减
+fn new_instance() -> StakingInstance {
+    let mut instance = common::new_instance(RATE);
+    let pool = &mut instance.pools[0];
+    pool.early_unstake_enabled = true;
+    pool.penalty_bps = 1_000;
+    pool.reward_forfeit_bps = 5_000;
+    instance
+}
+
+// 质押不超过门槛的上级，拿不到推荐奖励
+fn small_superior() -> User {
+    User {
+        total_deposited_amount: REFERRAL_MIN_SUPERIOR_DEPOSIT,
+        ..new_user()
+    }
+}
+
+fn settle(instance: &mut StakingInstance, user: &mut User, index: u64, now: u64) -> u64 {
+    update_reward_pool(now, instance).unwrap();
+    store_pending_reward(instance, user, index).unwrap();
+    user.staked_info[index as usize].accumulated_reward
+}
+
+#[test]
+fn decaying_rates_fall_linearly_to_zero_at_maturity() {
+    // 不递减时到期前一直按配置收取
+    assert_eq!(early_unstake_bps(1_000, false, 100, 200, 100), 1_000);
+    assert_eq!(early_unstake_bps(1_000, false, 100, 200, 199), 1_000);
+    assert_eq!(early_unstake_bps(1_000, false, 100, 200, 200), 0);
+
+    assert_eq!(early_unstake_bps(1_000, true, 100, 200, 100), 1_000);
+    assert_eq!(early_unstake_bps(1_000, true, 100, 200, 150), 500);
+    assert_eq!(early_unstake_bps(1_000, true, 100, 200, 175), 250);
+    assert_eq!(early_unstake_bps(1_000, true, 100, 200, 199), 10);
+    assert_eq!(early_unstake_bps(1_000, true, 100, 200, 200), 0);
+    assert_eq!(early_unstake_bps(1_000, true, 100, 200, 250), 0);
+    // 开始前按全额计算，余数向下取整
+    assert_eq!(early_unstake_bps(1_000, true, 100, 200, 50), 1_000);
+    assert_eq!(early_unstake_bps(3, true, 0, 4, 1), 2);
+    // 锁定期为 0 时无法递减
+    assert_eq!(early_unstake_bps(1_000, true, 100, 100, 99), 1_000);
+}
+
+#[test]
+fn decaying_penalty_is_applied_at_the_exit_time() {
+    let mut instance = new_instance();
+    instance.pools[0].penalty_decays = true;
+    let mut user = new_user();
+    record_stake(&mut instance, &mut user, 0, 0, AMOUNT, START).unwrap();
+    let end = user.staked_info[0].stake_end_time;
+    let halfway = START + (end - START) / 2;
+
+    let settlement =
+        record_early_unstake(&mut instance, &mut user, &small_superior(), 0, halfway).unwrap();
+    let penalty_bps = early_unstake_bps(1_000, true, START, end, halfway);
+    assert_eq!(penalty_bps, 500);
+    assert_eq!(settlement.lp_penalty, AMOUNT / 20);
+    assert_eq!(settlement.lp_returned, AMOUNT - AMOUNT / 20);
+}
+
+#[test]
+fn penalty_and_forfeit_round_down_in_favour_of_the_user() {
+    assert_eq!(apply_bps(9_999, 1), 0);
+    assert_eq!(apply_bps(10_009, 1_000), 1_000);
+
+    let mut instance = new_instance();
+    instance.pools[0].penalty_bps = 7;
+    instance.pools[0].reward_forfeit_bps = 3_333;
+    let mut user = new_user();
+    let amount = AMOUNT + 9;
+    record_stake(&mut instance, &mut user, 0, 0, amount, START).unwrap();
+
+    let unpaid = settle(&mut instance, &mut user, 0, START + 33);
+    let settlement =
+        record_early_unstake(&mut instance, &mut user, &small_superior(), 0, START + 33).unwrap();
+
+    assert_eq!(settlement.lp_penalty, amount * 7 / 10_000);
+    assert_eq!(settlement.lp_returned + settlement.lp_penalty, amount);
+    assert_eq!(settlement.forfeited_reward, unpaid * 3_333 / 10_000);
+    assert_eq!(
+        settlement.reward_payout + settlement.forfeited_reward,
+        unpaid
+    );
+}
+
+#[test]
+fn without_redistribution_penalty_goes_to_treasury_and_forfeit_stays_in_the_vault() {
+    let mut instance = new_instance();
+    let mut leaver = new_user();
+    let mut other = new_user();
+    record_stake(&mut instance, &mut leaver, 0, 0, AMOUNT, START).unwrap();
+    record_stake(&mut instance, &mut other, 0, 0, AMOUNT, START).unwrap();
+
+    let settlement = record_early_unstake(
+        &mut instance,
+        &mut leaver,
+        &small_superior(),
+        0,
+        START + 100,
+    )
+    .unwrap();
+    // 两人平分 100 秒的排放
+    assert_eq!(settlement.reward_payout, 25_000);
+    assert_eq!(settlement.forfeited_reward, 25_000);
+    assert_eq!(settlement.redistributed_reward, 0);
+    assert_eq!(settlement.lp_penalty, AMOUNT / 10);
+    assert_eq!(settlement.lp_returned, AMOUNT - AMOUNT / 10);
+
+    // 离场者的份额和欠款都已扣除，另一人的奖励不受影响
+    assert_eq!(instance.pools[0].total_shares, AMOUNT);
+    assert_eq!(
+        instance.reward_liability,
+        with_referral_allowance(100_000) - with_referral_allowance(50_000)
+    );
+    assert!(!leaver.staked_info[0].is_staked);
+    assert_eq!(leaver.total_deposited_amount, 0);
+    assert_eq!(settle(&mut instance, &mut other, 0, START + 100), 50_000);
+}
+
+#[test]
+fn redistributed_forfeits_go_to_the_remaining_stakers() {
+    let mut instance = new_instance();
+    instance.pools[0].redistribute_forfeits = true;
+    let mut leaver = new_user();
+    let mut other = new_user();
+    record_stake(&mut instance, &mut leaver, 0, 0, AMOUNT, START).unwrap();
+    record_stake(&mut instance, &mut other, 0, 0, AMOUNT, START).unwrap();
+
+    let settlement = record_early_unstake(
+        &mut instance,
+        &mut leaver,
+        &small_superior(),
+        0,
+        START + 100,
+    )
+    .unwrap();
+    assert_eq!(settlement.forfeited_reward, 25_000);
+    assert_eq!(settlement.redistributed_reward, 25_000);
+
+    // 放弃的奖励仍欠剩余质押者，不从未支付总额中扣除
+    assert_eq!(
+        instance.reward_liability,
+        with_referral_allowance(100_000) - with_referral_allowance(25_000)
+    );
+    assert_eq!(settle(&mut instance, &mut other, 0, START + 100), 75_000);
+}
+
+#[test]
+fn superior_gets_the_same_referral_as_claim_rewards() {
+    let large_superior = User {
+        total_deposited_amount: REFERRAL_MIN_SUPERIOR_DEPOSIT + 1,
+        ..new_user()
+    };
+    assert_eq!(calculate_referral_reward(&large_superior, 25_005), 2_500);
+    assert_eq!(calculate_referral_reward(&small_superior(), 25_005), 0);
+
+    let mut instance = new_instance();
+    let mut user = new_user();
+    record_stake(&mut instance, &mut user, 0, 0, AMOUNT, START).unwrap();
+    let settlement =
+        record_early_unstake(&mut instance, &mut user, &large_superior, 0, START + 100).unwrap();
+    // 推荐奖励按用户实际拿到的奖励计算，不含放弃的部分
+    assert_eq!(settlement.reward_payout, 50_000);
+    assert_eq!(settlement.referral_reward, 5_000);
+    // 发放的奖励连同推荐奖励都在预留范围内
+    assert_eq!(instance.reward_liability, 0);
+}
+
+#[test]
+fn only_unmatured_stakes_in_enabled_pools_can_exit_early() {
+    let mut instance = new_instance();
+    let mut user = new_user();
+    let superior = small_superior();
+    assert_eq!(
+        record_early_unstake(&mut instance, &mut user, &superior, 0, START),
+        Err(ErrorCode::NoStakingToCancel.into())
+    );
+
+    record_stake(&mut instance, &mut user, 0, 0, AMOUNT, START).unwrap();
+    let end = user.staked_info[0].stake_end_time;
+    assert_eq!(
+        record_early_unstake(&mut instance, &mut user, &superior, 0, end),
+        Err(ErrorCode::StakingAlreadyMatured.into())
+    );
+
+    instance.pools[0].early_unstake_enabled = false;
+    assert_eq!(
+        record_early_unstake(&mut instance, &mut user, &superior, 0, START + 1),
+        Err(ErrorCode::EarlyUnstakeDisabled.into())
+    );
+}
+
+#[test]
+fn lp_is_conserved_when_forfeits_are_redistributed() {
+    let mut instance = new_instance();
+    instance.pools[0].redistribute_forfeits = true;
+    instance.pools[0].penalty_bps = 1_234;
+    let mut users: Vec<User> = (0..4).map(|_| new_user()).collect();
+    let deposits = [AMOUNT, 3 * AMOUNT + 7, 5 * AMOUNT + 1, 2 * AMOUNT];
+    for (user, amount) in users.iter_mut().zip(deposits) {
+        record_stake(&mut instance, user, 0, 0, amount, START).unwrap();
+    }
+    // LP 金库中的本金
+    let mut vault: u64 = deposits.iter().sum();
+    let mut treasury = 0;
+
+    for (round, user) in users.iter_mut().take(3).enumerate() {
+        let now = START + 100 * (round as u64 + 1);
+        let settlement =
+            record_early_unstake(&mut instance, user, &small_superior(), 0, now).unwrap();
+        vault -= settlement.lp_returned + settlement.lp_penalty;
+        treasury += settlement.lp_penalty;
+        // 罚金只进国库，金库中剩下的恰好是剩余质押者的本金
+        assert_eq!(vault, instance.pools[0].total_shares);
+    }
+    assert_eq!(vault, users[3].staked_info[0].deposited_amount);
+    assert!(treasury > 0);
+}
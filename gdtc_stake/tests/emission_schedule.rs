@@ -0,0 +1,214 @@
+mod common;
+
+use common::START;
+use gdtc_stake::constants::EMISSION_DECAY_SCALE;
+use gdtc_stake::reward_math::{
+    accumulated_reward, cumulative_emission, decay_factor, scheduled_emission, scheduled_rate,
+};
+use gdtc_stake::structures::{EmissionSchedule, StakingInstance};
+use gdtc_stake::update_reward_pool;
+use proptest::prelude::*;
+
+const NINETY_DAYS: u32 = 90 * 86_400;
+
+fn halving(period_secs: u32) -> EmissionSchedule {
+    EmissionSchedule {
+        start_time: START,
+        period_secs,
+        decay_bps: 5_000,
+    }
+}
+
+fn new_instance(reward_token_per_sec: u64, schedule: EmissionSchedule) -> StakingInstance {
+    let mut instance = common::new_instance(reward_token_per_sec);
+    for pool in instance.pools.iter_mut() {
+        pool.total_shares = 1_000_000;
+    }
+    instance.emission_schedules = [schedule; 3];
+    instance
+}
+
+#[test]
+fn constant_schedule_matches_the_flat_rate() {
+    let schedule = EmissionSchedule::default();
+    assert_eq!(
+        scheduled_emission(1_234, &schedule, START, START + 86_400).unwrap(),
+        1_234 * 86_400
+    );
+    assert_eq!(scheduled_rate(1_234, &schedule, u64::MAX), 1_234);
+    // 没有 start_time 之前的衰减
+    assert_eq!(
+        scheduled_emission(1_000, &halving(100), START - 50, START).unwrap(),
+        50_000
+    );
+}
+
+#[test]
+fn halving_factors_are_exact() {
+    for periods in 0..=12u32 {
+        assert_eq!(
+            decay_factor(5_000, periods as u64),
+            EMISSION_DECAY_SCALE >> periods
+        );
+    }
+    assert_eq!(decay_factor(5_000, 64), 0);
+    assert_eq!(decay_factor(5_000, u64::MAX), 0);
+    // 0 表示只在第一个周期排放
+    assert_eq!(decay_factor(0, 0), EMISSION_DECAY_SCALE);
+    assert_eq!(decay_factor(0, 1), 0);
+}
+
+#[test]
+fn a_long_gap_is_integrated_segment_by_segment() {
+    // 1000/s 排放 100 秒，之后 500、250，第 4 段过去 50 秒
+    let schedule = halving(100);
+    assert_eq!(
+        scheduled_emission(1_000, &schedule, START, START + 350).unwrap(),
+        100_000 + 50_000 + 25_000 + 6_250
+    );
+    // 起止点都在周期中间
+    assert_eq!(
+        scheduled_emission(1_000, &schedule, START + 60, START + 230).unwrap(),
+        40 * 1_000 + 100 * 500 + 30 * 250
+    );
+    assert_eq!(scheduled_rate(1_000, &schedule, START + 99), 1_000);
+    assert_eq!(scheduled_rate(1_000, &schedule, START + 100), 500);
+    assert_eq!(scheduled_rate(1_000, &schedule, START + 350), 125);
+}
+
+#[test]
+fn total_emission_converges_to_the_geometric_limit() {
+    // 减半时总量收敛到首个周期排放量的两倍
+    let reward_per_sec = 1_000_000_000;
+    let schedule = halving(NINETY_DAYS);
+    let first_period = reward_per_sec as u128 * NINETY_DAYS as u128;
+    let total =
+        scheduled_emission(reward_per_sec, &schedule, START, START + 100 * 365 * 86_400).unwrap();
+    assert!(total <= 2 * first_period);
+    assert!(2 * first_period - total < first_period / 1_000_000);
+}
+
+#[test]
+fn closed_form_matches_summing_each_period() {
+    let reward_per_sec = 1_000_000_000;
+    let schedule = EmissionSchedule {
+        start_time: START,
+        period_secs: 7 * 86_400,
+        decay_bps: 9_000,
+    };
+    let periods = 200u64;
+    let period = schedule.period_secs as u64;
+    let summed: u128 = (0..periods)
+        .map(|k| {
+            scheduled_rate(reward_per_sec, &schedule, START + k * period) as u128 * period as u128
+        })
+        .sum();
+    let closed =
+        scheduled_emission(reward_per_sec, &schedule, START, START + periods * period).unwrap();
+    // 逐周期求和每个周期向下取整，误差每周期不超过 period
+    assert!(closed >= summed);
+    assert!(closed - summed <= periods as u128 * period as u128);
+}
+
+#[test]
+fn large_rates_with_long_periods_do_not_overflow() {
+    // 90 天周期下 rate * period * 1e12 * 1e4 超过 u128 的速率
+    for reward_per_sec in [10_000_000_000_000_000, u64::MAX] {
+        let rate = reward_per_sec as u128;
+        let period = NINETY_DAYS as u128;
+        let schedule = halving(NINETY_DAYS);
+        let one_and_a_half = START + NINETY_DAYS as u64 * 3 / 2;
+        assert_eq!(
+            cumulative_emission(reward_per_sec, &schedule, one_and_a_half).unwrap(),
+            rate * START as u128 + rate * period + rate * (period / 2) / 2
+        );
+        assert_eq!(
+            scheduled_emission(reward_per_sec, &schedule, START, one_and_a_half).unwrap(),
+            rate * period + rate * (period / 2) / 2
+        );
+    }
+}
+
+#[test]
+fn update_reward_pool_crosses_several_halvings_at_once() {
+    let reward_per_sec = 1_000_000;
+    let mut instance = new_instance(reward_per_sec, halving(NINETY_DAYS));
+    // 一次跨越 3 个半周期
+    let now = START + 3 * NINETY_DAYS as u64 + NINETY_DAYS as u64 / 2;
+    update_reward_pool(now, &mut instance).unwrap();
+
+    let pool = &instance.pools[0];
+    let earned = accumulated_reward(pool.total_shares, pool.accumulated_reward_per_share).unwrap();
+    let expected = reward_per_sec as u128 * NINETY_DAYS as u128 * (16 + 8 + 4 + 1) / 16;
+    assert!(earned <= expected);
+    assert!(expected - earned <= 1);
+    assert_eq!(pool.last_reward_timestamp, now);
+}
+
+#[test]
+fn cumulative_emission_does_not_drop_at_period_boundaries() {
+    // 曾经在第 88 到 89 个周期的边界处累计值回落 1，导致分段结算多发
+    let reward_per_sec = 3_805_610;
+    let schedule = EmissionSchedule {
+        start_time: START,
+        period_secs: 337_246,
+        decay_bps: 7_332,
+    };
+    let boundary = START + 89 * schedule.period_secs as u64;
+    let mut previous = cumulative_emission(reward_per_sec, &schedule, boundary - 3).unwrap();
+    for t in boundary - 2..=boundary + 2 {
+        let current = cumulative_emission(reward_per_sec, &schedule, t).unwrap();
+        assert!(current >= previous);
+        previous = current;
+    }
+    let pieces = scheduled_emission(reward_per_sec, &schedule, boundary - 3, boundary - 1).unwrap()
+        + scheduled_emission(reward_per_sec, &schedule, boundary - 1, boundary + 3).unwrap();
+    assert_eq!(
+        pieces,
+        scheduled_emission(reward_per_sec, &schedule, boundary - 3, boundary + 3).unwrap()
+    );
+}
+
+#[test]
+fn decay_factor_never_increases_with_more_periods() {
+    for decay_bps in [1, 5_000, 7_332, 9_000, 9_999] {
+        let mut previous = decay_factor(decay_bps, 0);
+        for periods in 1..5_000 {
+            let factor = decay_factor(decay_bps, periods);
+            assert!(factor <= previous);
+            previous = factor;
+        }
+    }
+}
+
+proptest! {
+    // 累计排放随时间单调不减，因此分几次结算与一次结算排放的总额完全相同
+    #[test]
+    fn settling_in_pieces_emits_the_same_total(
+        reward_per_sec in 1..=1_000_000_000u64,
+        period_secs in 1..=NINETY_DAYS,
+        decay_bps in 0..10_000u16,
+        cuts in prop::collection::vec(0..400 * 86_400u64, 1..30),
+    ) {
+        let schedule = EmissionSchedule { start_time: START, period_secs, decay_bps };
+        let mut points: Vec<u64> = cuts.iter().map(|cut| START - 86_400 + cut).collect();
+        points.sort_unstable();
+
+        let mut pieces = 0u128;
+        for window in points.windows(2) {
+            prop_assert!(
+                cumulative_emission(reward_per_sec, &schedule, window[1]).unwrap()
+                    >= cumulative_emission(reward_per_sec, &schedule, window[0]).unwrap()
+            );
+            pieces += scheduled_emission(reward_per_sec, &schedule, window[0], window[1]).unwrap();
+        }
+        let whole = scheduled_emission(
+            reward_per_sec,
+            &schedule,
+            points[0],
+            *points.last().unwrap(),
+        )
+        .unwrap();
+        prop_assert_eq!(pieces, whole);
+    }
+}
@@ -0,0 +1,534 @@
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use common::START;
+use gdtc_stake::constants::{
+    DEFAULT_UTC_OFFSET_SECONDS, LEGACY_INSTANCE_ID, LPTOKEN_SEED, STAKING_INSTANCE_VERSION,
+    STAKING_SEED, USER_SEED, USER_VERSION,
+};
+use gdtc_stake::structures::legacy::{
+    StakingInstanceV1, UserV1, STAKING_INSTANCE_V1_SPACE, USER_V1_SPACE,
+};
+use gdtc_stake::structures::{
+    EmissionSchedule, LockTerm, RateCurve, StakeLimits, Staked, StakingInstance, User,
+};
+use gdtc_stake::{
+    find_lp_authority_address, find_staking_instance_address, read_legacy_account,
+    upgrade_staking_instance, upgrade_user, ErrorCode,
+};
+
+// 按最初部署版本的字段顺序手工拼出账户数据，不经过 legacy.rs 中的结构体，
+// 空间按旧版 initialize_* 的 size_of 计算分配，末尾补 0
+struct LegacyBytes(Vec<u8>);
+
+impl LegacyBytes {
+    fn new(discriminator: [u8; 8]) -> Self {
+        LegacyBytes(discriminator.to_vec())
+    }
+
+    fn u64(mut self, value: u64) -> Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn bool(mut self, value: bool) -> Self {
+        self.0.push(value as u8);
+        self
+    }
+
+    fn pubkey(mut self, value: &Pubkey) -> Self {
+        self.0.extend_from_slice(value.as_ref());
+        self
+    }
+
+    fn zero_fill(self, borsh_len: usize, space: usize) -> Vec<u8> {
+        let mut bytes = self.0;
+        assert_eq!(bytes.len(), borsh_len);
+        bytes.resize(space, 0);
+        bytes
+    }
+}
+
+fn read_legacy<T: Discriminator, L: AnchorDeserialize>(mut data: Vec<u8>) -> L {
+    let key = Pubkey::new_unique();
+    let mut lamports = 0;
+    let account = AccountInfo::new(
+        &key,
+        false,
+        true,
+        &mut lamports,
+        &mut data,
+        &gdtc_stake::ID,
+        false,
+        0,
+    );
+    read_legacy_account::<T, L>(&account).unwrap()
+}
+
+#[test]
+fn legacy_account_spaces_match_the_first_deployment() {
+    // 旧版 StakingInstance：4 个公钥 + 内嵌的 3 个池子（各 5 个 u64），再按旧的计算方式多加 3 个池子的空间
+    assert_eq!(STAKING_INSTANCE_V1_SPACE, 8 + 4 * 32 + 3 * 40 + 3 * 40);
+    // 旧版 User：Staked 按内存布局补齐到 64 字节，User 补齐到 720 字节
+    assert_eq!(USER_V1_SPACE, 8 + 720);
+
+    // 最早的布局以账户空间识别，必须与当前布局的空间不同
+    assert_ne!(8 + User::INIT_SPACE, USER_V1_SPACE);
+    assert_ne!(8 + StakingInstance::INIT_SPACE, STAKING_INSTANCE_V1_SPACE);
+}
+
+#[test]
+fn legacy_staking_instance_migrates_without_losing_state() {
+    let authority = Pubkey::new_unique();
+    let reward_token_mint = Pubkey::new_unique();
+    let staking_token_mint = Pubkey::new_unique();
+    let lp_token_account = Pubkey::new_unique();
+    let mut bytes = LegacyBytes::new(StakingInstance::DISCRIMINATOR)
+        .pubkey(&authority)
+        .pubkey(&reward_token_mint)
+        .pubkey(&staking_token_mint);
+    for stake_type in 0..3 {
+        bytes = bytes
+            .u64(stake_type)
+            .u64(42 + stake_type)
+            .u64(u64::MAX - stake_type)
+            .u64(START + stake_type)
+            .u64(9_000 + stake_type);
+    }
+    let bytes = bytes
+        .pubkey(&lp_token_account)
+        .zero_fill(8 + 248, STAKING_INSTANCE_V1_SPACE);
+
+    let migrated = StakingInstance::from(read_legacy::<StakingInstance, StakingInstanceV1>(bytes));
+
+    assert_eq!(migrated.authority, authority);
+    assert_eq!(migrated.reward_token_mint, reward_token_mint);
+    assert_eq!(migrated.staking_token_mint, staking_token_mint);
+    assert_eq!(migrated.lp_token_account, lp_token_account);
+    assert_eq!(migrated.utc_offset_seconds, DEFAULT_UTC_OFFSET_SECONDS);
+    assert_eq!(migrated.treasury_lp_token_account, Pubkey::default());
+
+    // 单例实例的地址和金库权限沿用最初的种子，migrate_staking_instance 的地址校验因此能通过，
+    // 之前由该实例派生的用户地址也保持不变
+    assert_eq!(migrated.instance_id, LEGACY_INSTANCE_ID);
+    let (instance_address, _) = find_staking_instance_address(&migrated, &gdtc_stake::ID);
+    assert_eq!(
+        instance_address,
+        Pubkey::find_program_address(&[STAKING_SEED], &gdtc_stake::ID).0
+    );
+    assert_eq!(
+        find_lp_authority_address(&migrated, &instance_address, &gdtc_stake::ID),
+        Pubkey::find_program_address(&[LPTOKEN_SEED], &gdtc_stake::ID)
+    );
+    // 转换结果仍是版本 0，由 migrate_staking_instance 写回前升级到当前版本
+    assert_eq!(migrated.version, 0);
+    for (stake_type, (pool, months)) in migrated.pools.iter().zip([3, 6, 12]).enumerate() {
+        let stake_type = stake_type as u64;
+        assert_eq!(pool.stake_type, stake_type);
+        assert_eq!(pool.reward_token_per_sec, 42 + stake_type);
+        assert_eq!(
+            pool.accumulated_reward_per_share,
+            (u64::MAX - stake_type) as u128
+        );
+        assert_eq!(pool.last_reward_timestamp, START + stake_type);
+        assert_eq!(pool.total_shares, 9_000 + stake_type);
+        assert_eq!(pool.lock_term, LockTerm::CalendarMonths(months));
+        assert!(!pool.early_unstake_enabled);
+        assert!(pool
+            .reward_streams
+            .iter()
+            .all(|stream| stream.reward_token_per_sec == 0));
+    }
+}
+
+#[test]
+fn legacy_user_migrates_without_losing_state() {
+    let user_superior_token_account = Pubkey::new_unique();
+    let user_address = Pubkey::new_unique();
+    let mut bytes = LegacyBytes::new(User::DISCRIMINATOR)
+        .u64(10_000)
+        .pubkey(&user_superior_token_account);
+    for index in 0..10 {
+        bytes = bytes
+            .u64(1_000 + index)
+            .u64(u64::MAX - index)
+            .u64(7 + index)
+            .bool(index < 5)
+            .u64(index % 3)
+            .u64(START)
+            .u64(START + 100)
+            .u64(3 + index)
+            .bool(index == 9);
+    }
+    let bytes = bytes
+        .bool(true)
+        .pubkey(&user_address)
+        .zero_fill(8 + 653, USER_V1_SPACE);
+
+    let migrated = User::from(read_legacy::<User, UserV1>(bytes));
+
+    assert_eq!(migrated.total_deposited_amount, 10_000);
+    assert_eq!(
+        migrated.user_superior_token_account,
+        user_superior_token_account
+    );
+    assert!(migrated.isinit);
+    assert_eq!(migrated.user_address, user_address);
+    assert_eq!(migrated.version, 0);
+    for (index, staked) in migrated.staked_info.iter().enumerate() {
+        let index = index as u64;
+        assert_eq!(staked.deposited_amount, 1_000 + index);
+        assert_eq!(staked.reward_debt, (u64::MAX - index) as u128);
+        assert_eq!(staked.accumulated_reward, 7 + index);
+        assert_eq!(staked.is_staked, index < 5);
+        assert_eq!(staked.stake_type, index % 3);
+        assert_eq!(staked.stake_start_time, START);
+        assert_eq!(staked.stake_end_time, START + 100);
+        assert_eq!(staked.receivedReward, 3 + index);
+        assert_eq!(staked.can_cancel_stake, index == 9);
+        assert_eq!(staked.stream_reward_debt, [0; 2]);
+        assert_eq!(staked.stream_accumulated_reward, [0; 2]);
+    }
+}
+
+fn current_user() -> User {
+    User {
+        total_deposited_amount: 5_000,
+        staked_info: core::array::from_fn(|i| Staked {
+            deposited_amount: 1_000 + i as u64,
+            reward_debt: u128::MAX - i as u128,
+            is_staked: true,
+            ..common::empty_staked()
+        }),
+        ..common::new_user()
+    }
+}
+
+// version 之后的新增字段都取自预留字节，两者总长度只在账户扩容时变化
+// （StakingInstance 自 version 6 起为 256，User 自 version 3 起为 128）
+const STAKING_INSTANCE_TAIL_BYTES: usize = 256;
+const USER_TAIL_BYTES: usize = 128;
+
+// 模拟加 version 字段之前的账户：去掉末尾的 version、之后新增的字段和预留字节，再像 realloc 一样补 0 到新空间
+fn strip_version_and_zero_fill(bytes: &[u8], tail_bytes: usize, space: usize) -> Vec<u8> {
+    let mut old = bytes[..bytes.len() - 1 - tail_bytes].to_vec();
+    old.resize(space, 0);
+    old
+}
+
+#[test]
+fn unversioned_accounts_read_as_version_zero_after_zero_fill() {
+    let mut staking_instance = common::new_instance(42);
+    staking_instance.utc_offset_seconds = -3_600;
+    staking_instance.pools[2].accumulated_reward_per_share = u128::MAX;
+    let mut bytes = Vec::new();
+    staking_instance.try_serialize(&mut bytes).unwrap();
+    let old = strip_version_and_zero_fill(
+        &bytes,
+        STAKING_INSTANCE_TAIL_BYTES,
+        8 + StakingInstance::INIT_SPACE,
+    );
+
+    let loaded = StakingInstance::try_deserialize(&mut &old[..]).unwrap();
+    assert_eq!(loaded.version, 0);
+    assert_eq!((loaded.bump, loaded.lp_authority_bump), (0, 0));
+    assert!(!loaded.allowlist_enabled);
+    assert_eq!(loaded.stake_limits, [StakeLimits::default(); 3]);
+    assert_eq!(loaded.authority, staking_instance.authority);
+    assert_eq!(loaded.utc_offset_seconds, -3_600);
+    assert_eq!(
+        loaded.pools[2].accumulated_reward_per_share,
+        staking_instance.pools[2].accumulated_reward_per_share
+    );
+
+    let user = current_user();
+    let mut bytes = Vec::new();
+    user.try_serialize(&mut bytes).unwrap();
+    let old = strip_version_and_zero_fill(&bytes, USER_TAIL_BYTES, 8 + User::INIT_SPACE);
+    let loaded = User::try_deserialize(&mut &old[..]).unwrap();
+    assert_eq!(loaded.version, 0);
+    assert_eq!(loaded.bump, 0);
+    assert_eq!(loaded.user_address, user.user_address);
+    assert_eq!(
+        loaded.staked_info[9].reward_debt,
+        user.staked_info[9].reward_debt
+    );
+}
+
+#[test]
+fn version_5_accounts_grow_into_the_rate_curve_fields() {
+    let mut staking_instance = common::new_instance(42);
+    staking_instance.version = 5;
+    staking_instance.bump = 254;
+    staking_instance.emission_schedules[1] = EmissionSchedule {
+        start_time: START,
+        period_secs: 90 * 86_400,
+        decay_bps: 5_000,
+    };
+    let mut bytes = Vec::new();
+    staking_instance.try_serialize(&mut bytes).unwrap();
+
+    // version 5 的账户末尾只有 128 字节，收益率曲线位于原来的预留字节中；扩容后补 0
+    let mut old = bytes[..bytes.len() - (STAKING_INSTANCE_TAIL_BYTES - 128)].to_vec();
+    assert!(StakingInstance::try_deserialize(&mut &old[..]).is_err());
+    old.resize(8 + StakingInstance::INIT_SPACE, 0);
+
+    let loaded = StakingInstance::try_deserialize(&mut &old[..]).unwrap();
+    assert_eq!(loaded.version, 5);
+    assert_eq!(loaded.bump, 254);
+    assert_eq!(
+        loaded.emission_schedules,
+        staking_instance.emission_schedules
+    );
+    assert_eq!(loaded.rate_curves, [RateCurve::default(); 3]);
+}
+
+#[test]
+fn version_2_users_grow_into_the_stake_slots() {
+    let mut user = current_user();
+    user.version = 2;
+    user.bump = 253;
+    let mut bytes = Vec::new();
+    user.try_serialize(&mut bytes).unwrap();
+
+    // version 2 的用户账户末尾只有 64 字节，扩容后新增的质押 slot 和预留字节都为 0
+    let mut old = bytes[..bytes.len() - (USER_TAIL_BYTES - 64)].to_vec();
+    assert!(User::try_deserialize(&mut &old[..]).is_err());
+    old.resize(8 + User::INIT_SPACE, 0);
+
+    let loaded = User::try_deserialize(&mut &old[..]).unwrap();
+    assert_eq!(loaded.version, 2);
+    assert_eq!(loaded.bump, 253);
+    assert_eq!(loaded.stake_slots, [0; 10]);
+    assert_eq!(
+        loaded.staked_info[9].deposited_amount,
+        user.staked_info[9].deposited_amount
+    );
+}
+
+// 最初部署版本（e05cdfa）的账户结构，字段顺序、类型和注释原样照抄，用来生成迁移测试的账户数据
+#[allow(non_snake_case)]
+mod baseline {
+    use anchor_lang::prelude::*;
+
+    #[derive(AnchorSerialize)]
+    pub struct StakingInstance {
+        pub authority: Pubkey,          // 管理员账户
+        pub reward_token_mint: Pubkey,  // 奖励代币 Mint 地址
+        pub staking_token_mint: Pubkey, // 质押代币 Mint 地址
+        pub pools: [StakingPool; 3],    // 固定3个质押池
+        pub lp_token_account: Pubkey,   //合约接受lp的合约地址
+    }
+
+    #[derive(Debug, Clone, AnchorSerialize)]
+    pub struct StakingPool {
+        pub stake_type: u64,                   // 0 代表3个月，1 代表6个月，2代表12个月
+        pub reward_token_per_sec: u64,         // 每秒奖励代币数量
+        pub accumulated_reward_per_share: u64, // 累计奖励分摊
+        pub last_reward_timestamp: u64,        // 上次更新奖励的时间戳
+        pub total_shares: u64,                 // 该池中质押的总份额
+    }
+
+    #[derive(AnchorSerialize)]
+    pub struct User {
+        //这个字段必须第一位
+        pub total_deposited_amount: u64, // 用户总存入的质押金额
+        pub user_superior_token_account: Pubkey, // 用户的上级 Token 账户
+        pub staked_info: [Staked; 10],   // 固定10个质押池
+        pub isinit: bool,
+        pub user_address: Pubkey,
+    }
+
+    #[derive(Debug, Clone, AnchorSerialize)]
+    pub struct Staked {
+        pub deposited_amount: u64,   // 用户总存入的质押金额
+        pub reward_debt: u64,        // 用户奖励债务
+        pub accumulated_reward: u64, // 用户累计获得的奖励
+        pub is_staked: bool,         // 用户是否已质押
+        pub stake_type: u64,         // 质押类型
+        pub stake_start_time: u64,   // 质押开始时间（Unix 时间戳）
+        pub stake_end_time: u64,     // 质押结束时间（Unix 时间戳）
+        pub receivedReward: u64,     //已领取收益
+        pub can_cancel_stake: bool,  //是否可以解除质押
+    }
+
+    // 旧版 initialize_staking / initialize_user 的 space
+    pub const STAKING_INSTANCE_SPACE: usize =
+        8 + core::mem::size_of::<StakingInstance>() + 3 * core::mem::size_of::<StakingPool>();
+    pub const USER_SPACE: usize = 8 + core::mem::size_of::<User>();
+
+    // 与旧版 #[account] 写入的数据相同：判别符 + Borsh 数据，其余空间为 0
+    pub fn account_data<T: AnchorSerialize>(
+        discriminator: [u8; 8],
+        value: &T,
+        space: usize,
+    ) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        value.serialize(&mut data).unwrap();
+        assert!(data.len() <= space);
+        data.resize(space, 0);
+        data
+    }
+}
+
+fn baseline_staking_instance() -> baseline::StakingInstance {
+    baseline::StakingInstance {
+        authority: Pubkey::new_unique(),
+        reward_token_mint: Pubkey::new_unique(),
+        staking_token_mint: Pubkey::new_unique(),
+        pools: core::array::from_fn(|i| baseline::StakingPool {
+            stake_type: i as u64,
+            reward_token_per_sec: 1_000 * (i as u64 + 1),
+            accumulated_reward_per_share: 5_000_000_000_000 + i as u64,
+            last_reward_timestamp: START,
+            total_shares: 2_000_000 * (i as u64 + 1),
+        }),
+        lp_token_account: Pubkey::new_unique(),
+    }
+}
+
+fn baseline_user() -> baseline::User {
+    baseline::User {
+        total_deposited_amount: 3_000_000,
+        user_superior_token_account: Pubkey::new_unique(),
+        staked_info: core::array::from_fn(|i| baseline::Staked {
+            deposited_amount: if i < 3 { 1_000_000 } else { 0 },
+            reward_debt: if i < 3 { 4_000_000 + i as u64 } else { 0 },
+            accumulated_reward: 17 * i as u64,
+            is_staked: i < 3,
+            stake_type: (i % 3) as u64,
+            stake_start_time: START,
+            stake_end_time: START + 90 * 86_400,
+            receivedReward: 5,
+            can_cancel_stake: false,
+        }),
+        isinit: true,
+        user_address: Pubkey::new_unique(),
+    }
+}
+
+#[test]
+fn baseline_fixtures_use_the_legacy_spaces() {
+    assert_eq!(baseline::STAKING_INSTANCE_SPACE, STAKING_INSTANCE_V1_SPACE);
+    assert_eq!(baseline::USER_SPACE, USER_V1_SPACE);
+}
+
+#[test]
+fn baseline_staking_instance_fixture_migrates_in_place() {
+    let old = baseline_staking_instance();
+    let data = baseline::account_data(
+        StakingInstance::DISCRIMINATOR,
+        &old,
+        baseline::STAKING_INSTANCE_SPACE,
+    );
+    let (key, _) = Pubkey::find_program_address(&[STAKING_SEED], &gdtc_stake::ID);
+
+    // 与 migrate_staking_instance 相同的步骤：按空间识别旧布局、转换、升级，再按当前布局写回
+    assert_eq!(data.len(), STAKING_INSTANCE_V1_SPACE);
+    let mut migrated =
+        StakingInstance::from(read_legacy::<StakingInstance, StakingInstanceV1>(data));
+    upgrade_staking_instance(&mut migrated, &key, &gdtc_stake::ID).unwrap();
+    let mut written = Vec::new();
+    migrated.try_serialize(&mut written).unwrap();
+    assert_eq!(written.len(), 8 + StakingInstance::INIT_SPACE);
+    let loaded = StakingInstance::try_deserialize(&mut &written[..]).unwrap();
+
+    assert_eq!(loaded.version, STAKING_INSTANCE_VERSION);
+    assert_eq!(loaded.authority, old.authority);
+    assert_eq!(loaded.reward_token_mint, old.reward_token_mint);
+    assert_eq!(loaded.staking_token_mint, old.staking_token_mint);
+    assert_eq!(loaded.lp_token_account, old.lp_token_account);
+    assert_eq!(
+        loaded.bump,
+        Pubkey::find_program_address(&[STAKING_SEED], &gdtc_stake::ID).1
+    );
+    assert_eq!(
+        loaded.lp_authority_bump,
+        Pubkey::find_program_address(&[LPTOKEN_SEED], &gdtc_stake::ID).1
+    );
+    for (pool, old) in loaded.pools.iter().zip(old.pools.iter()) {
+        assert_eq!(pool.stake_type, old.stake_type);
+        assert_eq!(pool.reward_token_per_sec, old.reward_token_per_sec);
+        assert_eq!(
+            pool.accumulated_reward_per_share,
+            old.accumulated_reward_per_share as u128
+        );
+        assert_eq!(pool.last_reward_timestamp, old.last_reward_timestamp);
+        assert_eq!(pool.total_shares, old.total_shares);
+    }
+
+    // 再次迁移被拒绝；其他地址上的同样数据不能冒充单例实例
+    assert_eq!(
+        upgrade_staking_instance(&mut migrated, &key, &gdtc_stake::ID).unwrap_err(),
+        ErrorCode::AccountAlreadyMigrated.into()
+    );
+    let data = baseline::account_data(
+        StakingInstance::DISCRIMINATOR,
+        &old,
+        baseline::STAKING_INSTANCE_SPACE,
+    );
+    let mut impostor =
+        StakingInstance::from(read_legacy::<StakingInstance, StakingInstanceV1>(data));
+    assert_eq!(
+        upgrade_staking_instance(&mut impostor, &Pubkey::new_unique(), &gdtc_stake::ID)
+            .unwrap_err(),
+        ErrorCode::InvalidStakingInstance.into()
+    );
+}
+
+#[test]
+fn baseline_user_fixture_migrates_in_place() {
+    let old = baseline_user();
+    let data = baseline::account_data(User::DISCRIMINATOR, &old, baseline::USER_SPACE);
+    let (instance_key, _) = Pubkey::find_program_address(&[STAKING_SEED], &gdtc_stake::ID);
+    // 旧用户账户由单例实例的地址派生，公式与现在相同
+    let (user_key, user_bump) = Pubkey::find_program_address(
+        &[USER_SEED, instance_key.as_ref(), old.user_address.as_ref()],
+        &gdtc_stake::ID,
+    );
+
+    assert_eq!(data.len(), USER_V1_SPACE);
+    let mut migrated = User::from(read_legacy::<User, UserV1>(data));
+    upgrade_user(&mut migrated, &instance_key, &user_key, &gdtc_stake::ID).unwrap();
+    let mut written = Vec::new();
+    migrated.try_serialize(&mut written).unwrap();
+    assert_eq!(written.len(), 8 + User::INIT_SPACE);
+    let loaded = User::try_deserialize(&mut &written[..]).unwrap();
+
+    assert_eq!(loaded.version, USER_VERSION);
+    assert_eq!(loaded.bump, user_bump);
+    assert_eq!(loaded.total_deposited_amount, old.total_deposited_amount);
+    assert_eq!(
+        loaded.user_superior_token_account,
+        old.user_superior_token_account
+    );
+    assert_eq!(loaded.isinit, old.isinit);
+    assert_eq!(loaded.user_address, old.user_address);
+    assert_eq!(loaded.stake_slots, [0; 10]);
+    for (staked, old) in loaded.staked_info.iter().zip(old.staked_info.iter()) {
+        assert_eq!(staked.deposited_amount, old.deposited_amount);
+        assert_eq!(staked.reward_debt, old.reward_debt as u128);
+        assert_eq!(staked.accumulated_reward, old.accumulated_reward);
+        assert_eq!(staked.is_staked, old.is_staked);
+        assert_eq!(staked.stake_type, old.stake_type);
+        assert_eq!(staked.stake_start_time, old.stake_start_time);
+        assert_eq!(staked.stake_end_time, old.stake_end_time);
+        assert_eq!(staked.receivedReward, old.receivedReward);
+        assert_eq!(staked.can_cancel_stake, old.can_cancel_stake);
+    }
+
+    // 由其他实例派生的地址不能迁移
+    let data = baseline::account_data(User::DISCRIMINATOR, &old, baseline::USER_SPACE);
+    let mut other = User::from(read_legacy::<User, UserV1>(data));
+    assert_eq!(
+        upgrade_user(
+            &mut other,
+            &Pubkey::new_unique(),
+            &user_key,
+            &gdtc_stake::ID
+        )
+        .unwrap_err(),
+        ErrorCode::InvalidUserInstance.into()
+    );
+}
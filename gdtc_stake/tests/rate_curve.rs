@@ -0,0 +1,171 @@
+mod common;
+
+use common::{new_user, START};
+use gdtc_stake::reward_math::curve_rate;
+use gdtc_stake::structures::{RateCurve, StakingInstance, User};
+use gdtc_stake::tools::SECONDS_IN_YEAR;
+use gdtc_stake::{record_stake, store_pending_reward, update_reward_pool};
+use proptest::prelude::*;
+
+const USERS: usize = 3;
+const MAX_SHARES: u64 = 1_000_000_000_000;
+const MAX_GAP: u64 = 100_000;
+
+// 目标年化 20%，速率限制在 [1_000, 5_000_000] 之间
+const CURVE: RateCurve = RateCurve {
+    target_apr_bps: 2_000,
+    min_reward_per_sec: 1_000,
+    max_reward_per_sec: 5_000_000,
+};
+
+// 配置的固定速率 777 在设置曲线后不再使用
+fn new_instance(curve: RateCurve) -> StakingInstance {
+    let mut instance = common::new_instance(777);
+    instance.rate_curves = [curve; 3];
+    instance
+}
+
+// 与链上指令相同的调用顺序：每个用户每次质押占用一个新槽位
+struct Chain {
+    instance: StakingInstance,
+    users: Vec<User>,
+    slots: [u64; USERS],
+}
+
+impl Chain {
+    fn new(curve: RateCurve) -> Self {
+        Chain {
+            instance: new_instance(curve),
+            users: (0..USERS).map(|_| new_user()).collect(),
+            slots: [0; USERS],
+        }
+    }
+
+    fn deposit(&mut self, user: usize, amount: u64, now: u64) {
+        let slot = self.slots[user];
+        record_stake(
+            &mut self.instance,
+            &mut self.users[user],
+            slot,
+            0,
+            amount,
+            now,
+        )
+        .unwrap();
+        self.slots[user] += 1;
+    }
+
+    fn crank(&mut self, now: u64) {
+        update_reward_pool(now, &mut self.instance).unwrap();
+    }
+
+    // 结算到 now 后每个用户的累计奖励
+    fn earned(&mut self, now: u64) -> Vec<u64> {
+        self.crank(now);
+        self.users
+            .iter_mut()
+            .map(|user| {
+                for slot in 0..user.staked_info.len() as u64 {
+                    store_pending_reward(&mut self.instance, user, slot).unwrap();
+                }
+                user.staked_info
+                    .iter()
+                    .map(|staked| staked.accumulated_reward)
+                    .sum()
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn disabled_curve_keeps_the_flat_rate() {
+    assert_eq!(curve_rate(777, &RateCurve::default(), 0), 777);
+    assert_eq!(curve_rate(777, &RateCurve::default(), u64::MAX), 777);
+}
+
+#[test]
+fn rate_follows_the_target_apr_between_the_bounds() {
+    // 20% 年化下每秒速率 = 份额 * 0.2 / 一年秒数
+    let shares = 100_000 * SECONDS_IN_YEAR;
+    assert_eq!(curve_rate(777, &CURVE, shares), 20_000);
+    assert_eq!(curve_rate(777, &CURVE, 2 * shares), 40_000);
+    // 份额很少时不低于下限，很多时不超过上限
+    assert_eq!(curve_rate(777, &CURVE, 0), 1_000);
+    assert_eq!(curve_rate(777, &CURVE, u64::MAX), 5_000_000);
+}
+
+#[test]
+fn a_deposit_changes_the_rate_only_from_that_moment() {
+    let mut chain = Chain::new(CURVE);
+    let shares = 100_000 * SECONDS_IN_YEAR;
+    chain.deposit(0, shares, START);
+    // 第二笔质押前的 100 秒按第一笔份额的速率 20_000 排放
+    chain.deposit(1, shares, START + 100);
+    let earned = chain.earned(START + 200);
+    // 之后 100 秒按 40_000 排放，两人平分；份额超过精度，每次累加有少量取整误差
+    let expected = [20_000 * 100 + 20_000 * 100, 20_000 * 100];
+    for (earned, expected) in earned.iter().zip(expected.iter()) {
+        assert!(earned <= expected);
+        assert!(expected - earned <= 10, "{} vs {}", earned, expected);
+    }
+}
+
+proptest! {
+    #[test]
+    fn order_within_the_same_second_does_not_change_rewards(
+        amounts in prop::collection::vec(1..=MAX_SHARES, USERS..=USERS),
+        history in prop::collection::vec((0..USERS, 1..=MAX_SHARES, 0..MAX_GAP), 0..10),
+        gap in 1..MAX_GAP,
+        tail in 1..MAX_GAP,
+    ) {
+        let run = |reversed: bool| {
+            let mut chain = Chain::new(CURVE);
+            let mut now = START;
+            for (user, amount, step) in history.iter() {
+                now += step;
+                chain.deposit(*user, *amount, now);
+            }
+            now += gap;
+            let mut batch: Vec<usize> = (0..USERS).collect();
+            if reversed {
+                batch.reverse();
+            }
+            for user in batch {
+                chain.deposit(user, amounts[user], now);
+            }
+            chain.earned(now + tail)
+        };
+        prop_assert_eq!(run(false), run(true));
+    }
+
+    #[test]
+    fn cranking_changes_rewards_only_by_rounding(
+        steps in prop::collection::vec((0..USERS, 1..=MAX_SHARES, 1..MAX_GAP), 1..10),
+        cranks in prop::collection::vec(0..MAX_GAP, 0..10),
+        tail in 1..MAX_GAP,
+    ) {
+        // 同一组质押，一次在每笔质押之间插入额外的 crank，一次不插入
+        let run = |with_cranks: bool| {
+            let mut chain = Chain::new(CURVE);
+            let mut now = START;
+            for (index, (user, amount, step)) in steps.iter().enumerate() {
+                if with_cranks {
+                    if let Some(offset) = cranks.get(index) {
+                        chain.crank(now + offset % step);
+                    }
+                }
+                now += step;
+                chain.deposit(*user, *amount, now);
+            }
+            chain.earned(now + tail)
+        };
+        let plain = run(false);
+        let cranked = run(true);
+
+        // 份额不超过精度时，每多一次累加每个用户最多差 2 个最小单位
+        let bound = 2 * (cranks.len().min(steps.len()) as u64 + 1);
+        for (a, b) in plain.iter().zip(cranked.iter()) {
+            prop_assert!(a.abs_diff(*b) <= bound, "{} vs {}", a, b);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use common::{new_user, START};
+use gdtc_stake::structures::{StakingInstance, User};
+use gdtc_stake::{
+    redistribute_to_pool, store_pending_reward, update_reward_debt, update_reward_pool,
+};
+
+const RATE: u64 = 1_000_000;
+
+// 提前解押没收一半奖励，并分给仍在质押的用户
+fn new_instance() -> StakingInstance {
+    let mut instance = common::new_instance(RATE);
+    for pool in instance.pools.iter_mut() {
+        pool.early_unstake_enabled = true;
+        pool.reward_forfeit_bps = 5_000;
+        pool.redistribute_forfeits = true;
+    }
+    instance
+}
+
+// 与 enter_staking 相同的记账顺序
+fn stake(instance: &mut StakingInstance, user: &mut User, amount: u64, now: u64) {
+    let staked = &mut user.staked_info[0];
+    staked.deposited_amount = amount;
+    staked.is_staked = true;
+    staked.stake_start_time = now;
+    update_reward_pool(now, instance).unwrap();
+    instance.pools[0].total_shares += amount;
+    update_reward_debt(instance, user, 0).unwrap();
+}
+
+fn settle(instance: &mut StakingInstance, user: &mut User, now: u64) -> u64 {
+    update_reward_pool(now, instance).unwrap();
+    store_pending_reward(instance, user, 0).unwrap();
+    user.staked_info[0].accumulated_reward
+}
+
+#[test]
+fn forfeited_rewards_are_shared_by_remaining_stakers() {
+    let mut instance = new_instance();
+    let mut leaver = new_user();
+    let mut alice = new_user();
+    let mut bob = new_user();
+
+    stake(&mut instance, &mut leaver, 1_000_000_000, START);
+    stake(&mut instance, &mut alice, 1_000_000_000, START);
+    stake(&mut instance, &mut bob, 3_000_000_000, START);
+
+    // 离场者提前解押，放弃一半未领取奖励
+    let exit_time = START + 1_000;
+    let leaver_unpaid = settle(&mut instance, &mut leaver, exit_time);
+    let forfeited = leaver_unpaid / 2;
+    let paid_to_leaver = leaver_unpaid - forfeited;
+    instance.pools[0].total_shares -= leaver.staked_info[0].deposited_amount;
+    let redistributed = redistribute_to_pool(&mut instance.pools[0], forfeited).unwrap();
+    assert!(redistributed <= forfeited);
+
+    let alice_total = settle(&mut instance, &mut alice, exit_time);
+    let bob_total = settle(&mut instance, &mut bob, exit_time);
+
+    // 发放 + 未分配的放弃奖励 = 释放总量，误差仅来自向下取整
+    let emitted = RATE * 1_000;
+    let paid = paid_to_leaver + alice_total + bob_total;
+    let unallocated = forfeited - redistributed;
+    assert!(paid + unallocated <= emitted);
+    assert!(emitted - (paid + unallocated) <= 3);
+
+    // 剩余质押者按份额 1:3 分享
+    let alice_share = alice_total - emitted / 5;
+    let bob_share = bob_total - emitted * 3 / 5;
+    assert!(alice_share + bob_share <= redistributed);
+    assert!(redistributed - (alice_share + bob_share) <= 2);
+    assert!(bob_share.abs_diff(alice_share * 3) <= 3);
+}
+
+#[test]
+fn redistribution_is_skipped_when_no_shares_remain() {
+    let mut instance = new_instance();
+    let mut leaver = new_user();
+
+    stake(&mut instance, &mut leaver, 1_000_000_000, START);
+    let unpaid = settle(&mut instance, &mut leaver, START + 500);
+    let accumulated_before = instance.pools[0].accumulated_reward_per_share;
+
+    instance.pools[0].total_shares -= leaver.staked_info[0].deposited_amount;
+    assert_eq!(
+        redistribute_to_pool(&mut instance.pools[0], unpaid / 2).unwrap(),
+        0
+    );
+    assert_eq!(
+        instance.pools[0].accumulated_reward_per_share,
+        accumulated_before
+    );
+
+    // 之后进入的质押者不会拿到之前放弃的奖励
+    let mut newcomer = new_user();
+    stake(&mut instance, &mut newcomer, 1_000_000_000, START + 600);
+    assert_eq!(settle(&mut instance, &mut newcomer, START + 600), 0);
+}
+
+#[test]
+fn later_stakers_do_not_share_earlier_forfeits() {
+    let mut instance = new_instance();
+    let mut leaver = new_user();
+    let mut alice = new_user();
+
+    stake(&mut instance, &mut leaver, 1_000_000_000, START);
+    stake(&mut instance, &mut alice, 1_000_000_000, START);
+
+    let exit_time = START + 100;
+    let forfeited = settle(&mut instance, &mut leaver, exit_time);
+    instance.pools[0].total_shares -= leaver.staked_info[0].deposited_amount;
+    let redistributed = redistribute_to_pool(&mut instance.pools[0], forfeited).unwrap();
+
+    let mut newcomer = new_user();
+    stake(&mut instance, &mut newcomer, 1_000_000_000, exit_time);
+    assert_eq!(settle(&mut instance, &mut newcomer, exit_time), 0);
+
+    let alice_total = settle(&mut instance, &mut alice, exit_time);
+    assert!(alice_total >= RATE * 100 / 2 + redistributed - 1);
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use common::{new_instance, new_user, START};
+use gdtc_stake::structures::{StakingInstance, User};
+use gdtc_stake::{
+    record_stake, store_pending_reward, update_reward_pool, with_referral_allowance, ErrorCode,
+};
+
+const RATE: u64 = 1_000;
+const AMOUNT: u64 = 1_000_000_000;
+
+fn settle(instance: &mut StakingInstance, user: &mut User, now: u64) -> u64 {
+    update_reward_pool(now, instance).unwrap();
+    store_pending_reward(instance, user, 0).unwrap();
+    user.staked_info[0].accumulated_reward
+}
+
+#[test]
+fn empty_pool_emissions_are_dropped_and_not_paid_to_the_first_staker() {
+    let mut instance = new_instance(RATE);
+
+    // 空池期间只推进时间戳，不累加每份奖励，也不计入未支付总额
+    update_reward_pool(START + 1_000, &mut instance).unwrap();
+    assert_eq!(instance.pools[0].last_reward_timestamp, START + 1_000);
+    assert_eq!(instance.pools[0].accumulated_reward_per_share, 0);
+    assert_eq!(instance.reward_liability, 0);
+
+    // 第一个质押者只从进入时开始计息，空池的 1000 秒奖励不会补发
+    let mut user = new_user();
+    record_stake(&mut instance, &mut user, 0, 0, AMOUNT, START + 2_000).unwrap();
+    assert_eq!(instance.pools[0].last_reward_timestamp, START + 2_000);
+    assert_eq!(instance.reward_liability, 0);
+
+    let reward = settle(&mut instance, &mut user, START + 2_100);
+    assert_eq!(reward, 100 * RATE);
+    assert_eq!(
+        instance.reward_liability,
+        with_referral_allowance(100 * RATE)
+    );
+}
+
+#[test]
+fn pool_is_settled_before_a_new_stake_adds_its_shares() {
+    let mut instance = new_instance(RATE);
+    let mut early = new_user();
+    let mut late = new_user();
+    record_stake(&mut instance, &mut early, 0, 0, AMOUNT, START).unwrap();
+
+    // 后进入者的份额在结算之后才加入，进入前的奖励全部归先进入者
+    record_stake(&mut instance, &mut late, 0, 0, AMOUNT, START + 100).unwrap();
+    assert_eq!(instance.pools[0].last_reward_timestamp, START + 100);
+    assert_eq!(settle(&mut instance, &mut late, START + 100), 0);
+    assert_eq!(settle(&mut instance, &mut early, START + 100), 100 * RATE);
+
+    // 之后的奖励按份额平分
+    assert_eq!(settle(&mut instance, &mut late, START + 200), 50 * RATE);
+    assert_eq!(settle(&mut instance, &mut early, START + 200), 150 * RATE);
+}
+
+#[test]
+fn reward_liability_overflow_is_reported() {
+    let mut instance = new_instance(RATE);
+    let mut user = new_user();
+    record_stake(&mut instance, &mut user, 0, 0, AMOUNT, START).unwrap();
+
+    // 未支付总额溢出时报错，不会被截断到 u64::MAX，整笔交易回滚
+    instance.reward_liability = u64::MAX - 1;
+    assert_eq!(
+        update_reward_pool(START + 100, &mut instance),
+        Err(ErrorCode::Overflow.into())
+    );
+}
@@ -0,0 +1,243 @@
+mod common;
+
+use common::{new_instance, new_user, START};
+use gdtc_stake::constants::COMPUTATION_DECIMALS;
+use gdtc_stake::reward_math::{Position, RewardPool};
+use gdtc_stake::structures::User;
+use gdtc_stake::{store_pending_reward, update_reward_debt, update_reward_pool};
+use proptest::prelude::*;
+
+const USERS: usize = 4;
+// 单个用户最多 1e12 份（如 9 位小数的 LP 持有 1000 枚），池子总份额不超过 USERS * 1e12
+const MAX_SHARES: u64 = 1_000_000_000_000;
+const MAX_RATE: u64 = 1_000_000_000;
+const MAX_GAP: u64 = 100_000;
+
+#[derive(Debug, Clone, Copy)]
+enum Action {
+    Deposit(usize, u64),
+    Withdraw(usize, u64),
+    Claim(usize),
+}
+
+fn action() -> impl Strategy<Value = Action> {
+    prop_oneof![
+        (0..USERS, 1..=MAX_SHARES).prop_map(|(user, amount)| Action::Deposit(user, amount)),
+        (0..USERS, 1..=MAX_SHARES).prop_map(|(user, amount)| Action::Withdraw(user, amount)),
+        (0..USERS).prop_map(Action::Claim),
+    ]
+}
+
+// 用纯函数模型跑一串操作，同时记录按速率实际释放的奖励和可能产生取整误差的次数
+struct Simulation {
+    pool: RewardPool,
+    positions: [Position; USERS],
+    claimed: [u64; USERS],
+    emitted: u64,
+    accruals: u64,
+    settlements: u64,
+}
+
+impl Simulation {
+    fn new(reward_per_sec: u64) -> Self {
+        Simulation {
+            pool: RewardPool::new(reward_per_sec, START),
+            positions: [Position::default(); USERS],
+            claimed: [0; USERS],
+            emitted: 0,
+            accruals: 0,
+            settlements: 0,
+        }
+    }
+
+    fn apply(&mut self, now: u64, action: Action) {
+        if self.pool.total_shares > 0 && now > self.pool.last_reward_timestamp {
+            self.emitted += self.pool.reward_per_sec * (now - self.pool.last_reward_timestamp);
+            self.accruals += 1;
+        }
+        self.settlements += 1;
+        match action {
+            Action::Deposit(user, amount) => {
+                let amount = amount.min(MAX_SHARES - self.positions[user].shares);
+                self.pool
+                    .deposit(&mut self.positions[user], amount, now)
+                    .unwrap();
+            }
+            Action::Withdraw(user, amount) => {
+                let amount = amount.min(self.positions[user].shares);
+                self.pool
+                    .withdraw(&mut self.positions[user], amount, now)
+                    .unwrap();
+            }
+            Action::Claim(user) => {
+                self.claimed[user] += self.pool.claim(&mut self.positions[user], now).unwrap();
+            }
+        }
+    }
+
+    fn claim_all(&mut self, now: u64) {
+        for user in 0..USERS {
+            self.apply(now, Action::Claim(user));
+        }
+    }
+
+    fn total_claimed(&self) -> u64 {
+        self.claimed.iter().sum()
+    }
+}
+
+proptest! {
+    #[test]
+    fn distributed_never_exceeds_emitted_and_dust_is_bounded(
+        reward_per_sec in 1..=MAX_RATE,
+        steps in prop::collection::vec((0..MAX_GAP, action()), 1..40),
+        tail in 0..MAX_GAP,
+    ) {
+        let mut simulation = Simulation::new(reward_per_sec);
+        let mut now = START;
+        for (gap, action) in steps {
+            now += gap;
+            simulation.apply(now, action);
+        }
+        simulation.claim_all(now + tail);
+
+        let claimed = simulation.total_claimed();
+        prop_assert!(claimed <= simulation.emitted);
+        // 每次累加最多损失 总份额 / 精度 个单位，每次结算最多损失 1 个单位
+        let per_accrual = USERS as u64 * MAX_SHARES / COMPUTATION_DECIMALS + 1;
+        let dust = simulation.emitted - claimed;
+        prop_assert!(
+            dust <= simulation.accruals * per_accrual + simulation.settlements,
+            "dust {} after {} accruals and {} settlements",
+            dust,
+            simulation.accruals,
+            simulation.settlements
+        );
+    }
+
+    #[test]
+    fn order_within_the_same_second_does_not_change_rewards(
+        reward_per_sec in 1..=MAX_RATE,
+        history in prop::collection::vec((0..MAX_GAP, action()), 0..20),
+        batch in prop::collection::vec(action(), USERS..=USERS),
+        gap in 1..MAX_GAP,
+        tail in 1..MAX_GAP,
+    ) {
+        // 同一秒内每个用户各做一次操作，按原顺序和逆序执行，之后每个用户的奖励必须完全一致
+        let batch: Vec<Action> = batch
+            .into_iter()
+            .enumerate()
+            .map(|(user, action)| match action {
+                Action::Deposit(_, amount) => Action::Deposit(user, amount),
+                Action::Withdraw(_, amount) => Action::Withdraw(user, amount),
+                Action::Claim(_) => Action::Claim(user),
+            })
+            .collect();
+
+        let run = |batch: &mut dyn Iterator<Item = &Action>| {
+            let mut simulation = Simulation::new(reward_per_sec);
+            let mut now = START;
+            for (step_gap, action) in history.iter() {
+                now += step_gap;
+                simulation.apply(now, *action);
+            }
+            now += gap;
+            for action in batch {
+                simulation.apply(now, *action);
+            }
+            simulation.claim_all(now + tail);
+            simulation.claimed
+        };
+
+        let forward = run(&mut batch.iter());
+        let reversed = run(&mut batch.iter().rev());
+        prop_assert_eq!(forward, reversed);
+    }
+
+    #[test]
+    fn splitting_a_deposit_does_not_change_rewards(
+        reward_per_sec in 1..=MAX_RATE,
+        first in 1..MAX_SHARES / 2,
+        second in 1..MAX_SHARES / 2,
+        other in 1..MAX_SHARES,
+        gaps in prop::collection::vec(1..MAX_GAP, 1..10),
+    ) {
+        let run = |split: bool| {
+            let mut simulation = Simulation::new(reward_per_sec);
+            simulation.apply(START, Action::Deposit(1, other));
+            if split {
+                simulation.apply(START + 1, Action::Deposit(0, first));
+                simulation.apply(START + 1, Action::Deposit(0, second));
+            } else {
+                simulation.apply(START + 1, Action::Deposit(0, first + second));
+            }
+            let mut now = START + 1;
+            for gap in gaps.iter() {
+                now += gap;
+                simulation.apply(now, Action::Claim(0));
+            }
+            simulation.claimed
+        };
+        prop_assert_eq!(run(true), run(false));
+    }
+
+    #[test]
+    fn single_staker_receives_everything_but_rounding_dust(
+        reward_per_sec in 1..=MAX_RATE,
+        shares in 1..=MAX_SHARES,
+        gaps in prop::collection::vec(1..MAX_GAP, 1..20),
+    ) {
+        let mut simulation = Simulation::new(reward_per_sec);
+        simulation.apply(START, Action::Deposit(0, shares));
+        let mut now = START;
+        for gap in gaps.iter() {
+            now += gap;
+            simulation.apply(now, Action::Claim(0));
+        }
+        let emitted = reward_per_sec * (now - START);
+        prop_assert!(simulation.claimed[0] <= emitted);
+        prop_assert!(emitted - simulation.claimed[0] <= 2 * gaps.len() as u64);
+    }
+
+    #[test]
+    fn on_chain_accounting_matches_the_model(
+        reward_per_sec in 1..=MAX_RATE,
+        shares in prop::collection::vec(1..=MAX_SHARES, 2..=2),
+        gaps in prop::collection::vec(0..MAX_GAP, 1..20),
+    ) {
+        // 与 enter_staking / claim_rewards 相同的调用顺序
+        let mut instance = new_instance(reward_per_sec);
+        let mut users: Vec<User> = shares
+            .iter()
+            .map(|_| new_user())
+            .collect();
+        let mut simulation = Simulation::new(reward_per_sec);
+
+        for (user_index, (user, amount)) in users.iter_mut().zip(shares.iter()).enumerate() {
+            let now = START + user_index as u64;
+            update_reward_pool(now, &mut instance).unwrap();
+            user.staked_info[0].deposited_amount = *amount;
+            user.staked_info[0].is_staked = true;
+            instance.pools[0].total_shares += amount;
+            update_reward_debt(&mut instance, user, 0).unwrap();
+            simulation.apply(now, Action::Deposit(user_index, *amount));
+        }
+
+        let mut now = START + shares.len() as u64;
+        for gap in gaps {
+            now += gap;
+            for (user_index, user) in users.iter_mut().enumerate() {
+                update_reward_pool(now, &mut instance).unwrap();
+                store_pending_reward(&mut instance, user, 0).unwrap();
+                simulation.apply(now, Action::Claim(user_index));
+                let claimed = core::mem::take(&mut user.staked_info[0].accumulated_reward);
+                prop_assert_eq!(claimed, simulation.claimed[user_index]);
+                simulation.claimed[user_index] = 0;
+            }
+        }
+        prop_assert_eq!(
+            instance.pools[0].accumulated_reward_per_share,
+            simulation.pool.accumulated_reward_per_share
+        );
+    }
+}
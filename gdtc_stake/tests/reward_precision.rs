@@ -0,0 +1,78 @@
+mod common;
+
+use common::{new_instance, new_pool, new_user, START};
+use gdtc_stake::structures::{StakingInstance, User};
+use gdtc_stake::{
+    redistribute_to_pool, store_pending_reward, update_reward_debt, update_reward_pool, ErrorCode,
+};
+
+fn stake(instance: &mut StakingInstance, user: &mut User, amount: u64) {
+    let staked = &mut user.staked_info[0];
+    staked.deposited_amount = amount;
+    staked.is_staked = true;
+    update_reward_pool(START, instance).unwrap();
+    instance.pools[0].total_shares += amount;
+    update_reward_debt(instance, user, 0).unwrap();
+}
+
+#[test]
+fn small_pool_with_high_rate_accrues_beyond_u64_per_share() {
+    // 每秒每份奖励 1e9 * 1e12 = 1e21，超过 u64 上限，旧实现会被截断
+    let mut instance = new_instance(1_000_000_000);
+    let mut user = new_user();
+    stake(&mut instance, &mut user, 1);
+
+    update_reward_pool(START + 1_000, &mut instance).unwrap();
+    assert!(instance.pools[0].accumulated_reward_per_share > u64::MAX as u128);
+    store_pending_reward(&mut instance, &mut user, 0).unwrap();
+    assert_eq!(
+        user.staked_info[0].accumulated_reward,
+        1_000_000_000 * 1_000
+    );
+}
+
+#[test]
+fn accumulator_overflow_is_an_error_instead_of_freezing_accrual() {
+    let mut instance = new_instance(1_000);
+    let mut user = new_user();
+    stake(&mut instance, &mut user, 1);
+    instance.pools[0].accumulated_reward_per_share = u128::MAX - 1;
+
+    let result = update_reward_pool(START + 10, &mut instance);
+    assert_eq!(result.unwrap_err(), ErrorCode::Overflow.into());
+    // 失败时不推进时间戳，交易回滚后状态保持不变
+    assert_eq!(instance.pools[0].last_reward_timestamp, START);
+}
+
+#[test]
+fn reward_debt_above_accumulated_is_an_error_instead_of_zero() {
+    let mut instance = new_instance(1_000);
+    let mut user = new_user();
+    stake(&mut instance, &mut user, 1_000);
+    user.staked_info[0].reward_debt = 1;
+
+    let result = store_pending_reward(&mut instance, &mut user, 0);
+    assert_eq!(result.unwrap_err(), ErrorCode::Underflow.into());
+}
+
+#[test]
+fn pending_reward_that_does_not_fit_u64_is_an_error() {
+    let mut instance = new_instance(1_000);
+    let mut user = new_user();
+    stake(&mut instance, &mut user, u64::MAX);
+    instance.pools[0].accumulated_reward_per_share = 2_000_000_000_000 * 1_000_000_000_000;
+
+    let result = store_pending_reward(&mut instance, &mut user, 0);
+    assert_eq!(result.unwrap_err(), ErrorCode::Overflow.into());
+}
+
+#[test]
+fn redistribution_overflow_is_an_error() {
+    let mut pool = new_pool(0, 0);
+    pool.total_shares = 1;
+    pool.accumulated_reward_per_share = u128::MAX;
+    assert_eq!(
+        redistribute_to_pool(&mut pool, 1).unwrap_err(),
+        ErrorCode::Overflow.into()
+    );
+}
@@ -0,0 +1,174 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::{new_user, START};
+use gdtc_stake::structures::{RewardStream, StakingInstance, User};
+use gdtc_stake::{
+    check_stream_slot_reusable, deactivate_stream, forfeit_stream_rewards, record_early_unstake,
+    record_stake, store_pending_reward, take_stream_reward, update_reward_pool, ErrorCode,
+};
+
+fn stream(reward_token_per_sec: u64) -> RewardStream {
+    RewardStream {
+        reward_token_mint: Pubkey::new_unique(),
+        reward_vault: Pubkey::new_unique(),
+        reward_token_per_sec,
+        accumulated_reward_per_share: 0,
+    }
+}
+
+// 0 号池挂两条合作方奖励流
+fn new_instance() -> StakingInstance {
+    let mut instance = common::new_instance(1_000);
+    instance.pools[0].reward_streams[0] = stream(30);
+    instance.pools[0].reward_streams[1] = stream(7);
+    instance
+}
+
+// 与 claim_stream_rewards / claim_all_stream_rewards 相同的结算顺序
+fn settle(instance: &mut StakingInstance, user: &mut User, now: u64) {
+    update_reward_pool(now, instance).unwrap();
+    store_pending_reward(instance, user, 0).unwrap();
+}
+
+#[test]
+fn streams_accrue_pro_rata_alongside_the_main_reward() {
+    let mut instance = new_instance();
+    let mut first = new_user();
+    let mut second = new_user();
+    record_stake(&mut instance, &mut first, 0, 0, 1_000_000, START).unwrap();
+    record_stake(&mut instance, &mut second, 0, 0, 3_000_000, START + 100).unwrap();
+
+    settle(&mut instance, &mut first, START + 200);
+    settle(&mut instance, &mut second, START + 200);
+
+    // 前 100 秒独享，后 100 秒按 1:3 分
+    assert_eq!(
+        first.staked_info[0].stream_accumulated_reward[..2],
+        [30 * 100 + 30 * 100 / 4, 7 * 100 + 7 * 100 / 4]
+    );
+    assert_eq!(
+        second.staked_info[0].stream_accumulated_reward[..2],
+        [30 * 100 * 3 / 4, 7 * 100 * 3 / 4]
+    );
+    assert_eq!(first.staked_info[0].accumulated_reward, 125_000);
+    assert_eq!(second.staked_info[0].accumulated_reward, 75_000);
+
+    // 未启用的槽位不会累计
+    assert!(first.staked_info[0].stream_accumulated_reward[2..]
+        .iter()
+        .all(|reward| *reward == 0));
+}
+
+#[test]
+fn claiming_one_stream_leaves_the_others_untouched() {
+    let mut instance = new_instance();
+    let mut user = new_user();
+    record_stake(&mut instance, &mut user, 0, 0, 1_000_000, START).unwrap();
+    settle(&mut instance, &mut user, START + 100);
+
+    assert_eq!(
+        take_stream_reward(&mut user.staked_info[0], 1).unwrap(),
+        700
+    );
+    assert_eq!(
+        user.staked_info[0].stream_accumulated_reward[..2],
+        [3_000, 0]
+    );
+    // 已领取的奖励流不能重复领取
+    assert_eq!(
+        take_stream_reward(&mut user.staked_info[0], 1),
+        Err(ErrorCode::NoRewardsToClaim.into())
+    );
+
+    // 之后只累计新产生的部分
+    settle(&mut instance, &mut user, START + 150);
+    assert_eq!(
+        user.staked_info[0].stream_accumulated_reward[..2],
+        [4_500, 350]
+    );
+}
+
+#[test]
+fn claiming_all_streams_empties_every_slot() {
+    let mut instance = new_instance();
+    let mut user = new_user();
+    record_stake(&mut instance, &mut user, 0, 0, 1_000_000, START).unwrap();
+    settle(&mut instance, &mut user, START + 100);
+
+    let active = instance.pools[0]
+        .reward_streams
+        .iter()
+        .enumerate()
+        .filter(|(_, stream)| stream.is_active())
+        .map(|(stream_index, _)| stream_index);
+    let claimed: Vec<u64> = active
+        .map(|stream_index| take_stream_reward(&mut user.staked_info[0], stream_index).unwrap())
+        .collect();
+    assert_eq!(claimed, [3_000, 700]);
+    assert_eq!(forfeit_stream_rewards(&mut user.staked_info[0]), [0, 0]);
+}
+
+#[test]
+fn exiting_forfeits_unclaimed_stream_rewards() {
+    let mut instance = new_instance();
+    let mut user = new_user();
+    record_stake(&mut instance, &mut user, 0, 0, 1_000_000, START).unwrap();
+
+    // 合作方金库不足无法领取时，解押放弃奖励流的奖励而不是卡住本金
+    settle(&mut instance, &mut user, START + 100);
+    take_stream_reward(&mut user.staked_info[0], 0).unwrap();
+    assert_eq!(forfeit_stream_rewards(&mut user.staked_info[0]), [0, 700]);
+    assert_eq!(user.staked_info[0].stream_accumulated_reward, [0, 0]);
+}
+
+#[test]
+fn early_unstake_succeeds_with_unclaimed_stream_rewards() {
+    let mut instance = new_instance();
+    instance.pools[0].early_unstake_enabled = true;
+    let mut user = new_user();
+    record_stake(&mut instance, &mut user, 0, 0, 1_000_000, START).unwrap();
+
+    let settlement =
+        record_early_unstake(&mut instance, &mut user, &new_user(), 0, START + 100).unwrap();
+    assert_eq!(settlement.forfeited_stream_rewards, [3_000, 700]);
+    assert_eq!(settlement.lp_returned, 1_000_000);
+    assert!(!user.staked_info[0].is_staked);
+    assert_eq!(instance.pools[0].total_shares, 0);
+}
+
+#[test]
+fn deactivated_stream_stops_accruing_and_retires_its_slot() {
+    let mut instance = new_instance();
+    let mut user = new_user();
+    record_stake(&mut instance, &mut user, 0, 0, 1_000_000, START).unwrap();
+
+    // 停用前先结算到当前时间
+    deactivate_stream(&mut instance, 0, 1, START + 100).unwrap();
+    let stream = &instance.pools[0].reward_streams[1];
+    assert!(!stream.is_active());
+    assert_eq!(stream.accumulated_reward_per_share, 700 * 1_000_000);
+
+    // 停用后不再累计，其他奖励流不受影响
+    settle(&mut instance, &mut user, START + 200);
+    assert_eq!(user.staked_info[0].stream_accumulated_reward, [6_000, 0]);
+    assert_eq!(
+        instance.pools[0].reward_streams[1].accumulated_reward_per_share,
+        700 * 1_000_000
+    );
+
+    // 旧质押者的奖励债务仍按保留的累计值计算，槽位不能再挂载新的奖励流
+    assert_eq!(
+        check_stream_slot_reusable(&instance.pools[0].reward_streams[1]),
+        Err(ErrorCode::RewardStreamRetired.into())
+    );
+    assert!(check_stream_slot_reusable(&instance.pools[1].reward_streams[0]).is_ok());
+    assert_eq!(
+        deactivate_stream(&mut instance, 0, 1, START + 300),
+        Err(ErrorCode::RewardStreamNotActive.into())
+    );
+    assert_eq!(
+        deactivate_stream(&mut instance, 0, 2, START + 300),
+        Err(ErrorCode::InvalidRewardStreamIndex.into())
+    );
+}
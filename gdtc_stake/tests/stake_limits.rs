@@ -0,0 +1,72 @@
+mod common;
+
+use common::{empty_staked, new_user};
+use gdtc_stake::structures::{StakeLimits, Staked};
+use gdtc_stake::{check_stake_limits, user_pool_deposits, ErrorCode};
+
+const LIMITS: StakeLimits = StakeLimits {
+    min_stake: 1_000,
+    max_stake_per_user: 10_000,
+    pool_capacity: 50_000,
+};
+
+fn staked(stake_type: u64, deposited_amount: u64, is_staked: bool) -> Staked {
+    Staked {
+        deposited_amount,
+        is_staked,
+        stake_type,
+        ..empty_staked()
+    }
+}
+
+#[test]
+fn zero_stake_is_rejected_even_without_limits() {
+    assert_eq!(
+        check_stake_limits(&StakeLimits::default(), 0, 0, 0).unwrap_err(),
+        ErrorCode::ZeroStakeAmount.into()
+    );
+    assert!(check_stake_limits(&StakeLimits::default(), u64::MAX - 1, u64::MAX - 1, 1).is_ok());
+}
+
+#[test]
+fn each_limit_has_its_own_error() {
+    assert!(check_stake_limits(&LIMITS, 0, 0, 1_000).is_ok());
+    assert_eq!(
+        check_stake_limits(&LIMITS, 0, 0, 999).unwrap_err(),
+        ErrorCode::StakeBelowMinimum.into()
+    );
+    assert_eq!(
+        check_stake_limits(&LIMITS, 9_000, 9_000, 1_001).unwrap_err(),
+        ErrorCode::UserStakeCapExceeded.into()
+    );
+    assert_eq!(
+        check_stake_limits(&LIMITS, 49_500, 0, 1_000).unwrap_err(),
+        ErrorCode::PoolCapacityExceeded.into()
+    );
+}
+
+#[test]
+fn limits_are_inclusive() {
+    assert!(check_stake_limits(&LIMITS, 40_000, 9_000, 1_000).is_ok());
+    assert!(check_stake_limits(&LIMITS, 49_000, 0, 1_000).is_ok());
+}
+
+#[test]
+fn only_active_stakes_in_the_same_pool_count_towards_the_user_cap() {
+    let mut user = new_user();
+    user.staked_info[0] = staked(1, 4_000, true);
+    user.staked_info[3] = staked(1, 5_000, true);
+    // 已解押的槽位和其他池的质押不计入
+    user.staked_info[5] = staked(1, 7_000, false);
+    user.staked_info[9] = staked(2, 8_000, true);
+
+    assert_eq!(user_pool_deposits(&user, 1), 9_000);
+    assert_eq!(user_pool_deposits(&user, 2), 8_000);
+    assert_eq!(user_pool_deposits(&user, 0), 0);
+
+    assert!(check_stake_limits(&LIMITS, 9_000, user_pool_deposits(&user, 1), 1_000).is_ok());
+    assert_eq!(
+        check_stake_limits(&LIMITS, 9_000, user_pool_deposits(&user, 1), 1_001).unwrap_err(),
+        ErrorCode::UserStakeCapExceeded.into()
+    );
+}
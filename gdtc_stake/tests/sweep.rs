@@ -0,0 +1,200 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator, Event};
+use common::{new_user, START};
+use gdtc_stake::events::Swept;
+use gdtc_stake::structures::{RewardStream, StakingInstance};
+use gdtc_stake::{
+    clear_reward_liability, record_early_unstake, record_stake, release_reward_liability,
+    reward_liability_bound, store_pending_reward, sweepable_amount, update_reward_pool,
+    with_referral_allowance, ErrorCode,
+};
+
+fn new_instance() -> StakingInstance {
+    common::new_instance(1_000)
+}
+
+#[test]
+fn lp_principal_backing_total_shares_is_never_sweepable() {
+    let mut instance = new_instance();
+    instance.pools[0].total_shares = 3_000;
+    instance.pools[2].total_shares = 2_000;
+    let lp_vault = instance.lp_token_account;
+    let lp_mint = instance.staking_token_mint;
+
+    // 误转入的 500 可以取回，本金不行
+    assert_eq!(
+        sweepable_amount(&instance, &lp_vault, &lp_mint, 5_500).unwrap(),
+        500
+    );
+    assert_eq!(
+        sweepable_amount(&instance, &lp_vault, &lp_mint, 4_000).unwrap(),
+        0
+    );
+    // PDA 名下其他持有质押代币的账户同样保留本金
+    assert_eq!(
+        sweepable_amount(&instance, &Pubkey::new_unique(), &lp_mint, 5_500).unwrap(),
+        500
+    );
+}
+
+#[test]
+fn rewards_owed_to_users_are_never_sweepable() {
+    let mut instance = new_instance();
+    instance.reward_liability = 11_000;
+    let reward_mint = instance.reward_token_mint;
+    let vault = Pubkey::new_unique();
+
+    assert_eq!(
+        sweepable_amount(&instance, &vault, &reward_mint, 10_000).unwrap(),
+        0
+    );
+    assert_eq!(
+        sweepable_amount(&instance, &vault, &reward_mint, 12_345).unwrap(),
+        1_345
+    );
+}
+
+#[test]
+fn unrelated_mints_are_fully_sweepable() {
+    let mut instance = new_instance();
+    instance.pools[0].total_shares = 3_000;
+    instance.reward_liability = 11_000;
+    assert_eq!(
+        sweepable_amount(&instance, &Pubkey::new_unique(), &Pubkey::new_unique(), 777).unwrap(),
+        777
+    );
+}
+
+#[test]
+fn active_reward_stream_vaults_are_refused() {
+    let mut instance = new_instance();
+    let stream = RewardStream {
+        reward_token_mint: Pubkey::new_unique(),
+        reward_vault: Pubkey::new_unique(),
+        reward_token_per_sec: 5,
+        accumulated_reward_per_share: 0,
+    };
+    instance.pools[1].reward_streams[0] = stream.clone();
+    assert_eq!(
+        sweepable_amount(
+            &instance,
+            &stream.reward_vault,
+            &stream.reward_token_mint,
+            100
+        )
+        .unwrap_err(),
+        ErrorCode::RewardStreamVaultNotSweepable.into()
+    );
+}
+
+#[test]
+fn liability_tracks_emissions_and_payouts() {
+    let mut instance = new_instance();
+    let mut user = new_user();
+    record_stake(&mut instance, &mut user, 0, 0, 1_000_000, START).unwrap();
+    assert_eq!(instance.reward_liability, 0);
+
+    // 100 秒排放 100_000，另留 10% 推荐奖励
+    update_reward_pool(START + 100, &mut instance).unwrap();
+    assert_eq!(instance.reward_liability, 110_000);
+    // 再过 100 秒同样计入
+    update_reward_pool(START + 200, &mut instance).unwrap();
+    assert_eq!(instance.reward_liability, 220_000);
+
+    store_pending_reward(&mut instance, &mut user, 0).unwrap();
+    let owed = user.staked_info[0].accumulated_reward;
+    assert!(with_referral_allowance(owed) <= instance.reward_liability);
+
+    // 领取后只剩取整误差
+    release_reward_liability(&mut instance, owed);
+    assert_eq!(
+        instance.reward_liability,
+        220_000 - with_referral_allowance(owed)
+    );
+    assert!(instance.reward_liability <= 2);
+    release_reward_liability(&mut instance, u64::MAX);
+    assert_eq!(instance.reward_liability, 0);
+}
+
+#[test]
+fn migrated_instances_reserve_an_upper_bound() {
+    let mut instance = new_instance();
+    let mut first = new_user();
+    let mut second = new_user();
+    record_stake(&mut instance, &mut first, 0, 0, 400_000, START).unwrap();
+    record_stake(&mut instance, &mut second, 0, 0, 600_000, START + 50).unwrap();
+    update_reward_pool(START + 150, &mut instance).unwrap();
+
+    // 实际欠款：两人待领取的奖励
+    store_pending_reward(&mut instance, &mut first, 0).unwrap();
+    store_pending_reward(&mut instance, &mut second, 0).unwrap();
+    let owed = first.staked_info[0].accumulated_reward + second.staked_info[0].accumulated_reward;
+
+    let bound = reward_liability_bound(&instance).unwrap();
+    assert!(bound >= with_referral_allowance(owed));
+    assert!(bound <= with_referral_allowance(owed) + instance.reward_liability);
+}
+
+#[test]
+fn migrated_liability_is_reconciled_to_zero_after_every_user_exits() {
+    let mut instance = new_instance();
+    instance.pools[0].early_unstake_enabled = true;
+    let reward_mint = instance.reward_token_mint;
+    let vault = Pubkey::new_unique();
+    let mut first = new_user();
+    let mut second = new_user();
+    let superior = new_user();
+    record_stake(&mut instance, &mut first, 0, 0, 400_000, START).unwrap();
+    record_stake(&mut instance, &mut second, 0, 0, 600_000, START + 50).unwrap();
+
+    // 迁移时按上界预留
+    update_reward_pool(START + 150, &mut instance).unwrap();
+    instance.reward_liability = reward_liability_bound(&instance).unwrap();
+
+    // 一人离场后另一人仍在质押，不能对账
+    let paid = record_early_unstake(&mut instance, &mut first, &superior, 0, START + 200)
+        .unwrap()
+        .reward_payout;
+    assert!(paid > 0);
+    assert_eq!(
+        clear_reward_liability(&mut instance),
+        Err(ErrorCode::ActiveStakesRemain.into())
+    );
+
+    // 全部离场后上界的多余部分仍留在未支付总额中，导致无法取回
+    record_early_unstake(&mut instance, &mut second, &superior, 0, START + 300).unwrap();
+    assert!(instance.reward_liability > 0);
+    let balance = 1_000_000;
+    assert!(sweepable_amount(&instance, &vault, &reward_mint, balance).unwrap() < balance);
+
+    clear_reward_liability(&mut instance).unwrap();
+    assert_eq!(instance.reward_liability, 0);
+    assert_eq!(
+        sweepable_amount(&instance, &vault, &reward_mint, balance).unwrap(),
+        balance
+    );
+
+    // 之后重新质押照常计入
+    record_stake(&mut instance, &mut first, 0, 0, 1_000_000, START + 400).unwrap();
+    update_reward_pool(START + 500, &mut instance).unwrap();
+    assert_eq!(instance.reward_liability, 110_000);
+}
+
+#[test]
+fn swept_event_round_trips() {
+    let event = Swept {
+        staking_instance: Pubkey::new_unique(),
+        authority: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        source: Pubkey::new_unique(),
+        destination: Pubkey::new_unique(),
+        amount: 42,
+    };
+    let data = event.data();
+    assert_eq!(&data[..8], Swept::DISCRIMINATOR);
+    let decoded = Swept::deserialize(&mut &data[8..]).unwrap();
+    assert_eq!(decoded.destination, event.destination);
+    assert_eq!(decoded.amount, 42);
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use common::{empty_staked, new_user};
+use gdtc_stake::constants::VOTE_MAX_LOCK_SECS;
+use gdtc_stake::structures::Staked;
+use gdtc_stake::{staked_voting_power, user_voting_power};
+use proptest::prelude::*;
+
+const NOW: u64 = 1_700_000_000;
+const SNAPSHOT_SLOT: u64 = 250_000_000;
+
+fn staked(deposited_amount: u64, stake_end_time: u64) -> Staked {
+    Staked {
+        deposited_amount,
+        is_staked: deposited_amount > 0,
+        stake_type: 2,
+        stake_start_time: NOW - 1,
+        stake_end_time,
+        ..empty_staked()
+    }
+}
+
+#[test]
+fn power_is_weighted_by_remaining_lock_time() {
+    let amount = 1_000_000;
+    // 剩余一年及以上为满权重
+    assert_eq!(
+        staked_voting_power(&staked(amount, NOW + VOTE_MAX_LOCK_SECS), NOW),
+        amount
+    );
+    assert_eq!(
+        staked_voting_power(&staked(amount, NOW + 2 * VOTE_MAX_LOCK_SECS), NOW),
+        amount
+    );
+    assert_eq!(
+        staked_voting_power(&staked(amount, NOW + VOTE_MAX_LOCK_SECS / 2), NOW),
+        amount / 2
+    );
+    assert_eq!(
+        staked_voting_power(&staked(amount, NOW + VOTE_MAX_LOCK_SECS / 4), NOW),
+        amount / 4
+    );
+
+    // 到期及到期之后为 0，已解押的槽位为 0
+    assert_eq!(staked_voting_power(&staked(amount, NOW), NOW), 0);
+    assert_eq!(staked_voting_power(&staked(amount, NOW - 1), NOW), 0);
+    let mut unstaked = staked(amount, NOW + VOTE_MAX_LOCK_SECS);
+    unstaked.is_staked = false;
+    assert_eq!(staked_voting_power(&unstaked, NOW), 0);
+}
+
+#[test]
+fn stakes_entered_after_the_snapshot_do_not_count() {
+    let mut user = new_user();
+    user.staked_info[0] = staked(1_000, NOW + VOTE_MAX_LOCK_SECS);
+    user.stake_slots[0] = SNAPSHOT_SLOT - 10;
+    // slot 为 0：version 3 之前的质押，早于任何快照
+    user.staked_info[4] = staked(2_000, NOW + VOTE_MAX_LOCK_SECS);
+    user.stake_slots[4] = 0;
+    // 快照所在的 slot 计入，之后的不计入
+    user.staked_info[7] = staked(4_000, NOW + VOTE_MAX_LOCK_SECS);
+    user.stake_slots[7] = SNAPSHOT_SLOT;
+    user.staked_info[9] = staked(8_000, NOW + VOTE_MAX_LOCK_SECS);
+    user.stake_slots[9] = SNAPSHOT_SLOT + 1;
+
+    assert_eq!(user_voting_power(&user, SNAPSHOT_SLOT, NOW).unwrap(), 7_000);
+    assert_eq!(
+        user_voting_power(&user, SNAPSHOT_SLOT + 1, NOW).unwrap(),
+        15_000
+    );
+    assert_eq!(user_voting_power(&user, 0, NOW).unwrap(), 2_000);
+}
+
+proptest! {
+    // 投票权不超过质押数量，并随时间线性衰减，不会增加
+    #[test]
+    fn power_decays_linearly_to_the_end_time(
+        amount in 1..u64::MAX,
+        remaining in 0..2 * VOTE_MAX_LOCK_SECS,
+        elapsed in 0..2 * VOTE_MAX_LOCK_SECS,
+    ) {
+        let position = staked(amount, NOW + remaining);
+        let now = staked_voting_power(&position, NOW);
+        let later = staked_voting_power(&position, NOW + elapsed);
+        prop_assert!(now <= amount);
+        prop_assert!(later <= now);
+
+        let expected = amount as u128 * remaining.min(VOTE_MAX_LOCK_SECS) as u128
+            / VOTE_MAX_LOCK_SECS as u128;
+        prop_assert_eq!(now as u128, expected);
+    }
+}